@@ -0,0 +1,283 @@
+//! Timing of main-phase adjudication.
+//!
+//! Run with `cargo bench --bench resolver`. This uses only the standard library so it runs on
+//! stable Rust; each case reports the mean time per adjudication in the fastest of several
+//! samples, which is less sensitive to other load on the machine than a single long run.
+//!
+//! To compare two versions of the resolver, save a baseline from the first with
+//! `cargo bench --bench resolver -- --save-baseline <name>`, then run the second with
+//! `cargo bench --bench resolver -- --baseline <name>` to print the change for each case.
+
+use diplomacy::geo::{standard_map, Map};
+use diplomacy::judge::{MappedMainOrder, OrderState, ParadoxRule, Rulebook, Submission};
+use std::collections::HashMap;
+use std::fs;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const SAMPLES: u32 = 20;
+
+/// A contested 34-unit board: supports cut each other all along the front, two supported
+/// attacks meet at Denmark, and a convoy to Tunis is attacked.
+const CROWDED_BOARD: &[&str] = &[
+    "AUS: A vie -> gal",
+    "AUS: A bud Supports A vie -> gal",
+    "AUS: A ser -> rum",
+    "AUS: A bul Supports A ser -> rum",
+    "AUS: F alb -> ion",
+    "AUS: F gre Supports F alb -> ion",
+    "AUS: A tri -> tyr",
+    "RUS: A war -> gal",
+    "RUS: A ukr Supports A war -> gal",
+    "RUS: A rum Supports A war -> gal",
+    "RUS: F sev -> bla",
+    "RUS: F swe -> den",
+    "RUS: F bal Supports F swe -> den",
+    "TUR: F bla -> rum",
+    "TUR: A arm -> sev",
+    "TUR: F ank Supports F bla",
+    "TUR: A smy -> con",
+    "GER: A mun -> boh",
+    "GER: A sil Supports A mun -> boh",
+    "GER: F den Holds",
+    "GER: F kie Supports F den",
+    "GER: F hel Supports F den",
+    "ENG: F nth -> den",
+    "ENG: F ska Supports F nth -> den",
+    "ENG: F edi -> nth",
+    "ENG: F lon -> eng",
+    "FRA: F eng -> bel",
+    "FRA: F mao -> eng",
+    "FRA: A pic Supports F eng -> bel",
+    "FRA: A bur -> mun",
+    "ITA: A ven -> tyr",
+    "ITA: A apu -> tun",
+    "ITA: F ion convoys apu -> tun",
+    "ITA: F tys Supports F ion",
+];
+
+/// Armies from Brest to Norway, each moving into the province the next one leaves. Each move
+/// depends on the one ahead of it.
+const MOVE_CHAIN: &[&str] = &[
+    "FRA: A bre -> par",
+    "FRA: A par -> bur",
+    "FRA: A bur -> mun",
+    "FRA: A mun -> boh",
+    "FRA: A boh -> vie",
+    "FRA: A vie -> bud",
+    "FRA: A bud -> rum",
+    "FRA: A rum -> ukr",
+    "FRA: A ukr -> mos",
+    "FRA: A mos -> stp",
+    "FRA: A stp -> fin",
+    "FRA: A fin -> swe",
+    "FRA: A swe -> nwy",
+];
+
+/// Four armies rotate through the Low Countries while every other unit supports one of them.
+const CIRCULAR_MOVEMENT: &[&str] = &[
+    "FRA: A bel -> hol",
+    "FRA: A hol -> ruh",
+    "FRA: A ruh -> bur",
+    "FRA: A bur -> bel",
+    "FRA: F nth Supports A bel -> hol",
+    "FRA: A kie Supports A hol -> ruh",
+    "FRA: A mun Supports A ruh -> bur",
+    "FRA: A pic Supports A bur -> bel",
+    "GER: A par -> bur",
+    "GER: F eng -> bel",
+    "GER: A hel Supports F eng -> bel",
+];
+
+/// DATC 6.F.24: a second-order paradox that no convoy or support can settle.
+const PARADOX: &[&str] = &[
+    "ENG: F edi -> nth",
+    "ENG: F lon Supports F edi -> nth",
+    "ENG: F iri -> eng",
+    "ENG: F mao Supports F iri -> eng",
+    "FRA: A bre -> lon",
+    "FRA: F eng convoys bre -> lon",
+    "FRA: F bel Supports F eng",
+    "RUS: A nwy -> bel",
+    "RUS: F nth convoys nwy -> bel",
+];
+
+/// A board of `rows` by `cols` land provinces, each bordering its neighbors along rows and
+/// columns, with orders for every province except the last in each row. Armies in even rows
+/// move one province along their row; armies in odd rows support the move above and ahead.
+fn grid_board(rows: usize, cols: usize) -> (Map, Vec<String>) {
+    let name = |row: usize, col: usize| {
+        let index = row * cols + col;
+        [index / 676, index / 26 % 26, index % 26]
+            .iter()
+            .map(|&letter| char::from(b'a' + letter as u8))
+            .collect::<String>()
+    };
+
+    let mut provinces = String::new();
+    let mut regions = String::new();
+    let mut borders = String::new();
+    let mut orders = vec![];
+    for row in 0..rows {
+        for col in 0..cols {
+            provinces += &format!("{},Province {},\n", name(row, col), row * cols + col);
+            regions += &format!("{},,land\n", name(row, col));
+            if col + 1 < cols {
+                borders += &format!("{},{},land\n", name(row, col), name(row, col + 1));
+            }
+            if row + 1 < rows {
+                borders += &format!("{},{},land\n", name(row, col), name(row + 1, col));
+            }
+        }
+
+        for col in 0..cols - 1 {
+            orders.push(if row % 2 == 0 {
+                format!("FRA: A {} -> {}", name(row, col), name(row, col + 1))
+            } else {
+                let target = (col + 2).min(cols - 1);
+                format!(
+                    "GER: A {} Supports A {} -> {}",
+                    name(row, col),
+                    name(row - 1, col + 1),
+                    name(row - 1, target)
+                )
+            });
+        }
+    }
+
+    let document = format!(
+        "[provinces]\n{}[regions]\n{}[borders]\n{}",
+        provinces, regions, borders
+    );
+    (Map::from_reader(document.as_bytes()).unwrap(), orders)
+}
+
+/// Adjudicate `orders` repeatedly in each of `SAMPLES` samples, and return the mean time per
+/// adjudication of the fastest sample.
+fn time(map: &Map, rules: &Rulebook, orders: &[impl AsRef<str>]) -> Duration {
+    let submission = Submission::with_inferred_state(
+        orders
+            .iter()
+            .map(|ord| ord.as_ref().parse::<MappedMainOrder>().unwrap())
+            .collect(),
+    );
+
+    // Aim for samples of roughly the same length regardless of board size.
+    let runs = (20_000 / orders.len() as u32).max(5);
+    let mut fastest = Duration::MAX;
+
+    for _ in 0..SAMPLES {
+        let start = Instant::now();
+        for _ in 0..runs {
+            let outcome = submission.adjudicate(map, rules.clone());
+            let states: HashMap<_, OrderState> = black_box(outcome).into();
+            black_box(states);
+        }
+
+        fastest = fastest.min(start.elapsed() / runs);
+    }
+
+    fastest
+}
+
+/// Where the named baseline is stored.
+fn baseline_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("resolver-{}.baseline", name))
+}
+
+/// Read a saved baseline as the time in nanoseconds for each case.
+fn read_baseline(name: &str) -> HashMap<String, u128> {
+    let path = baseline_path(name);
+    let contents = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Unable to read baseline {}: {}", path.display(), e));
+    contents
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(case, nanos)| (case.to_string(), nanos.parse().unwrap()))
+        .collect()
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1).filter(|arg| arg != "--bench");
+    let mut save = None;
+    let mut baseline = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--save-baseline" => save = args.next(),
+            "--baseline" => baseline = args.next().map(|name| read_baseline(&name)),
+            other => panic!("Unknown argument {}", other),
+        }
+    }
+
+    let small_grid = grid_board(8, 26);
+    let large_grid = grid_board(16, 26);
+    let standard = standard_map();
+    let default_rules = Rulebook::default();
+    let cases: Vec<(&str, Duration, usize)> = vec![
+        (
+            "crowded_board",
+            time(standard, &default_rules, CROWDED_BOARD),
+            CROWDED_BOARD.len(),
+        ),
+        (
+            "move_chain",
+            time(standard, &default_rules, MOVE_CHAIN),
+            MOVE_CHAIN.len(),
+        ),
+        (
+            "circular_movement",
+            time(standard, &default_rules, CIRCULAR_MOVEMENT),
+            CIRCULAR_MOVEMENT.len(),
+        ),
+        (
+            "paradox_szykman",
+            time(standard, &default_rules, PARADOX),
+            PARADOX.len(),
+        ),
+        (
+            "paradox_dptg",
+            time(
+                standard,
+                &Rulebook::with_paradox_rule(ParadoxRule::Dptg),
+                PARADOX,
+            ),
+            PARADOX.len(),
+        ),
+        (
+            "grid_200",
+            time(&small_grid.0, &default_rules, &small_grid.1),
+            small_grid.1.len(),
+        ),
+        (
+            "grid_400",
+            time(&large_grid.0, &default_rules, &large_grid.1),
+            large_grid.1.len(),
+        ),
+    ];
+
+    let mut saved = String::new();
+    for (name, elapsed, orders) in cases {
+        saved += &format!("{} {}\n", name, elapsed.as_nanos());
+        let change = baseline
+            .as_ref()
+            .and_then(|baseline| baseline.get(name))
+            .map(|&before| {
+                let change = (elapsed.as_nanos() as f64 / before as f64 - 1.0) * 100.0;
+                format!("  was {:>9.1}µs ({:+.1}%)", before as f64 / 1000.0, change)
+            })
+            .unwrap_or_default();
+
+        println!(
+            "{:<20} {:>4} orders {:>9.1}µs per adjudication{}",
+            name,
+            orders,
+            elapsed.as_secs_f64() * 1e6,
+            change
+        );
+    }
+
+    if let Some(name) = save {
+        fs::write(baseline_path(&name), saved).unwrap();
+    }
+}
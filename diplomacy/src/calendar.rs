@@ -27,6 +27,7 @@ pub enum Error {
 /// Some turns neither need nor accept orders, such as a retreat phase after a main phase in which
 /// no units are dislodged. In these scenarios, the caller should still advance its turn counter to
 /// indicate the completion of that turn.
+#[derive(Debug, Clone)]
 pub struct Calendar {
     starting_year: usize,
     starting_month_index: usize,
@@ -78,7 +79,7 @@ impl Calendar {
     }
 
     /// Create an infinite iterator that goes through all past and future `Time` values in the game.
-    pub fn iter(&self) -> Iter {
+    pub fn iter(&self) -> Iter<'_> {
         Iter {
             calendar: self,
             step: 0,
@@ -0,0 +1,440 @@
+//! Types for playing a game from one phase to the next.
+//!
+//! A `Game` holds everything needed to adjudicate the current phase: the map, the rules, the
+//! calendar, unit positions and supply center ownership. Calling `Game::advance` with the
+//! orders for the current phase adjudicates it and returns the game at the next phase that
+//! needs orders.
+//!
+//! A game can be stored as a `Snapshot`, which is serializable when the `serde` feature is
+//! enabled, and resumed later with `Game::resume`.
+
+use crate::calendar::Calendar;
+use crate::geo::{variant::Variant, Map, ProvinceKey, RegionKey, SupplyCenter};
+use crate::judge::build::{self, WorldState};
+use crate::judge::{
+    retreat, Adjudicate, MappedBuildOrder, MappedMainOrder, MappedRetreatOrder, Rulebook,
+    Submission,
+};
+use crate::scoring::Standings;
+use crate::{
+    Nation, Phase, ShortName, SupplyCenterOwnership, Time, Unit, UnitPosition, UnitPositions,
+    UnitType,
+};
+use from_variants::FromVariants;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::{error, fmt};
+
+/// The orders issued for one phase of the game.
+#[derive(Debug, Clone, PartialEq, Eq, FromVariants)]
+pub enum Orders {
+    Main(Vec<MappedMainOrder>),
+    Retreat(Vec<MappedRetreatOrder>),
+    Build(Vec<MappedBuildOrder>),
+}
+
+impl Orders {
+    /// The phase in which the orders can be issued.
+    pub fn phase(&self) -> Phase {
+        match self {
+            Orders::Main(_) => Phase::Main,
+            Orders::Retreat(_) => Phase::Retreat,
+            Orders::Build(_) => Phase::Build,
+        }
+    }
+}
+
+/// The error type for advancing a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The orders were for a different phase than the current phase of the game.
+    WrongPhase { expected: Phase, received: Phase },
+    /// A snapshot was taken at a time that isn't in the calendar.
+    TimeNotInCalendar(Time),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::WrongPhase { expected, received } => write!(
+                f,
+                "Expected orders for the {:?} phase, received {:?} orders",
+                expected, received
+            ),
+            Error::TimeNotInCalendar(time) => {
+                write!(f, "Time not in calendar: {}", time.short_name())
+            }
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// A game in progress at a specific phase.
+///
+/// # Phases Without Orders
+/// `Game::advance` skips retreat phases in which no dislodged unit has anywhere to go, and build
+/// phases in which no nation can build or needs to disband. Dislodged units that can't retreat
+/// are disbanded, and the skipped phase is not returned.
+///
+/// # Supply Centers
+/// Supply center ownership is updated when the game enters a build phase: each supply center
+/// with a unit in it belongs to that unit's nation, and vacant supply centers keep their owner.
+#[derive(Debug, Clone)]
+pub struct Game<'a, A = Rulebook> {
+    map: &'a Map,
+    rules: A,
+    calendar: Calendar,
+    time: Time,
+    units: Vec<UnitPosition<'static, RegionKey>>,
+    ownerships: SupplyCenterOwnership,
+    /// During a retreat phase, the main phase that dislodged units and the retreat phase
+    /// starting data it produced. The main phase is only adjudicated again when a game is
+    /// resumed from a snapshot.
+    main_phase: Option<(MainPhase, retreat::OwnedStart)>,
+}
+
+/// A main phase in which units were dislodged, which is needed to adjudicate the retreat phase
+/// that follows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MainPhase {
+    /// The units on the board at the start of the main phase.
+    pub units: Vec<UnitPosition<'static, RegionKey>>,
+    /// The orders submitted for the main phase.
+    pub orders: Vec<MappedMainOrder>,
+}
+
+/// The state of a game at a specific phase, which can be stored and used to resume the game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    pub time: Time,
+    pub units: Vec<UnitPosition<'static, RegionKey>>,
+    pub ownerships: SupplyCenterOwnership,
+    /// During a retreat phase, the main phase that dislodged units.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub main_phase: Option<MainPhase>,
+}
+
+impl Game<'static> {
+    /// Start a game of a built-in variant using the default rules.
+    pub fn from_variant(variant: &'static Variant) -> Self {
+        Game::new(
+            variant.map(),
+            variant.calendar(),
+            variant.starting_units().to_vec(),
+            Rulebook::default(),
+        )
+    }
+}
+
+impl<'a, A: Adjudicate + Clone> Game<'a, A> {
+    /// Start a game at the first phase of `calendar`, with each nation owning its home supply
+    /// centers.
+    pub fn new(
+        map: &'a Map,
+        calendar: Calendar,
+        units: Vec<UnitPosition<'static, RegionKey>>,
+        rules: A,
+    ) -> Self {
+        Self {
+            map,
+            rules,
+            time: calendar.nth(0),
+            calendar,
+            units,
+            ownerships: SupplyCenterOwnership::initial(map),
+            main_phase: None,
+        }
+    }
+
+    /// Resume a game from a snapshot taken with `Game::snapshot`.
+    pub fn resume(
+        map: &'a Map,
+        calendar: Calendar,
+        rules: A,
+        snapshot: Snapshot,
+    ) -> Result<Self, Error> {
+        if calendar.position(&snapshot.time).is_none() {
+            return Err(Error::TimeNotInCalendar(snapshot.time));
+        }
+
+        let main_phase = snapshot.main_phase.map(|main_phase| {
+            let submission = Submission::new(&main_phase.units, main_phase.orders.clone());
+            let start = submission
+                .adjudicate(map, rules.clone())
+                .to_retreat_start()
+                .into_owned();
+            (main_phase, start)
+        });
+
+        Ok(Self {
+            map,
+            rules,
+            calendar,
+            time: snapshot.time,
+            units: snapshot.units,
+            ownerships: snapshot.ownerships,
+            main_phase,
+        })
+    }
+
+    /// Take a snapshot of the current state of the game.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            time: self.time.clone(),
+            units: self.units.clone(),
+            ownerships: self.ownerships.clone(),
+            main_phase: self
+                .main_phase
+                .as_ref()
+                .map(|(main_phase, _)| main_phase.clone()),
+        }
+    }
+
+    /// The map on which the game is played.
+    pub fn map(&self) -> &'a Map {
+        self.map
+    }
+
+    /// The rules used to adjudicate main phases.
+    pub fn rules(&self) -> &A {
+        &self.rules
+    }
+
+    /// The current phase of the game.
+    pub fn time(&self) -> &Time {
+        &self.time
+    }
+
+    /// The units on the board. During a retreat phase, this does not include dislodged units.
+    pub fn units(&self) -> &[UnitPosition<'static, RegionKey>] {
+        &self.units
+    }
+
+    /// The owner of each supply center that has one.
+    pub fn ownerships(&self) -> &SupplyCenterOwnership {
+        &self.ownerships
+    }
+
+    /// The nations in the game, which are the nations with a home supply center on the map or
+    /// a unit on the board.
+    pub fn nations(&self) -> HashSet<&Nation> {
+        self.map
+            .provinces()
+            .filter_map(|province| match &province.supply_center {
+                SupplyCenter::Home(nation) => Some(nation),
+                _ => None,
+            })
+            .chain(self.units.iter().map(|unit| unit.nation()))
+            .collect()
+    }
+
+    /// The number of supply centers and units each nation has. Use this to check whether the
+    /// game is over and to score it.
+    pub fn standings(&self) -> Standings {
+        Standings::new(self.nations(), &self.ownerships, &self.units)
+    }
+
+    /// During a retreat phase, the dislodged units and the regions each of them can retreat
+    /// to. This is empty in other phases.
+    pub fn retreat_destinations(
+        &self,
+    ) -> HashMap<UnitPosition<'static, RegionKey>, BTreeSet<RegionKey>> {
+        let start = match &self.main_phase {
+            Some((_, start)) => start.to_start(),
+            None => return HashMap::new(),
+        };
+
+        start
+            .retreat_destinations()
+            .iter()
+            .map(|(unit, dests)| {
+                (
+                    unit.clone().into_owned(),
+                    dests.available().into_iter().cloned().collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Whether the current phase needs orders from any nation. Main phases always need orders.
+    pub fn needs_orders(&self) -> bool {
+        match self.time.phase() {
+            Phase::Main => true,
+            Phase::Retreat => self.main_phase.is_some(),
+            Phase::Build => self.needs_builds(),
+        }
+    }
+
+    /// Adjudicate the current phase using `orders`, and return the game at the next phase that
+    /// needs orders.
+    ///
+    /// The calendar must include a main phase, or this will never return.
+    pub fn advance(&self, orders: impl Into<Orders>) -> Result<Self, Error> {
+        let mut next = match (self.time.phase(), orders.into()) {
+            (Phase::Main, Orders::Main(orders)) => self.resolve_main(orders),
+            (Phase::Retreat, Orders::Retreat(orders)) => self.resolve_retreat(orders),
+            (Phase::Build, Orders::Build(orders)) => self.resolve_build(orders),
+            (expected, orders) => {
+                return Err(Error::WrongPhase {
+                    expected,
+                    received: orders.phase(),
+                })
+            }
+        };
+
+        while !next.needs_orders() {
+            next = match next.time.phase() {
+                Phase::Main => unreachable!("main phases always need orders"),
+                Phase::Retreat => next.resolve_retreat(vec![]),
+                Phase::Build => next.resolve_build(vec![]),
+            };
+        }
+
+        Ok(next)
+    }
+
+    fn resolve_main(&self, orders: Vec<MappedMainOrder>) -> Self {
+        let submission = Submission::new(&self.units, orders.clone());
+        let outcome = submission.adjudicate(self.map, self.rules.clone());
+        let start = outcome.to_retreat_start();
+
+        // Resolving the retreat phase without orders leaves out every dislodged unit.
+        let units = to_owned_positions(&retreat::Context::new(&start, vec![]).resolve());
+        let main_phase = if start.needs_player_input() {
+            let main_phase = MainPhase {
+                units: self.units.clone(),
+                orders,
+            };
+            Some((main_phase, start.into_owned()))
+        } else {
+            None
+        };
+
+        self.next_phase(units, main_phase)
+    }
+
+    fn resolve_retreat(&self, orders: Vec<MappedRetreatOrder>) -> Self {
+        let start = match &self.main_phase {
+            Some((_, start)) => start.to_start(),
+            None => return self.next_phase(self.units.clone(), None),
+        };
+
+        let units = to_owned_positions(&retreat::Context::new(&start, orders).resolve());
+
+        self.next_phase(units, None)
+    }
+
+    fn resolve_build(&self, orders: Vec<MappedBuildOrder>) -> Self {
+        let world = BuildWorld {
+            nations: self.nations(),
+            units: &self.units,
+        };
+
+        let last_time = HashMap::from(&self.ownerships);
+        let context =
+            build::ResolverContext::new(self.map, &last_time, &world, orders.iter().collect());
+        let units = context
+            .resolve()
+            .final_units
+            .into_iter()
+            .flat_map(|(nation, units)| {
+                units.into_iter().map(move |(unit_type, region)| {
+                    UnitPosition::new(Unit::new(Cow::Owned(nation.clone()), unit_type), region)
+                })
+            })
+            .collect();
+
+        self.next_phase(units, None)
+    }
+
+    /// Check if any nation can build or must disband in the current build phase.
+    fn needs_builds(&self) -> bool {
+        self.nations().into_iter().any(|nation| {
+            let centers = self.ownerships.center_count(nation);
+            let units = self.units.iter().filter(|u| u.nation() == nation).count();
+
+            units > centers
+                || (units < centers
+                    && self.map.provinces().any(|province| {
+                        let key = ProvinceKey::from(province);
+                        province.supply_center == SupplyCenter::Home(nation.clone())
+                            && self.ownerships.owner(&key) == Some(nation)
+                            && self.units.find_province_occupier(&key).is_none()
+                    }))
+        })
+    }
+
+    fn next_phase(
+        &self,
+        mut units: Vec<UnitPosition<'static, RegionKey>>,
+        main_phase: Option<(MainPhase, retreat::OwnedStart)>,
+    ) -> Self {
+        let turn = self
+            .calendar
+            .position(&self.time)
+            .expect("current time should be in the calendar");
+        let time = self.calendar.nth(turn + 1);
+
+        units.sort_by(|a, b| a.region.cmp(&b.region));
+
+        let mut ownerships = self.ownerships.clone();
+        if time.phase() == Phase::Build {
+            ownerships.update(self.map, &units);
+        }
+
+        Self {
+            map: self.map,
+            rules: self.rules.clone(),
+            calendar: self.calendar.clone(),
+            main_phase: main_phase.filter(|_| time.phase() == Phase::Retreat),
+            time,
+            units,
+            ownerships,
+        }
+    }
+}
+
+/// The unit positions of a game, as needed to adjudicate a build phase.
+struct BuildWorld<'a> {
+    nations: HashSet<&'a Nation>,
+    units: &'a [UnitPosition<'static, RegionKey>],
+}
+
+impl WorldState for BuildWorld<'_> {
+    fn nations(&self) -> HashSet<&Nation> {
+        self.nations.clone()
+    }
+
+    fn occupier(&self, province: &ProvinceKey) -> Option<&Nation> {
+        self.units
+            .iter()
+            .find(|unit| unit.region.province() == province)
+            .map(|unit| unit.nation())
+    }
+
+    fn unit_count(&self, nation: &Nation) -> u8 {
+        self.units.iter().filter(|u| u.nation() == nation).count() as u8
+    }
+
+    fn units(&self, nation: &Nation) -> HashSet<(UnitType, RegionKey)> {
+        self.units
+            .iter()
+            .filter(|u| u.nation() == nation)
+            .map(|u| (u.unit.unit_type(), u.region.clone()))
+            .collect()
+    }
+}
+
+fn to_owned_positions(
+    positions: &impl UnitPositions<RegionKey>,
+) -> Vec<UnitPosition<'static, RegionKey>> {
+    positions
+        .unit_positions()
+        .into_iter()
+        .map(UnitPosition::into_owned)
+        .collect()
+}
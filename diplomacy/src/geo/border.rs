@@ -1,8 +1,10 @@
 use crate::geo::{RegionKey, Terrain};
+use crate::UnitType;
 
 /// An undirected edge between two regions in a graph of the map. Units move
 /// between regions via borders.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Border(RegionKey, RegionKey, Terrain);
 
 impl Border {
@@ -15,6 +17,11 @@ impl Border {
         self.2
     }
 
+    /// Returns true if a unit of type `unit_type` can move across this border.
+    pub fn is_passable_by(&self, unit_type: UnitType) -> bool {
+        unit_type.can_occupy(self.terrain())
+    }
+
     pub fn sides(&self) -> (&RegionKey, &RegionKey) {
         (&self.0, &self.1)
     }
@@ -4,29 +4,124 @@
 //! 1. Create a `ProvinceRegistry` and add all provinces.
 //! 1. Call `ProvinceRegistry::finish()` and then add all regions to that function's return.
 //! 1. Call `RegionRegistry::finish()` and add all borders to that function's return.
-//! 1. Call `BorderRegistry::finish()` and use the resulting map.
+//! 1. Call `BorderRegistry::finish()` and use the resulting map, or fix the problems it reports.
+//! 1. If the map will be played with units already on the board, call `check_starting_units`
+//!    with those units.
 
-use super::{Border, Coast, Map, Province, ProvinceKey, Region, Terrain};
-use crate::ShortName;
-use std::collections::HashMap;
+use super::{Border, Coast, Map, Province, ProvinceKey, Region, RegionKey, SupplyCenter, Terrain};
+use crate::{Nation, ShortName, UnitPosition, UnitType};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
 
-#[derive(Debug, Clone)]
+/// The error type for assembling or loading a map.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MapError {
-    ProvinceNotFound,
-    RegionNotFound,
-    IncompatibleBorderTerrain,
+    /// A region referred to a province which hasn't been registered.
+    ProvinceNotFound(String),
+    /// A border referred to a region which hasn't been registered.
+    RegionNotFound(String),
+    /// A border's terrain doesn't match the terrain of the two regions it connects.
+    IncompatibleBorderTerrain(String, String),
+    /// A row or value in a map document couldn't be parsed.
+    Malformed(String),
+    /// A map document had a section header where it wasn't expected.
+    UnexpectedSection(String),
+    /// A map document ended without the named section.
+    MissingSection(String),
+    /// Reading a map document failed.
+    Io(String),
+    /// An error on the given line of a map document, counting from 1.
+    Line(usize, Box<MapError>),
+    /// A province was registered more than once.
+    DuplicateProvince(String),
+    /// A region was registered more than once.
+    DuplicateRegion(String),
+    /// A region has no borders, so no unit could ever enter or leave it.
+    RegionWithoutBorders(String),
+    /// A coastal region has no border with the sea, so no fleet could ever reach it.
+    CoastNotReachableBySea(String),
+    /// A border between two regions was registered more than once.
+    DuplicateBorder(String, String),
+    /// A border between two regions was registered more than once with different terrain.
+    AsymmetricBorder(String, String),
+    /// A province with named coasts has no region for armies.
+    MissingLandRegion(String),
+    /// A province made up only of sea regions is marked as a supply center.
+    SeaSupplyCenter(String),
+    /// A nation has home supply centers but no units at the start of the game.
+    HomeCenterWithoutUnits(Nation),
+    /// A starting unit is in a region that isn't on the map or that the unit can't occupy.
+    InvalidStartingUnit(String),
+    /// The map has one or more problems, which are listed in full.
+    Invalid(Vec<MapError>),
+}
+
+impl Error for MapError {}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapError::ProvinceNotFound(name) => write!(f, "Province not found: '{}'", name),
+            MapError::RegionNotFound(name) => write!(f, "Region not found: '{}'", name),
+            MapError::IncompatibleBorderTerrain(r1, r2) => {
+                write!(f, "Incompatible border terrain: '{}' to '{}'", r1, r2)
+            }
+            MapError::Malformed(input) => write!(f, "Malformed input: '{}'", input),
+            MapError::UnexpectedSection(name) => write!(f, "Unexpected section: '{}'", name),
+            MapError::MissingSection(name) => write!(f, "Missing section: '{}'", name),
+            MapError::Io(message) => write!(f, "I/O error: {}", message),
+            MapError::Line(line, e) => write!(f, "Line {}: {}", line, e),
+            MapError::DuplicateProvince(name) => write!(f, "Duplicate province: '{}'", name),
+            MapError::DuplicateRegion(name) => write!(f, "Duplicate region: '{}'", name),
+            MapError::RegionWithoutBorders(name) => write!(f, "Region has no borders: '{}'", name),
+            MapError::CoastNotReachableBySea(name) => {
+                write!(f, "Coast not reachable by sea: '{}'", name)
+            }
+            MapError::DuplicateBorder(r1, r2) => {
+                write!(f, "Duplicate border: '{}' to '{}'", r1, r2)
+            }
+            MapError::AsymmetricBorder(r1, r2) => {
+                write!(f, "Asymmetric border: '{}' to '{}'", r1, r2)
+            }
+            MapError::MissingLandRegion(name) => {
+                write!(f, "Province with coasts has no land region: '{}'", name)
+            }
+            MapError::SeaSupplyCenter(name) => write!(f, "Sea supply center: '{}'", name),
+            MapError::HomeCenterWithoutUnits(nation) => {
+                write!(f, "Nation has home centers but no units: '{}'", nation)
+            }
+            MapError::InvalidStartingUnit(unit) => write!(f, "Invalid starting unit: '{}'", unit),
+            MapError::Invalid(errors) => {
+                write!(f, "Invalid map:")?;
+                for error in errors {
+                    write!(f, "\n  {}", error)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
 }
 
 /// A collection of provinces that validates on insertion.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct ProvinceRegistry {
     provinces: HashMap<String, Province>,
+    problems: Vec<MapError>,
 }
 
 impl ProvinceRegistry {
     /// Inserts a new province into the registry.
+    ///
+    /// A province with the same name as an earlier one replaces it, and is reported as a problem
+    /// by `BorderRegistry::finish`.
     pub fn register(&mut self, p: Province) -> Result<(), MapError> {
-        self.provinces.insert(p.short_name().into_owned(), p);
+        let name = p.short_name().into_owned();
+        if self.provinces.insert(name.clone(), p).is_some() {
+            self.problems.push(MapError::DuplicateProvince(name));
+        }
+
         Ok(())
     }
 
@@ -41,6 +136,7 @@ impl ProvinceRegistry {
 pub struct RegionRegistry {
     provinces: HashMap<String, Province>,
     regions: HashMap<String, Region>,
+    problems: Vec<MapError>,
 }
 
 impl RegionRegistry {
@@ -48,6 +144,7 @@ impl RegionRegistry {
         RegionRegistry {
             provinces: provinces.provinces,
             regions: HashMap::new(),
+            problems: provinces.problems,
         }
     }
 
@@ -56,6 +153,9 @@ impl RegionRegistry {
     /// This function validates that:
     ///
     /// 1. `province_name` identifies a known province.
+    ///
+    /// A region with the same name as an earlier one replaces it, and is reported as a problem
+    /// by `BorderRegistry::finish`.
     pub fn register(
         &mut self,
         province_name: &str,
@@ -63,8 +163,11 @@ impl RegionRegistry {
         terrain: Terrain,
     ) -> Result<(), MapError> {
         let region = Region::new(self.find_province(province_name)?, coast, terrain);
-        self.regions
-            .insert(region.short_name().into_owned(), region);
+        let name = region.short_name().into_owned();
+        if self.regions.insert(name.clone(), region).is_some() {
+            self.problems.push(MapError::DuplicateRegion(name));
+        }
+
         Ok(())
     }
 
@@ -72,7 +175,7 @@ impl RegionRegistry {
         self.provinces
             .get(k)
             .map(ProvinceKey::from)
-            .ok_or(MapError::ProvinceNotFound)
+            .ok_or_else(|| MapError::ProvinceNotFound(k.into()))
     }
 
     /// Stops accepting regions and starts accepting borders.
@@ -93,6 +196,7 @@ pub struct BorderRegistry {
     provinces: HashMap<String, Province>,
     regions: HashMap<String, Region>,
     borders: Vec<Border>,
+    problems: Vec<MapError>,
 }
 
 impl BorderRegistry {
@@ -102,6 +206,7 @@ impl BorderRegistry {
             provinces: builder.provinces,
             regions: builder.regions,
             borders: vec![],
+            problems: builder.problems,
         }
     }
 
@@ -116,7 +221,8 @@ impl BorderRegistry {
             let rk1 = self.find_region(r1)?;
             let rk2 = self.find_region(r2)?;
 
-            BorderRegistry::validate_terrain(rk1.terrain(), rk2.terrain(), terrain)?;
+            BorderRegistry::validate_terrain(rk1.terrain(), rk2.terrain(), terrain)
+                .map_err(|_| MapError::IncompatibleBorderTerrain(r1.into(), r2.into()))?;
         }
 
         self.borders.push(Border::new(
@@ -127,9 +233,119 @@ impl BorderRegistry {
         Ok(())
     }
 
-    /// Convert the builder to an immutable Map instance.
-    pub fn finish(self) -> Map {
-        Map::from(self)
+    /// Validate the map and convert the builder to an immutable Map instance.
+    ///
+    /// If the map has any problems, all of them are returned instead of the map. Problems
+    /// include:
+    ///
+    /// 1. Provinces, regions or borders that were registered more than once.
+    /// 1. Borders registered more than once with different terrain.
+    /// 1. Regions with no borders, unless the whole province is impassable and has no supply
+    ///    center, and coastal regions with no border to the sea.
+    /// 1. Provinces with named coasts but no region for armies.
+    /// 1. Supply centers in provinces made up only of sea regions.
+    ///
+    /// The map doesn't know which units start the game on it, so starting units are checked
+    /// separately by `check_starting_units` once the map is finished.
+    pub fn finish(self) -> Result<Map, Vec<MapError>> {
+        let mut problems = self.problems.clone();
+        problems.extend(self.border_problems());
+        problems.extend(self.region_problems());
+        problems.extend(self.province_problems());
+
+        if problems.is_empty() {
+            Ok(Map::from(self))
+        } else {
+            Err(problems)
+        }
+    }
+
+    fn border_problems(&self) -> Vec<MapError> {
+        let mut problems = vec![];
+        let mut seen: HashMap<BTreeSet<&RegionKey>, Terrain> = HashMap::new();
+
+        for border in &self.borders {
+            let (r1, r2) = border.sides();
+            let key = vec![r1, r2].into_iter().collect::<BTreeSet<_>>();
+            match seen.get(&key) {
+                Some(&terrain) => problems.push(if terrain == border.terrain() {
+                    MapError::DuplicateBorder(r1.short_name().into(), r2.short_name().into())
+                } else {
+                    MapError::AsymmetricBorder(r1.short_name().into(), r2.short_name().into())
+                }),
+                None => {
+                    seen.insert(key, border.terrain());
+                }
+            }
+        }
+
+        problems
+    }
+
+    fn region_problems(&self) -> Vec<MapError> {
+        let mut problems = vec![];
+
+        for (name, region) in sorted(&self.regions) {
+            let key = RegionKey::from(region);
+            let borders = self
+                .borders
+                .iter()
+                .filter(|b| b.contains(&key))
+                .collect::<Vec<_>>();
+
+            if borders.is_empty() {
+                if !self.is_impassable(region.province()) {
+                    problems.push(MapError::RegionWithoutBorders(name.clone()));
+                }
+            } else if region.terrain() == Terrain::Coast
+                && !borders.iter().any(|b| b.terrain() == Terrain::Sea)
+            {
+                problems.push(MapError::CoastNotReachableBySea(name.clone()));
+            }
+        }
+
+        problems
+    }
+
+    /// Whether a province is deliberately impassable, such as Switzerland on the standard map:
+    /// none of its regions have borders, and it isn't a supply center.
+    fn is_impassable(&self, province: &ProvinceKey) -> bool {
+        let is_supply_center = self
+            .provinces
+            .get(&*province.short_name())
+            .map(Province::is_supply_center)
+            .unwrap_or(false);
+
+        !is_supply_center
+            && !self
+                .borders
+                .iter()
+                .any(|b| b.sides().0 == province || b.sides().1 == province)
+    }
+
+    fn province_problems(&self) -> Vec<MapError> {
+        let mut problems = vec![];
+
+        for (name, province) in sorted(&self.provinces) {
+            let key = ProvinceKey::from(province);
+            let regions = self
+                .regions
+                .values()
+                .filter(|r| r.province() == &key)
+                .collect::<Vec<_>>();
+
+            if regions.iter().any(|r| r.coast().is_some())
+                && !regions.iter().any(|r| r.coast().is_none())
+            {
+                problems.push(MapError::MissingLandRegion(name.clone()));
+            }
+
+            if province.is_supply_center() && regions.iter().all(|r| r.terrain() == Terrain::Sea) {
+                problems.push(MapError::SeaSupplyCenter(name.clone()));
+            }
+        }
+
+        problems
     }
 
     /// Get a view of the contents in a format that `Map` can use.
@@ -148,24 +364,79 @@ impl BorderRegistry {
         if let Some(region) = self.regions.get(short_name) {
             Ok(region)
         } else {
-            Err(MapError::RegionNotFound)
+            Err(MapError::RegionNotFound(short_name.into()))
         }
     }
 
-    fn validate_terrain(rt1: Terrain, rt2: Terrain, bt: Terrain) -> Result<(), MapError> {
+    fn validate_terrain(rt1: Terrain, rt2: Terrain, bt: Terrain) -> Result<(), ()> {
         use crate::geo::Terrain::*;
 
         if ((rt1 == Sea || rt2 == Sea) && bt != Sea)
             || ((rt1 == Land || rt2 == Land) && bt != Land)
             || ((rt1 == Sea && rt2 == Land) || (rt1 == Land && rt2 == Sea))
         {
-            Err(MapError::IncompatibleBorderTerrain)
+            Err(())
         } else {
             Ok(())
         }
     }
 }
 
+/// Iterate through a map of items by name in a stable order, so problems are reported
+/// consistently.
+fn sorted<T>(items: &HashMap<String, T>) -> Vec<(&String, &T)> {
+    let mut items = items.iter().collect::<Vec<_>>();
+    items.sort_by_key(|(name, _)| *name);
+    items
+}
+
+/// Check that every nation with a home supply center on `map` has at least one unit in
+/// `units`, and that every unit is in a region of the map it can occupy.
+///
+/// This is the second step of validating a map for play, after `BorderRegistry::finish`. It
+/// isn't part of `finish` because some variants start the game with a build phase instead of
+/// units on the board, and skip this check.
+pub fn check_starting_units(map: &Map, units: &[UnitPosition<'_, RegionKey>]) -> Vec<MapError> {
+    let mut problems = vec![];
+
+    for unit in units {
+        let valid = map
+            .find_region(&unit.region.short_name())
+            .map(|region| {
+                !matches!(
+                    (unit.unit.unit_type(), region.terrain()),
+                    (UnitType::Army, Terrain::Sea) | (UnitType::Fleet, Terrain::Land)
+                )
+            })
+            .unwrap_or(false);
+        if !valid {
+            problems.push(MapError::InvalidStartingUnit(format!(
+                "{}: {} {}",
+                unit.nation(),
+                unit.unit.unit_type().short_name(),
+                unit.region.short_name()
+            )));
+        }
+    }
+
+    let with_units = units.iter().map(|u| u.nation()).collect::<HashSet<_>>();
+    let with_homes = map
+        .provinces()
+        .filter_map(|p| match &p.supply_center {
+            SupplyCenter::Home(nation) => Some(nation),
+            _ => None,
+        })
+        .collect::<BTreeSet<_>>();
+
+    for nation in with_homes {
+        if !with_units.contains(nation) {
+            problems.push(MapError::HomeCenterWithoutUnits(nation.clone()));
+        }
+    }
+
+    problems
+}
+
 impl From<RegionRegistry> for BorderRegistry {
     fn from(rr: RegionRegistry) -> Self {
         rr.finish()
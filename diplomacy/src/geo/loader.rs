@@ -0,0 +1,531 @@
+//! Loads maps from data provided at runtime.
+//!
+//! # Text Format
+//! A map document has three sections, which must appear in this order. Each section holds
+//! comma-separated rows in the same layout as the CSV files behind the standard map.
+//!
+//! ```text
+//! # Lines starting with '#' and blank lines are ignored.
+//! [provinces]
+//! short_name,full_name,supply_center_for
+//! bre,Brest,FRA
+//! mao,Mid-Atlantic Ocean,
+//! por,Portugal,neutral
+//!
+//! [regions]
+//! province,coast,terrain
+//! bre,,coast
+//! mao,,sea
+//! por,,coast
+//!
+//! [borders]
+//! region 1,region 2,terrain
+//! bre,mao,sea
+//! mao,por,sea
+//! ```
+//!
+//! The header row of each section is optional. `supply_center_for` is empty for provinces
+//! without a supply center, `neutral` for unowned supply centers, or the nation whose home
+//! supply center it is. `coast` is empty or one of `n`, `e`, `s` or `w`, and `terrain` is one of
+//! `land`, `coast` or `sea`.
+//!
+//! # Structured Formats
+//! The same data can be expressed as a `MapDefinition`, which can be deserialized from JSON,
+//! TOML or any other format supported by `serde` when the `serde` feature is enabled.
+
+use super::builder::{BorderRegistry, MapError, ProvinceRegistry, RegionRegistry};
+use super::{Coast, Map, Province, Region, SupplyCenter, Terrain};
+use crate::ShortName;
+use std::convert::TryFrom;
+use std::io::BufRead;
+
+const PROVINCES_HEADER: &str = "short_name,full_name,supply_center_for";
+const REGIONS_HEADER: &str = "province,coast,terrain";
+const BORDERS_HEADER: &str = "region 1,region 2,terrain";
+
+/// The provinces, regions and borders that make up a map.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapDefinition {
+    pub provinces: Vec<ProvinceDefinition>,
+    pub regions: Vec<RegionDefinition>,
+    pub borders: Vec<BorderDefinition>,
+}
+
+/// A row in the `[provinces]` section of a map document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProvinceDefinition {
+    pub short_name: String,
+    pub full_name: String,
+    /// `None` for provinces without a supply center, `"neutral"` for unowned supply centers,
+    /// or the nation whose home supply center this is.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub supply_center_for: Option<String>,
+}
+
+/// A row in the `[regions]` section of a map document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegionDefinition {
+    pub province: String,
+    /// One of `n`, `e`, `s` or `w`, if the province has multiple coasts.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub coast: Option<String>,
+    /// One of `land`, `coast` or `sea`.
+    pub terrain: String,
+}
+
+/// A row in the `[borders]` section of a map document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BorderDefinition {
+    /// The short name of a region, including its coast if any, such as `stp(nc)`.
+    pub from: String,
+    pub to: String,
+    /// One of `land`, `coast` or `sea`.
+    pub terrain: String,
+}
+
+impl ProvinceDefinition {
+    fn from_line(line: &str) -> Result<Self, MapError> {
+        match split_line(line)[..] {
+            [short_name, full_name, supply_center_for] => Ok(Self {
+                short_name: short_name.into(),
+                full_name: full_name.into(),
+                supply_center_for: none_if_empty(supply_center_for),
+            }),
+            _ => Err(MapError::Malformed(line.into())),
+        }
+    }
+
+    fn register(&self, registry: &mut ProvinceRegistry) -> Result<(), MapError> {
+        let supply_center = match self.supply_center_for.as_deref() {
+            None | Some("") => SupplyCenter::None,
+            Some("neutral") => SupplyCenter::Neutral,
+            Some(nation) => SupplyCenter::Home(nation.into()),
+        };
+
+        registry.register(Province {
+            short_name: self.short_name.clone(),
+            full_name: self.full_name.clone(),
+            supply_center,
+        })
+    }
+}
+
+impl RegionDefinition {
+    fn from_line(line: &str) -> Result<Self, MapError> {
+        match split_line(line)[..] {
+            [province, coast, terrain] => Ok(Self {
+                province: province.into(),
+                coast: none_if_empty(coast),
+                terrain: terrain.into(),
+            }),
+            _ => Err(MapError::Malformed(line.into())),
+        }
+    }
+
+    fn register(&self, registry: &mut RegionRegistry) -> Result<(), MapError> {
+        registry.register(
+            &self.province,
+            coast_from_word(self.coast.as_deref().unwrap_or(""))?,
+            terrain_from_word(&self.terrain)?,
+        )
+    }
+}
+
+impl BorderDefinition {
+    fn from_line(line: &str) -> Result<Self, MapError> {
+        match split_line(line)[..] {
+            [from, to, terrain] => Ok(Self {
+                from: from.into(),
+                to: to.into(),
+                terrain: terrain.into(),
+            }),
+            _ => Err(MapError::Malformed(line.into())),
+        }
+    }
+
+    fn register(&self, registry: &mut BorderRegistry) -> Result<(), MapError> {
+        registry.register(&self.from, &self.to, terrain_from_word(&self.terrain)?)
+    }
+}
+
+impl TryFrom<MapDefinition> for Map {
+    type Error = MapError;
+
+    fn try_from(definition: MapDefinition) -> Result<Self, Self::Error> {
+        let mut provinces = ProvinceRegistry::default();
+        for province in &definition.provinces {
+            province.register(&mut provinces)?;
+        }
+
+        let mut regions = provinces.finish();
+        for region in &definition.regions {
+            region.register(&mut regions)?;
+        }
+
+        let mut borders = regions.finish();
+        for border in &definition.borders {
+            border.register(&mut borders)?;
+        }
+
+        borders.finish().map_err(MapError::Invalid)
+    }
+}
+
+/// Describe an existing map. Provinces and regions are listed in order of their short names.
+impl From<&Map> for MapDefinition {
+    fn from(map: &Map) -> Self {
+        let mut provinces = map
+            .provinces()
+            .map(ProvinceDefinition::from)
+            .collect::<Vec<_>>();
+        provinces.sort_by(|a, b| a.short_name.cmp(&b.short_name));
+
+        let mut regions = map.regions().collect::<Vec<_>>();
+        regions.sort_by_key(|region| region.short_name());
+
+        Self {
+            provinces,
+            regions: regions.into_iter().map(RegionDefinition::from).collect(),
+            borders: map
+                .borders()
+                .map(|border| {
+                    let (from, to) = border.sides();
+                    BorderDefinition {
+                        from: from.short_name().into_owned(),
+                        to: to.short_name().into_owned(),
+                        terrain: terrain_word(border.terrain()).into(),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<Map> for MapDefinition {
+    fn from(map: Map) -> Self {
+        Self::from(&map)
+    }
+}
+
+impl From<&Province> for ProvinceDefinition {
+    fn from(province: &Province) -> Self {
+        Self {
+            short_name: province.short_name.clone(),
+            full_name: province.full_name.clone(),
+            supply_center_for: match &province.supply_center {
+                SupplyCenter::None => None,
+                SupplyCenter::Neutral => Some("neutral".into()),
+                SupplyCenter::Home(nation) => Some(nation.short_name().into_owned()),
+            },
+        }
+    }
+}
+
+impl From<&Region> for RegionDefinition {
+    fn from(region: &Region) -> Self {
+        Self {
+            province: region.province().short_name().into_owned(),
+            coast: region.coast().map(|coast| coast_word(coast).into()),
+            terrain: terrain_word(region.terrain()).into(),
+        }
+    }
+}
+
+/// The section of a map document being read, holding the registry for that section.
+enum Section {
+    Start,
+    Provinces(ProvinceRegistry),
+    Regions(RegionRegistry),
+    Borders(BorderRegistry),
+}
+
+/// Read a map document in the text format described in the module documentation.
+pub(in crate::geo) fn load(reader: impl BufRead) -> Result<Map, MapError> {
+    let mut section = Section::Start;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.map_err(|e| MapError::Io(e.to_string()))?;
+        section = read_line(section, line.trim())
+            .map_err(|e| MapError::Line(line_number, Box::new(e)))?;
+    }
+
+    match section {
+        Section::Borders(borders) => borders.finish().map_err(MapError::Invalid),
+        _ => Err(MapError::MissingSection("borders".into())),
+    }
+}
+
+fn read_line(section: Section, line: &str) -> Result<Section, MapError> {
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(section);
+    }
+
+    match (section, line) {
+        (Section::Start, "[provinces]") => Ok(Section::Provinces(ProvinceRegistry::default())),
+        (Section::Provinces(provinces), "[regions]") => Ok(Section::Regions(provinces.finish())),
+        (Section::Regions(regions), "[borders]") => Ok(Section::Borders(regions.finish())),
+        (_, line) if line.starts_with('[') => Err(MapError::UnexpectedSection(line.into())),
+        (Section::Start, line) => Err(MapError::Malformed(line.into())),
+        (section, line) if is_header(&section, line) => Ok(section),
+        (Section::Provinces(mut provinces), line) => {
+            ProvinceDefinition::from_line(line)?.register(&mut provinces)?;
+            Ok(Section::Provinces(provinces))
+        }
+        (Section::Regions(mut regions), line) => {
+            RegionDefinition::from_line(line)?.register(&mut regions)?;
+            Ok(Section::Regions(regions))
+        }
+        (Section::Borders(mut borders), line) => {
+            BorderDefinition::from_line(line)?.register(&mut borders)?;
+            Ok(Section::Borders(borders))
+        }
+    }
+}
+
+fn is_header(section: &Section, line: &str) -> bool {
+    let header = match section {
+        Section::Start => return false,
+        Section::Provinces(_) => PROVINCES_HEADER,
+        Section::Regions(_) => REGIONS_HEADER,
+        Section::Borders(_) => BORDERS_HEADER,
+    };
+
+    split_line(line) == split_line(header)
+}
+
+/// Register the provinces, regions and borders from the CSV files that define a map, each of
+/// which starts with a header row.
+pub(in crate::geo) fn load_csv(
+    provinces: &str,
+    regions: &str,
+    borders: &str,
+) -> Result<Map, MapError> {
+    let mut province_registry = ProvinceRegistry::default();
+    for line in csv_rows(provinces) {
+        ProvinceDefinition::from_line(line)?.register(&mut province_registry)?;
+    }
+
+    let mut region_registry = province_registry.finish();
+    for line in csv_rows(regions) {
+        RegionDefinition::from_line(line)?.register(&mut region_registry)?;
+    }
+
+    let mut border_registry = region_registry.finish();
+    for line in csv_rows(borders) {
+        BorderDefinition::from_line(line)?.register(&mut border_registry)?;
+    }
+
+    border_registry.finish().map_err(MapError::Invalid)
+}
+
+fn csv_rows(file: &str) -> impl Iterator<Item = &str> {
+    file.lines().skip(1).filter(|line| !line.trim().is_empty())
+}
+
+fn split_line(line: &str) -> Vec<&str> {
+    line.split(',').map(str::trim).collect()
+}
+
+fn none_if_empty(word: &str) -> Option<String> {
+    if word.is_empty() {
+        None
+    } else {
+        Some(word.into())
+    }
+}
+
+fn coast_from_word(w: &str) -> Result<Option<Coast>, MapError> {
+    match w {
+        "" => Ok(None),
+        "n" => Ok(Some(Coast::North)),
+        "e" => Ok(Some(Coast::East)),
+        "s" => Ok(Some(Coast::South)),
+        "w" => Ok(Some(Coast::West)),
+        _ => Err(MapError::Malformed(w.into())),
+    }
+}
+
+fn coast_word(coast: Coast) -> &'static str {
+    match coast {
+        Coast::North => "n",
+        Coast::East => "e",
+        Coast::South => "s",
+        Coast::West => "w",
+    }
+}
+
+fn terrain_from_word(w: &str) -> Result<Terrain, MapError> {
+    match w {
+        "sea" => Ok(Terrain::Sea),
+        "coast" => Ok(Terrain::Coast),
+        "land" => Ok(Terrain::Land),
+        _ => Err(MapError::Malformed(w.into())),
+    }
+}
+
+fn terrain_word(terrain: Terrain) -> &'static str {
+    match terrain {
+        Terrain::Sea => "sea",
+        Terrain::Coast => "coast",
+        Terrain::Land => "land",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load, MapDefinition};
+    use crate::geo::builder::MapError;
+    use crate::geo::{Map, RegionKey, SupplyCenter};
+    use std::convert::TryFrom;
+
+    const TINY: &str = "
+        # Three provinces on the Atlantic coast.
+        [provinces]
+        short_name,full_name,supply_center_for
+        bre,Brest,FRA
+        mao,Mid-Atlantic Ocean,
+        por,Portugal,neutral
+
+        [regions]
+        bre,,coast
+        mao,,sea
+        por,,coast
+
+        [borders]
+        bre,mao,sea
+        mao,por,sea
+    ";
+
+    #[test]
+    fn loads_text_document() {
+        let map = Map::from_reader(TINY.as_bytes()).unwrap();
+
+        assert_eq!(3, map.provinces().count());
+        assert_eq!(
+            Some(&SupplyCenter::Home("FRA".into())),
+            map.provinces()
+                .find(|p| p.short_name == "bre")
+                .map(|p| &p.supply_center)
+        );
+        assert_eq!(
+            vec![&RegionKey::new("mao", None)],
+            map.find_bordering(&RegionKey::new("por", None))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn definition_round_trips_full_names() {
+        let map = Map::from_reader(TINY.as_bytes()).unwrap();
+        let definition = MapDefinition::from(&map);
+
+        assert_eq!(
+            vec!["Brest", "Mid-Atlantic Ocean", "Portugal"],
+            definition
+                .provinces
+                .iter()
+                .map(|p| p.full_name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(map, Map::try_from(definition).unwrap());
+    }
+
+    #[test]
+    fn reports_line_of_unknown_region() {
+        let doc = TINY.replace("mao,por,sea", "mao,spa,sea");
+        match load(doc.as_bytes()) {
+            Err(MapError::Line(16, e)) => assert_eq!(MapError::RegionNotFound("spa".into()), *e),
+            other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn reports_line_of_bad_terrain() {
+        let doc = TINY.replace("bre,,coast", "bre,,mountain");
+        match load(doc.as_bytes()) {
+            Err(MapError::Line(10, e)) => assert_eq!(MapError::Malformed("mountain".into()), *e),
+            other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn reports_every_problem() {
+        let doc = TINY
+            .replace(
+                "mao,Mid-Atlantic Ocean,",
+                "mao,Mid-Atlantic Ocean,neutral\nbre,Brest,FRA",
+            )
+            .replace("mao,,sea", "mao,,sea\nspa,,coast\nspa,n,sea")
+            .replace(
+                "por,Portugal,neutral",
+                "por,Portugal,neutral\nspa,Spain,neutral",
+            )
+            .replace(
+                "mao,por,sea",
+                "mao,por,sea\nbre,por,coast\npor,bre,land\nbre,mao,sea",
+            );
+
+        match load(doc.as_bytes()) {
+            Err(MapError::Invalid(problems)) => assert_eq!(
+                vec![
+                    MapError::DuplicateProvince("bre".into()),
+                    MapError::AsymmetricBorder("por".into(), "bre".into()),
+                    MapError::DuplicateBorder("bre".into(), "mao".into()),
+                    MapError::RegionWithoutBorders("spa".into()),
+                    MapError::RegionWithoutBorders("spa(nc)".into()),
+                    MapError::SeaSupplyCenter("mao".into()),
+                ],
+                problems
+            ),
+            other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn reports_coast_without_sea_and_province_without_land() {
+        let doc = TINY
+            .replace("por,Portugal,neutral", "por,Portugal,neutral\ngas,Gascony,")
+            .replace("por,,coast", "por,n,sea\ngas,,coast")
+            .replace("mao,por,sea", "mao,por(nc),sea\nbre,gas,coast");
+
+        match load(doc.as_bytes()) {
+            Err(MapError::Invalid(problems)) => assert_eq!(
+                vec![
+                    MapError::CoastNotReachableBySea("gas".into()),
+                    MapError::MissingLandRegion("por".into()),
+                    MapError::SeaSupplyCenter("por".into()),
+                ],
+                problems
+            ),
+            other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn allows_impassable_province() {
+        let doc = TINY
+            .replace(
+                "por,Portugal,neutral",
+                "por,Portugal,neutral\nswi,Switzerland,",
+            )
+            .replace("por,,coast", "por,,coast\nswi,,land");
+
+        assert!(load(doc.as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn requires_sections_in_order() {
+        let doc = "[regions]\nbre,,coast\n";
+        match load(doc.as_bytes()) {
+            Err(MapError::Line(1, e)) => {
+                assert_eq!(MapError::UnexpectedSection("[regions]".into()), *e)
+            }
+            other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+}
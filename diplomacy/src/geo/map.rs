@@ -1,61 +1,219 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::io::BufRead;
+use std::slice;
 
 use super::{Border, Province, ProvinceKey, Region, RegionKey};
-use crate::geo::builder::BorderRegistry;
+use crate::geo::builder::{BorderRegistry, MapError};
+use crate::geo::loader;
 
 /// A collection of provinces, their constituent regions, and the interconnecting borders.
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+///
+/// With the `serde` feature enabled, maps are serialized in the same shape as a
+/// `MapDefinition`, and are validated when deserialized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "loader::MapDefinition", try_from = "loader::MapDefinition")
+)]
 pub struct Map {
     provinces: HashMap<String, Province>,
     regions: HashMap<String, Region>,
     borders: Vec<Border>,
+    /// Indices in `borders` of the borders touching each region, in registration order.
+    region_borders: HashMap<RegionKey, Vec<usize>>,
+    /// Indices in `borders` of the borders touching any region of each province, in
+    /// registration order.
+    province_borders: HashMap<ProvinceKey, Vec<usize>>,
+    /// A hash of the provinces, regions and borders, so that maps can be told apart without
+    /// comparing their contents.
+    digest: u64,
 }
 
 impl Map {
+    /// Load a map from a document listing its provinces, regions and borders. See the
+    /// `geo::loader` module for a description of the format.
+    ///
+    /// Errors in the document are reported with the line on which they occurred.
+    pub fn from_reader(reader: impl BufRead) -> Result<Self, MapError> {
+        loader::load(reader)
+    }
+
     /// Iterate through the provinces in the map. Each province will be returned exactly once,
     /// but order is unspecified.
     pub fn provinces(&self) -> impl Iterator<Item = &Province> {
         self.provinces.values()
     }
 
+    /// Iterate through the regions in the map. Each region will be returned exactly once, but
+    /// order is unspecified.
+    pub fn regions(&self) -> impl Iterator<Item = &Region> {
+        self.regions.values()
+    }
+
+    /// Iterate through the borders in the map, in the order they were registered.
+    pub fn borders(&self) -> impl Iterator<Item = &Border> {
+        self.borders.iter()
+    }
+
+    /// Find a province by its short name.
+    pub fn find_province<'a>(&'a self, short_name: &str) -> Option<&'a Province> {
+        self.provinces.get(short_name)
+    }
+
     /// Find a region by its canonical short name.
     pub fn find_region<'a>(&'a self, short_name: &str) -> Option<&'a Region> {
         self.regions.get(short_name)
     }
 
-    /// Get all borders with a region.
-    pub fn borders_containing<'a, L: PartialEq<RegionKey>>(&'a self, r: &L) -> Vec<&Border> {
-        self.borders.iter().filter(|b| b.contains(r)).collect()
+    /// Get all borders with a region, or with any region of a province, in the order they
+    /// were registered.
+    pub fn borders_containing<L: BorderedLocation>(&self, r: &L) -> Borders<'_> {
+        r.borders_in(self)
     }
 
-    /// Gets the set of regions which connect to the specified region. If `terrain`
-    /// is provided, only borders matching that terrain will be provided.
-    pub fn find_bordering<'a>(&'a self, region: &impl PartialEq<RegionKey>) -> Vec<&RegionKey> {
+    /// Gets the regions which connect to the specified region, or to any region of the
+    /// specified province.
+    pub fn find_bordering<'a, 'b, L: BorderedLocation>(
+        &'a self,
+        region: &'b L,
+    ) -> impl 'b + Iterator<Item = &'a RegionKey>
+    where
+        'a: 'b,
+    {
         self.borders_containing(region)
-            .iter()
-            .filter_map(|b| b.dest_from(region))
-            .collect()
+            .filter_map(move |b| b.dest_from(region))
     }
 
     /// Get a border between two regions, if one exists.
     pub fn find_border_between(&self, r1: &RegionKey, r2: &RegionKey) -> Option<&Border> {
-        self.borders.iter().find(|b| b.connects(r1, r2))
+        self.borders_containing(r1).find(|b| b.connects(r1, r2))
     }
 
     /// Finds all borders connecting a region to a given province.
     /// Used for support and convoy cases.
-    pub fn find_borders_between<'a>(&'a self, r1: &RegionKey, p2: &ProvinceKey) -> Vec<&Border> {
-        self.borders.iter().filter(|b| b.connects(r1, p2)).collect()
+    pub fn find_borders_between<'a, 'b>(
+        &'a self,
+        r1: &'b RegionKey,
+        p2: &'b ProvinceKey,
+    ) -> impl 'b + Iterator<Item = &'a Border>
+    where
+        'a: 'b,
+    {
+        self.borders_containing(r1)
+            .filter(move |b| b.connects(r1, p2))
+    }
+
+    /// A hash of the map's contents. Maps with the same provinces, regions and borders have the
+    /// same digest within a process.
+    pub(crate) fn digest(&self) -> u64 {
+        self.digest
     }
+
+    fn borders_at<'a, K: Eq + Hash>(
+        &'a self,
+        table: &'a HashMap<K, Vec<usize>>,
+        key: &K,
+    ) -> Borders<'a> {
+        Borders {
+            borders: &self.borders,
+            indices: table.get(key).map(Vec::as_slice).unwrap_or_default().iter(),
+        }
+    }
+}
+
+/// A location whose borders can be looked up in a `Map`. This is implemented for regions, and
+/// for provinces, which touch every border of each of their regions.
+pub trait BorderedLocation: PartialEq<RegionKey> {
+    /// Get the borders touching this location in `map`.
+    fn borders_in<'a>(&self, map: &'a Map) -> Borders<'a>;
 }
 
+impl BorderedLocation for RegionKey {
+    fn borders_in<'a>(&self, map: &'a Map) -> Borders<'a> {
+        map.borders_at(&map.region_borders, self)
+    }
+}
+
+impl BorderedLocation for ProvinceKey {
+    fn borders_in<'a>(&self, map: &'a Map) -> Borders<'a> {
+        map.borders_at(&map.province_borders, self)
+    }
+}
+
+/// An iterator over the borders touching a location, in the order they were registered.
+#[derive(Debug, Clone)]
+pub struct Borders<'a> {
+    borders: &'a [Border],
+    indices: slice::Iter<'a, usize>,
+}
+
+impl<'a> Iterator for Borders<'a> {
+    type Item = &'a Border;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.indices.next().map(|&index| &self.borders[index])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Borders<'_> {}
+
+impl Default for Map {
+    fn default() -> Self {
+        BorderRegistry::default().into()
+    }
+}
+
+/// Convert a registry to a map without validating it. Prefer `BorderRegistry::finish`, which
+/// reports problems with the map.
 impl From<BorderRegistry> for Map {
     fn from(other: BorderRegistry) -> Self {
         let (provinces, regions, borders) = other.contents();
+        let mut region_borders = HashMap::<_, Vec<_>>::new();
+        let mut province_borders = HashMap::<_, Vec<_>>::new();
+        for (index, border) in borders.iter().enumerate() {
+            let (r1, r2) = border.sides();
+            for region in &[r1, r2] {
+                region_borders
+                    .entry((*region).clone())
+                    .or_default()
+                    .push(index);
+            }
+
+            province_borders
+                .entry(r1.province().clone())
+                .or_default()
+                .push(index);
+            if r1.province() != r2.province() {
+                province_borders
+                    .entry(r2.province().clone())
+                    .or_default()
+                    .push(index);
+            }
+        }
+
+        let mut hasher = DefaultHasher::new();
+        let mut sorted_provinces = provinces.values().collect::<Vec<_>>();
+        sorted_provinces.sort_by_key(|province| &province.short_name);
+        sorted_provinces.hash(&mut hasher);
+        let mut sorted_regions = regions.iter().collect::<Vec<_>>();
+        sorted_regions.sort_by_key(|(name, _)| *name);
+        sorted_regions.hash(&mut hasher);
+        borders.hash(&mut hasher);
+
         Self {
             provinces,
             regions,
             borders,
+            region_borders,
+            province_borders,
+            digest: hasher.finish(),
         }
     }
 }
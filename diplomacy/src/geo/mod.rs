@@ -4,15 +4,22 @@
 mod border;
 mod location;
 mod map;
+mod path;
 mod province;
 mod region;
 mod standard;
 
 pub mod builder;
+pub mod loader;
+pub mod variant;
 
 pub use self::border::Border;
+pub use self::builder::MapError;
+pub use self::loader::{BorderDefinition, MapDefinition, ProvinceDefinition, RegionDefinition};
 pub use self::location::Location;
-pub use self::map::Map;
+pub use self::map::{BorderedLocation, Borders, Map};
+pub use self::path::{Convoys, DistanceTable};
 pub use self::province::{Province, ProvinceKey, SupplyCenter};
 pub use self::region::{Coast, Region, RegionKey, Terrain};
 pub use self::standard::standard_map;
+pub use self::variant::Variant;
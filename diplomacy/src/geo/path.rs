@@ -0,0 +1,343 @@
+//! Shortest paths and distances between regions of a map.
+//!
+//! Fleets travel between regions, so a fleet's path names the coast it uses in provinces with
+//! several coasts. Armies travel between provinces; their paths use the region without a coast
+//! for each province, and any coast named in a query for an army is ignored.
+
+use super::{Map, ProvinceKey, RegionKey, Terrain};
+use crate::{ShortName, UnitType};
+use std::collections::{HashMap, VecDeque};
+
+/// Whether armies may cross sea provinces when measuring paths across a map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Convoys {
+    /// Armies only move over land.
+    Ignored,
+    /// Armies may also cross sea provinces as though convoyed, whether or not there are fleets
+    /// in position to do so. Each sea province crossed counts as a move.
+    Counted,
+}
+
+impl Map {
+    /// Find a shortest path for a unit of type `unit_type` from `from` to `to`. The path starts
+    /// with `from` and ends with `to`. Returns `None` if the unit can't reach `to`.
+    pub fn shortest_path(
+        &self,
+        unit_type: UnitType,
+        convoys: Convoys,
+        from: &RegionKey,
+        to: &RegionKey,
+    ) -> Option<Vec<RegionKey>> {
+        let to = node(unit_type, to);
+        let mut reached_from = HashMap::<RegionKey, Option<RegionKey>>::new();
+        let mut queue = VecDeque::new();
+
+        let start = node(unit_type, from);
+        reached_from.insert(start.clone(), None);
+        queue.push_back(start);
+
+        while let Some(region) = queue.pop_front() {
+            if region == to {
+                let mut path = vec![region];
+                while let Some(Some(previous)) = reached_from.get(&path[path.len() - 1]) {
+                    path.push(previous.clone());
+                }
+
+                path.reverse();
+                return Some(path);
+            }
+
+            for next in self.next_regions(unit_type, convoys, &region) {
+                if !reached_from.contains_key(&next) {
+                    reached_from.insert(next.clone(), Some(region.clone()));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Count the moves a unit of type `unit_type` would need to get from `from` to `to`.
+    /// Returns `None` if the unit can't reach `to`.
+    pub fn distance(
+        &self,
+        unit_type: UnitType,
+        convoys: Convoys,
+        from: &RegionKey,
+        to: &RegionKey,
+    ) -> Option<usize> {
+        self.shortest_path(unit_type, convoys, from, to)
+            .map(|path| path.len() - 1)
+    }
+
+    /// Count the moves a unit of type `unit_type` in `from` would need to reach the nearest
+    /// region satisfying `is_goal`.
+    pub(crate) fn distance_to_nearest(
+        &self,
+        unit_type: UnitType,
+        convoys: Convoys,
+        from: &RegionKey,
+        is_goal: impl Fn(&RegionKey) -> bool,
+    ) -> Option<usize> {
+        self.distances_from(unit_type, convoys, from)
+            .into_iter()
+            .filter(|(region, _)| is_goal(region))
+            .map(|(_, distance)| distance)
+            .min()
+    }
+
+    /// Calculate the distance between every pair of regions a unit of type `unit_type` can
+    /// occupy. The table doesn't borrow the map, so it can be calculated once and kept.
+    pub fn distance_table(&self, unit_type: UnitType, convoys: Convoys) -> DistanceTable {
+        let mut regions = self
+            .regions()
+            .map(RegionKey::from)
+            .filter(|region| node(unit_type, region) == *region)
+            .filter(|region| {
+                self.terrain_of(region)
+                    .map(|terrain| unit_type.can_occupy(terrain))
+                    .unwrap_or(false)
+            })
+            .collect::<Vec<_>>();
+        regions.sort();
+
+        let mut distances = vec![None; regions.len() * regions.len()];
+        for (row, from) in regions.iter().enumerate() {
+            for (to, distance) in self.distances_from(unit_type, convoys, from) {
+                if let Ok(column) = regions.binary_search(&to) {
+                    distances[row * regions.len() + column] = Some(distance);
+                }
+            }
+        }
+
+        DistanceTable {
+            unit_type,
+            convoys,
+            regions,
+            distances,
+        }
+    }
+
+    /// Find the distance from `from` to every region a unit of type `unit_type` can reach.
+    fn distances_from(
+        &self,
+        unit_type: UnitType,
+        convoys: Convoys,
+        from: &RegionKey,
+    ) -> HashMap<RegionKey, usize> {
+        let mut distances = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        let start = node(unit_type, from);
+        distances.insert(start.clone(), 0);
+        queue.push_back((start, 0));
+
+        while let Some((region, distance)) = queue.pop_front() {
+            for next in self.next_regions(unit_type, convoys, &region) {
+                if !distances.contains_key(&next) {
+                    distances.insert(next.clone(), distance + 1);
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// Find the regions a unit of type `unit_type` in `region` could move to in one move.
+    fn next_regions(
+        &self,
+        unit_type: UnitType,
+        convoys: Convoys,
+        region: &RegionKey,
+    ) -> Vec<RegionKey> {
+        match unit_type {
+            UnitType::Fleet => self
+                .borders_containing(region)
+                .filter(|border| border.is_passable_by(UnitType::Fleet))
+                .filter_map(|border| border.dest_from(region))
+                .cloned()
+                .collect(),
+            UnitType::Army => {
+                let province = region.province();
+                let at_sea = self.is_sea(province);
+                self.borders_containing(province)
+                    .filter_map(|border| {
+                        let dest = border.dest_from(province)?.province();
+                        let by_convoy =
+                            convoys == Convoys::Counted && (at_sea || self.is_sea(dest));
+                        if border.is_passable_by(UnitType::Army) || by_convoy {
+                            Some(RegionKey::new(dest.clone(), None))
+                        } else {
+                            None
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    fn terrain_of(&self, region: &RegionKey) -> Option<Terrain> {
+        self.find_region(&region.short_name())
+            .map(|region| region.terrain())
+    }
+
+    fn is_sea(&self, province: &ProvinceKey) -> bool {
+        self.terrain_of(&RegionKey::new(province.clone(), None)) == Some(Terrain::Sea)
+    }
+}
+
+/// The region a unit of type `unit_type` in `region` is treated as occupying for pathfinding.
+fn node(unit_type: UnitType, region: &RegionKey) -> RegionKey {
+    match unit_type {
+        UnitType::Fleet => region.clone(),
+        UnitType::Army => RegionKey::new(region.province().clone(), None),
+    }
+}
+
+/// The distance between every pair of regions a unit can occupy, calculated by
+/// `Map::distance_table`.
+///
+/// With the `serde` feature enabled, tables can be serialized so they can be cached between
+/// runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DistanceTable {
+    unit_type: UnitType,
+    convoys: Convoys,
+    /// The regions in the table, in sorted order.
+    regions: Vec<RegionKey>,
+    /// The distance between each pair of regions, with a row for each region moved from.
+    distances: Vec<Option<usize>>,
+}
+
+impl DistanceTable {
+    /// The type of unit whose moves the table counts.
+    pub fn unit_type(&self) -> UnitType {
+        self.unit_type
+    }
+
+    /// Whether armies could cross sea provinces when the table was calculated.
+    pub fn convoys(&self) -> Convoys {
+        self.convoys
+    }
+
+    /// Iterate over the regions in the table, in sorted order.
+    pub fn regions(&self) -> impl Iterator<Item = &RegionKey> {
+        self.regions.iter()
+    }
+
+    /// Get the number of moves needed to get from `from` to `to`. Returns `None` if either
+    /// region isn't in the table, or `to` can't be reached from `from`.
+    pub fn get(&self, from: &RegionKey, to: &RegionKey) -> Option<usize> {
+        let row = self.index_of(from)?;
+        let column = self.index_of(to)?;
+        self.distances[row * self.regions.len() + column]
+    }
+
+    /// Iterate over the regions that can be reached from `from` in at most `moves` moves, with
+    /// the distance to each, in sorted order of region.
+    pub fn within<'a>(
+        &'a self,
+        from: &RegionKey,
+        moves: usize,
+    ) -> impl 'a + Iterator<Item = (&'a RegionKey, usize)> {
+        let row = self
+            .index_of(from)
+            .map(|row| &self.distances[row * self.regions.len()..(row + 1) * self.regions.len()])
+            .unwrap_or_default();
+
+        self.regions
+            .iter()
+            .zip(row)
+            .filter_map(move |(region, distance)| match distance {
+                Some(distance) if *distance <= moves => Some((region, *distance)),
+                _ => None,
+            })
+    }
+
+    fn index_of(&self, region: &RegionKey) -> Option<usize> {
+        self.regions
+            .binary_search(&node(self.unit_type, region))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Convoys;
+    use crate::geo::{standard_map, Coast, RegionKey};
+    use crate::{ShortName, UnitType};
+
+    fn reg(s: &str) -> RegionKey {
+        RegionKey::new(s, None)
+    }
+
+    #[test]
+    fn army_paths() {
+        let map = standard_map();
+        assert_eq!(
+            Some(2),
+            map.distance(UnitType::Army, Convoys::Ignored, &reg("par"), &reg("mun"))
+        );
+        assert_eq!(
+            None,
+            map.distance(UnitType::Army, Convoys::Ignored, &reg("lon"), &reg("par"))
+        );
+        let path = map
+            .shortest_path(UnitType::Army, Convoys::Counted, &reg("lon"), &reg("par"))
+            .unwrap();
+        assert_eq!(4, path.len());
+        assert_eq!(
+            vec![&reg("lon"), &reg("eng"), &reg("par")],
+            vec![&path[0], &path[1], &path[3]]
+        );
+    }
+
+    #[test]
+    fn fleet_paths_use_coasts() {
+        let map = standard_map();
+        let stp_nc = RegionKey::new("stp", Some(Coast::North));
+        let stp_sc = RegionKey::new("stp", Some(Coast::South));
+
+        assert_eq!(
+            Some(1),
+            map.distance(UnitType::Fleet, Convoys::Ignored, &stp_sc, &reg("bot"))
+        );
+        assert_eq!(
+            Some(vec![stp_nc.clone(), reg("bar")]),
+            map.shortest_path(UnitType::Fleet, Convoys::Ignored, &stp_nc, &reg("bar"))
+        );
+        assert_eq!(
+            None,
+            map.distance(UnitType::Fleet, Convoys::Ignored, &reg("bre"), &reg("par"))
+        );
+    }
+
+    #[test]
+    fn distance_table_matches_queries() {
+        let map = standard_map();
+        let table = map.distance_table(UnitType::Army, Convoys::Ignored);
+
+        assert!(table.regions().all(|region| region.coast().is_none()));
+        assert_eq!(Some(2), table.get(&reg("par"), &reg("mun")));
+        assert_eq!(
+            Some(0),
+            table.get(&reg("spa"), &RegionKey::new("spa", Coast::North))
+        );
+        assert_eq!(None, table.get(&reg("lon"), &reg("par")));
+        assert_eq!(None, table.get(&reg("nth"), &reg("lon")));
+
+        let mut nearby = table
+            .within(&reg("bur"), 1)
+            .map(|(region, _)| region.short_name().into_owned())
+            .collect::<Vec<_>>();
+        nearby.sort();
+        assert_eq!(
+            vec!["bel", "bur", "gas", "mar", "mun", "par", "pic", "ruh"],
+            nearby
+        );
+    }
+}
@@ -5,6 +5,7 @@ use std::borrow::Cow;
 /// The supply-center nature of a province. This information is used in the build phase
 /// to determine how many units a nation can sustain and where new units can be built.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SupplyCenter {
     /// The province does not grant a build to whoever controls it.
     None,
@@ -17,8 +18,11 @@ pub enum SupplyCenter {
 
 /// A controllable area of the environment.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Province {
     pub short_name: String,
+    /// The name of the province as shown to players, such as "Mid-Atlantic Ocean".
+    pub full_name: String,
     pub supply_center: SupplyCenter,
 }
 
@@ -52,7 +56,7 @@ impl ProvinceKey {
     }
 }
 
-impl<'a> From<&'a Province> for ProvinceKey {
+impl From<&Province> for ProvinceKey {
     fn from(p: &Province) -> Self {
         ProvinceKey(p.short_name().into_owned())
     }
@@ -64,7 +68,7 @@ impl From<String> for ProvinceKey {
     }
 }
 
-impl<'a> From<&'a str> for ProvinceKey {
+impl From<&str> for ProvinceKey {
     fn from(s: &str) -> Self {
         ProvinceKey(String::from(s))
     }
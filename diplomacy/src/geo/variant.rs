@@ -0,0 +1,204 @@
+//! Built-in game variants. Each variant pairs a map with the units that begin the game on it.
+//!
+//! Variants that use the standard board share the standard map's regions and borders, and
+//! may assign home supply centers differently. Variants played on other boards can be loaded
+//! with `Map::from_reader`.
+//!
+//! Ancient Mediterranean, 1900 and Youngstown are not built in yet. Each is played on its own
+//! board, and adding one means adding its provinces, regions, borders and starting units
+//! beside the standard map's, with tests that load them.
+
+use crate::geo::builder::check_starting_units;
+use crate::geo::{loader, standard_map, Map, RegionKey, SupplyCenter};
+use crate::{Calendar, Nation, Phase, Season, Time, UnitPosition};
+use lazy_static::lazy_static;
+use std::collections::BTreeSet;
+
+lazy_static! {
+    static ref STANDARD: Variant = Variant::new(
+        "Standard",
+        standard_map(),
+        Time::new(Season::Spring, 1901, Phase::Main),
+        include_str!("units.txt"),
+    );
+    static ref FLEET_ROME: Variant = Variant::new(
+        "Fleet Rome",
+        standard_map(),
+        Time::new(Season::Spring, 1901, Phase::Main),
+        include_str!("variants/fleet_rome/units.txt"),
+    );
+    static ref CHAOS_MAP: Map = loader::load_csv(
+        include_str!("variants/chaos/provinces.csv"),
+        include_str!("regions.csv"),
+        include_str!("borders.csv"),
+    )
+    .expect("chaos map shouldn't have issues");
+    static ref CHAOS: Variant = Variant::new(
+        "Chaos",
+        &CHAOS_MAP,
+        Time::new(Season::Winter, 1900, Phase::Build),
+        ""
+    );
+}
+
+/// A map and the units on it at the start of the game.
+#[derive(Debug)]
+pub struct Variant {
+    name: &'static str,
+    map: &'static Map,
+    start: Time,
+    starting_units: Vec<UnitPosition<'static, RegionKey>>,
+}
+
+impl Variant {
+    /// Create a variant, checking its starting units against the map. Variants that start
+    /// with a build phase have no units on the board, so they aren't checked.
+    fn new(name: &'static str, map: &'static Map, start: Time, units: &str) -> Self {
+        let starting_units = units
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                line.parse()
+                    .unwrap_or_else(|_| panic!("{} has an invalid starting unit", name))
+            })
+            .collect::<Vec<_>>();
+
+        if start.phase() != Phase::Build {
+            let problems = check_starting_units(map, &starting_units);
+            assert!(
+                problems.is_empty(),
+                "{} has invalid starting units: {:?}",
+                name,
+                problems
+            );
+        }
+
+        Self {
+            name,
+            map,
+            start,
+            starting_units,
+        }
+    }
+
+    /// The name of the variant.
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// The map on which the variant is played.
+    pub fn map(&self) -> &'static Map {
+        self.map
+    }
+
+    /// The first phase of the game.
+    pub fn start(&self) -> &Time {
+        &self.start
+    }
+
+    /// The calendar of the variant, starting with the first phase of the game. Every variant
+    /// has spring and fall main phases, each followed by a retreat phase, and a winter build
+    /// phase.
+    pub fn calendar(&self) -> Calendar {
+        Calendar::new(
+            self.start.clone(),
+            vec![
+                (Season::Spring, Phase::Main),
+                (Season::Fall, Phase::Main),
+                (Season::Winter, Phase::Build),
+            ],
+        )
+        .expect("variant calendar should be valid")
+    }
+
+    /// The units on the board at the start of the game.
+    pub fn starting_units(&self) -> &[UnitPosition<'static, RegionKey>] {
+        &self.starting_units
+    }
+
+    /// The nations playing the variant, which are the nations with at least one home supply
+    /// center on the map.
+    pub fn nations(&self) -> BTreeSet<&Nation> {
+        self.map
+            .provinces()
+            .filter_map(|province| match &province.supply_center {
+                SupplyCenter::Home(nation) => Some(nation),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// The standard game: seven nations on the standard map.
+pub fn standard() -> &'static Variant {
+    &STANDARD
+}
+
+/// The standard game, except Italy starts with a fleet in Rome instead of an army.
+pub fn fleet_rome() -> &'static Variant {
+    &FLEET_ROME
+}
+
+/// The standard map divided among 34 nations, each of which has one supply center as its
+/// only home. Nations are named for their home province, such as `PAR` for Paris.
+///
+/// There are no units on the board at the start of the game; each nation builds its first
+/// unit in an initial build phase.
+pub fn chaos() -> &'static Variant {
+    &CHAOS
+}
+
+/// Get all the built-in variants.
+pub fn all() -> Vec<&'static Variant> {
+    vec![standard(), fleet_rome(), chaos()]
+}
+
+/// Find a built-in variant by name, ignoring case.
+pub fn find(name: &str) -> Option<&'static Variant> {
+    all()
+        .into_iter()
+        .find(|variant| variant.name.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geo::builder::MapError;
+    use crate::UnitType;
+
+    #[test]
+    fn standard_variant() {
+        let variant = standard();
+        assert_eq!(7, variant.nations().len());
+        assert_eq!(22, variant.starting_units().len());
+        assert!(check_starting_units(variant.map(), variant.starting_units()).is_empty());
+    }
+
+    #[test]
+    fn fleet_rome_variant() {
+        let rome = RegionKey::new("rom", None);
+        let unit = fleet_rome()
+            .starting_units()
+            .iter()
+            .find(|unit| unit.region == rome)
+            .unwrap();
+
+        assert_eq!(UnitType::Fleet, unit.unit.unit_type());
+        assert!(check_starting_units(fleet_rome().map(), fleet_rome().starting_units()).is_empty());
+    }
+
+    #[test]
+    fn chaos_variant() {
+        let variant = chaos();
+        assert_eq!(34, variant.nations().len());
+        assert!(variant.starting_units().is_empty());
+        assert_eq!(
+            34,
+            check_starting_units(variant.map(), variant.starting_units())
+                .iter()
+                .filter(|problem| matches!(problem, MapError::HomeCenterWithoutUnits(_)))
+                .count()
+        );
+        assert_eq!(Some(variant.name()), find("chaos").map(|v| v.name()));
+    }
+}
@@ -1,14 +1,17 @@
 //! Resolver for build phases.
 
-use super::{MappedBuildOrder, OrderState};
-use crate::geo::{Map, ProvinceKey, RegionKey, SupplyCenter};
+use super::{report, MappedBuildOrder, OrderState};
+use crate::geo::{Coast, Convoys, Map, ProvinceKey, RegionKey, SupplyCenter};
 use crate::order::BuildCommand;
-use crate::{Nation, ShortName, UnitType};
+use crate::{Nation, ShortName, Unit, UnitPosition, UnitType};
+use std::borrow::Cow;
+use std::cmp::Reverse;
 use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 
 /// The outcome of a build-turn order.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OrderOutcome {
     /// The build or disband order was successful, resulting in a change in units
     /// in the world.
@@ -24,12 +27,21 @@ pub enum OrderOutcome {
     ForeignControlled,
     /// Build failed because the target province already has a friendly unit in it.
     OccupiedProvince,
-    /// The build command is to a region that is qualified for
+    /// The build command is to a region the unit type cannot occupy, such as a fleet in an
+    /// inland province or an army on a named coast.
     InvalidTerrain,
+    /// The build command is for a fleet in a province with multiple coasts, but did not say
+    /// which coast the fleet should be built on.
+    UnspecifiedCoast,
+    /// Another valid build command earlier in the phase is for the same province. Only one
+    /// unit can be built in a province, even if the two commands name different coasts.
+    MultipleBuildsInProvince,
     /// Disband failed because no unit exists at that location.
     DisbandingNonexistentUnit,
     /// Disband failed because the issuing power does not control the unit at that location.
     DisbandingForeignUnit,
+    /// Another valid disband command earlier in the phase is for the same unit.
+    MultipleDisbandsForUnit,
     /// The issuing nation has already had as many successful builds as they are allowed.
     AllBuildsUsed,
     /// The issuing nation has already had as many successful disbands as they are allowed.
@@ -123,6 +135,26 @@ impl<'a, W: WorldState> ResolverContext<'a, W> {
             .or_else(|| self.last_time.get(province))
     }
 
+    /// Get the home supply centers of `nation` which it currently controls. If the nation
+    /// controls none of them, all of its home supply centers are returned instead.
+    fn owned_home_scs(&'a self, nation: &Nation) -> HashSet<&'a ProvinceKey> {
+        let homes = match self.home_scs.get(nation) {
+            Some(homes) => homes,
+            None => return HashSet::new(),
+        };
+
+        let owned = homes
+            .iter()
+            .filter(|&home| self.current_owner(home) == Some(nation))
+            .collect::<HashSet<_>>();
+
+        if owned.is_empty() {
+            homes.iter().collect()
+        } else {
+            owned
+        }
+    }
+
     pub fn resolve(&'a self) -> Outcome<'a> {
         Resolution::new(self).resolve(self)
     }
@@ -130,7 +162,12 @@ impl<'a, W: WorldState> ResolverContext<'a, W> {
 
 struct Resolution<'a> {
     deltas: HashMap<&'a Nation, (BuildCommand, i16)>,
-    state: HashMap<&'a MappedBuildOrder, OrderOutcome>,
+    /// The outcome of checking each order on its own merits. This is index-aligned with the
+    /// context's orders.
+    adjudications: Vec<OrderOutcome>,
+    /// The final outcome of each order, once resolved. This is index-aligned with the context's
+    /// orders, so that an order submitted twice is resolved twice.
+    state: Vec<Option<OrderOutcome>>,
     civil_disorder: HashSet<(UnitType, RegionKey)>,
     final_units: HashMap<&'a Nation, HashSet<(UnitType, RegionKey)>>,
 }
@@ -144,10 +181,17 @@ impl<'a> Resolution<'a> {
             .map(|nation| (nation, context.this_time.units(nation)))
             .collect();
 
+        // Nations that control no supply centers have no ownership entry, but must still
+        // disband all of their units.
         let deltas = context
             .ownerships
-            .iter()
-            .filter_map(|(&nation, ownerships)| {
+            .keys()
+            .copied()
+            .chain(context.this_time.nations())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .filter_map(|nation| {
+                let ownerships = context.ownerships.get(nation).copied().unwrap_or(0);
                 let adjustment = ownerships - context.this_time.unit_count(nation) as i16;
                 match adjustment {
                     0 => None,
@@ -159,15 +203,16 @@ impl<'a> Resolution<'a> {
 
         Resolution {
             deltas,
-            state: HashMap::with_capacity(context.orders.len()),
+            adjudications: adjudicate_phase(context),
+            state: vec![None; context.orders.len()],
             civil_disorder: HashSet::new(),
             final_units,
         }
     }
 
     pub fn resolve(mut self, context: &'a ResolverContext<impl WorldState>) -> Outcome<'a> {
-        for order in &context.orders {
-            self.resolve_order(context, order);
+        for (idx, order) in context.orders.iter().enumerate() {
+            self.resolve_order(idx, order);
         }
 
         for (nation, delta) in &mut self.deltas {
@@ -176,101 +221,221 @@ impl<'a> Resolution<'a> {
             }
 
             let usize_delta: usize = delta.1.try_into().unwrap();
-            let units = self.final_units.remove(nation).unwrap();
-
-            for unit in units.clone().into_iter().take(usize_delta) {
-                self.civil_disorder.insert(unit);
-            }
+            let mut units = self
+                .final_units
+                .remove(nation)
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>();
+
+            // Units are removed in the order described by the rulebook: farthest from an
+            // owned home supply center first, then fleets before armies, then alphabetically
+            // by the full name of their province.
+            let homes = context.owned_home_scs(nation);
+            units.sort_by_cached_key(|(unit_type, region)| {
+                (
+                    Reverse(
+                        distance_to_home(context.world, &homes, *unit_type, region)
+                            .unwrap_or(usize::MAX),
+                    ),
+                    *unit_type != UnitType::Fleet,
+                    context
+                        .world
+                        .find_province(&region.province().short_name())
+                        .map(|province| province.full_name.clone()),
+                    region.short_name().into_owned(),
+                )
+            });
+
+            let kept = units.split_off(usize_delta.min(units.len()));
+            self.civil_disorder.extend(units);
+            self.final_units.insert(nation, kept.into_iter().collect());
+        }
 
-            self.final_units
-                .insert(nation, units.into_iter().skip(usize_delta).collect());
+        let outcomes = context
+            .orders
+            .iter()
+            .copied()
+            .zip(self.state.into_iter().map(Option::unwrap))
+            .collect::<Vec<_>>();
+        let mut orders = HashMap::with_capacity(outcomes.len());
+        for &(order, outcome) in &outcomes {
+            orders.entry(order).or_insert(outcome);
         }
 
         Outcome {
-            orders: self.state,
+            orders,
+            outcomes,
             final_units: self.final_units,
             civil_disorder: self.civil_disorder,
         }
     }
 
-    fn resolve_order(
-        &mut self,
-        context: &'a ResolverContext<impl WorldState>,
-        order: &'a MappedBuildOrder,
-    ) -> OrderOutcome {
+    fn resolve_order(&mut self, idx: usize, order: &'a MappedBuildOrder) -> OrderOutcome {
         use self::OrderOutcome::*;
 
         // We already know the answer to this one
-        if let Some(outcome) = self.state.get(order) {
-            return *outcome;
+        if let Some(outcome) = self.state[idx] {
+            return outcome;
         }
 
-        let mut delta = if let Some(delta) = self.deltas.get_mut(&order.nation) {
+        let delta = if let Some(delta) = self.deltas.get_mut(&order.nation) {
             delta
         } else {
-            return self.resolve_as(order, RedeploymentProhibited);
+            return self.resolve_as(idx, RedeploymentProhibited);
         };
 
         // A power is only allowed to build or disband in a given turn, not both
         if delta.0 != order.command {
-            return self.resolve_as(order, RedeploymentProhibited);
+            return self.resolve_as(idx, RedeploymentProhibited);
         }
 
-        let adjudication = adjudicate(context, order);
+        let adjudication = self.adjudications[idx];
 
         if adjudication != OrderOutcome::Succeeds {
-            return self.resolve_as(order, adjudication);
+            return self.resolve_as(idx, adjudication);
         }
 
         match order.command {
             BuildCommand::Build => {
                 if delta.1 == 0 {
-                    return self.resolve_as(order, AllBuildsUsed);
+                    return self.resolve_as(idx, AllBuildsUsed);
                 }
 
                 delta.1 -= 1;
 
                 self.final_units
                     .entry(&order.nation)
-                    .or_insert_with(HashSet::new)
+                    .or_default()
                     .insert((order.unit_type, order.region.clone()));
 
-                self.resolve_as(order, Succeeds)
+                self.resolve_as(idx, Succeeds)
             }
             BuildCommand::Disband => {
                 if delta.1 == 0 {
-                    return self.resolve_as(order, AllDisbandsUsed);
+                    return self.resolve_as(idx, AllDisbandsUsed);
                 }
 
                 delta.1 -= 1;
 
+                // The order may not name the coast of the disbanded fleet, so the unit is
+                // found by province.
                 self.final_units
                     .entry(&order.nation)
-                    .or_insert_with(HashSet::new)
-                    .remove(&(order.unit_type, order.region.clone()));
+                    .or_default()
+                    .retain(|(unit_type, region)| {
+                        *unit_type != order.unit_type
+                            || region.province() != order.region.province()
+                    });
 
-                self.resolve_as(order, Succeeds)
+                self.resolve_as(idx, Succeeds)
             }
         }
     }
 
-    fn resolve_as(
-        &mut self,
-        order: &'a MappedBuildOrder,
-        resolution: OrderOutcome,
-    ) -> OrderOutcome {
-        self.state.insert(order, resolution);
+    fn resolve_as(&mut self, idx: usize, resolution: OrderOutcome) -> OrderOutcome {
+        self.state[idx] = Some(resolution);
         resolution
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct Outcome<'a> {
+    /// The outcome of each distinct order. An order submitted more than once has the outcome
+    /// of its first copy.
     pub orders: HashMap<&'a MappedBuildOrder, OrderOutcome>,
+    /// The outcome of every order, in the order they were passed to `ResolverContext::new`.
+    pub outcomes: Vec<(&'a MappedBuildOrder, OrderOutcome)>,
     pub civil_disorder: HashSet<(UnitType, RegionKey)>,
     pub final_units: HashMap<&'a Nation, HashSet<(UnitType, RegionKey)>>,
 }
 
+impl Outcome<'_> {
+    /// Create a copy of the outcome which owns its orders and nations.
+    pub fn into_owned(self) -> OwnedOutcome {
+        OwnedOutcome {
+            orders: self
+                .orders
+                .into_iter()
+                .map(|(order, outcome)| (order.clone(), outcome))
+                .collect(),
+            outcomes: self
+                .outcomes
+                .into_iter()
+                .map(|(order, outcome)| (order.clone(), outcome))
+                .collect(),
+            civil_disorder: self.civil_disorder,
+            final_units: self
+                .final_units
+                .into_iter()
+                .map(|(nation, units)| (nation.clone(), units))
+                .collect(),
+        }
+    }
+
+    /// Create an owned report of the outcome of every order and the resulting unit positions.
+    pub fn to_report(&self) -> report::BuildPhaseReport {
+        let mut orders = self
+            .outcomes
+            .iter()
+            .map(|&(order, outcome)| report::OrderReport {
+                order: order.clone(),
+                outcome,
+            })
+            .collect::<Vec<_>>();
+        report::sort_reports(&mut orders);
+
+        let mut civil_disorder = self.civil_disorder.iter().cloned().collect::<Vec<_>>();
+        civil_disorder.sort();
+
+        report::BuildPhaseReport {
+            orders,
+            civil_disorder,
+            units: report::owned_positions(self.final_units.iter().flat_map(|(&nation, units)| {
+                units.iter().map(move |(unit_type, region)| {
+                    UnitPosition::new(Unit::new(Cow::Borrowed(nation), *unit_type), region)
+                })
+            })),
+        }
+    }
+}
+
+/// The outcome of a build phase, owning its orders and nations so it can outlive them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedOutcome {
+    /// The outcome of each distinct order. An order submitted more than once has the outcome
+    /// of its first copy.
+    pub orders: HashMap<MappedBuildOrder, OrderOutcome>,
+    /// The outcome of every order, in the order they were submitted.
+    pub outcomes: Vec<(MappedBuildOrder, OrderOutcome)>,
+    pub civil_disorder: HashSet<(UnitType, RegionKey)>,
+    pub final_units: HashMap<Nation, HashSet<(UnitType, RegionKey)>>,
+}
+
+/// Check every order in the phase on its own merits, then reject any valid order for a province
+/// that an earlier valid order in the phase already targeted. A second copy of the same order
+/// is rejected like any other.
+///
+/// The outcomes are index-aligned with the context's orders.
+fn adjudicate_phase(context: &ResolverContext<impl WorldState>) -> Vec<OrderOutcome> {
+    let mut adjudications = Vec::with_capacity(context.orders.len());
+    let mut targeted = HashSet::new();
+
+    for &order in &context.orders {
+        let mut outcome = adjudicate(context, order);
+        if outcome == OrderOutcome::Succeeds && !targeted.insert(order.region.province()) {
+            outcome = match order.command {
+                BuildCommand::Build => OrderOutcome::MultipleBuildsInProvince,
+                BuildCommand::Disband => OrderOutcome::MultipleDisbandsForUnit,
+            };
+        }
+
+        adjudications.push(outcome);
+    }
+
+    adjudications
+}
+
 /// Rulebook function for build-phase adjudication. This function does not worry about order quantities,
 /// and just focuses on whether or not a given build or disband command is otherwise valid.
 fn adjudicate(
@@ -307,7 +472,12 @@ fn adjudicate(
             };
 
             if !order.unit_type.can_occupy(region.terrain()) {
-                return InvalidTerrain;
+                return if order.unit_type == UnitType::Fleet && has_coasts(context.world, province)
+                {
+                    UnspecifiedCoast
+                } else {
+                    InvalidTerrain
+                };
             }
 
             Succeeds
@@ -315,11 +485,53 @@ fn adjudicate(
         BuildCommand::Disband => match context.this_time.occupier(province) {
             None => DisbandingNonexistentUnit,
             Some(nation) if &order.nation != nation => DisbandingForeignUnit,
-            _ => Succeeds,
+            Some(nation) => {
+                let has_unit = context
+                    .this_time
+                    .units(nation)
+                    .iter()
+                    .any(|(unit_type, region)| {
+                        *unit_type == order.unit_type && region.province() == province
+                    });
+
+                if has_unit {
+                    Succeeds
+                } else {
+                    DisbandingNonexistentUnit
+                }
+            }
         },
     }
 }
 
+/// Count the moves a unit in `region` would need to reach any of `homes`, or `None` if it can't
+/// reach any of them.
+///
+/// Fleets move as they would during a main phase, and may arrive on any coast of a home
+/// province. Armies may also cross sea provinces as though convoyed, whether or not there are
+/// fleets in position to do so; each sea province crossed counts as a move.
+fn distance_to_home(
+    world: &Map,
+    homes: &HashSet<&ProvinceKey>,
+    unit_type: UnitType,
+    region: &RegionKey,
+) -> Option<usize> {
+    world.distance_to_nearest(unit_type, Convoys::Counted, region, |region| {
+        homes.contains(region.province())
+    })
+}
+
+/// Returns true if `province` is divided into separately-named coasts.
+fn has_coasts(world: &Map, province: &ProvinceKey) -> bool {
+    [Coast::North, Coast::East, Coast::South, Coast::West]
+        .iter()
+        .any(|&coast| {
+            world
+                .find_region(&RegionKey::new(province.clone(), coast).short_name())
+                .is_some()
+        })
+}
+
 /// Convert a map into an initial ownership state where each nation owns their home
 /// supply centers and all other supply centers are unowned.
 pub fn to_initial_ownerships(map: &Map) -> HashMap<ProvinceKey, Nation> {
@@ -336,9 +548,9 @@ pub fn to_initial_ownerships(map: &Map) -> HashMap<ProvinceKey, Nation> {
 
 #[cfg(test)]
 mod tests {
-    use super::to_initial_ownerships;
-    use crate::geo::{standard_map, ProvinceKey};
-    use crate::Nation;
+    use super::{distance_to_home, to_initial_ownerships};
+    use crate::geo::{standard_map, Coast, ProvinceKey, RegionKey};
+    use crate::{Nation, UnitType};
 
     #[test]
     fn to_initial_ownerships_for_standard_map() {
@@ -351,4 +563,27 @@ mod tests {
 
         assert_eq!(None, ownerships.get(&ProvinceKey::from("bel")));
     }
+
+    #[test]
+    fn distance_to_home_by_unit_type() {
+        let stp = ProvinceKey::from("stp");
+        let homes = vec![&stp].into_iter().collect();
+        let distance = |unit_type, region: &str, coast: Option<Coast>| {
+            distance_to_home(
+                standard_map(),
+                &homes,
+                unit_type,
+                &RegionKey::new(region, coast),
+            )
+        };
+
+        assert_eq!(
+            Some(0),
+            distance(UnitType::Fleet, "stp", Some(Coast::North))
+        );
+        assert_eq!(Some(1), distance(UnitType::Fleet, "bot", None));
+        assert_eq!(Some(2), distance(UnitType::Fleet, "nth", None));
+        assert_eq!(Some(2), distance(UnitType::Army, "swe", None));
+        assert_eq!(None, distance(UnitType::Fleet, "mos", None));
+    }
 }
@@ -1,6 +1,8 @@
-use super::strength::{Prevent, Strength};
+use super::strength::{Counted, Prevent, Strength};
 use super::{convoy, support};
-use super::{Adjudicate, Context, MappedMainOrder, ResolverState};
+use super::{
+    Adjudicate, Context, ConvoyToAdjacent, MappedMainOrder, OrderState, ParadoxRule, ResolverState,
+};
 use crate::order::{Command, MainCommand};
 use crate::{geo::ProvinceKey, ShortName};
 
@@ -19,12 +21,12 @@ pub fn path_exists<'a>(
             if order.unit_type.can_occupy(reg.terrain()) {
                 // If the move order allows direct travel, look for a border that would support
                 // direct movement.
-                let can_travel_directly = !cmd.mandates_convoy()
-                    && context
-                        .world_map
-                        .find_border_between(&order.region, dst)
-                        .map(|b| b.is_passable_by(order.unit_type))
-                        .unwrap_or(false);
+                let can_travel_directly =
+                    !mandates_convoy(context, order) && has_direct_border(context, order);
+
+                if convoy::forbids_convoy(order) {
+                    return can_travel_directly;
+                }
 
                 // NOTE: As-written, this short-circuits convoy assessment when
                 // there is an acceptable direct route. Don't change that behavior, as
@@ -38,6 +40,51 @@ pub fn path_exists<'a>(
     false
 }
 
+/// Returns true if a border passable by the unit connects `order`'s region to its destination.
+fn has_direct_border(context: &Context<'_, impl Adjudicate>, order: &MappedMainOrder) -> bool {
+    order
+        .move_dest()
+        .and_then(|dst| context.world_map.find_border_between(&order.region, dst))
+        .map(|b| b.is_passable_by(order.unit_type))
+        .unwrap_or(false)
+}
+
+/// Returns true if `order` is a move that must travel by convoy even if a direct border exists.
+///
+/// This is the case when the order says "via convoy", or when the rules infer convoy intent from
+/// a fleet of the same nation ordered to convoy the army.
+fn mandates_convoy(context: &Context<'_, impl Adjudicate>, order: &MappedMainOrder) -> bool {
+    match &order.command {
+        MainCommand::Move(cmd) if cmd.mandates_convoy() => true,
+        MainCommand::Move(cmd) if cmd.forbids_convoy() => false,
+        MainCommand::Move(_) => {
+            context.rules.convoy_to_adjacent() == ConvoyToAdjacent::Intent
+                && context
+                    .convoys_for(order)
+                    .any(|convoy| convoy.nation == order.nation)
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if `order` is a move that can only reach its destination by convoy, either
+/// because it requires one or because no direct border is passable by the unit.
+pub fn is_convoyed(context: &Context<'_, impl Adjudicate>, order: &MappedMainOrder) -> bool {
+    order.is_move()
+        && !convoy::forbids_convoy(order)
+        && (mandates_convoy(context, order) || !has_direct_border(context, order))
+}
+
+/// Returns true if `order` would be carried by a convoy route if one exists. Under the
+/// kidnapping rule, any convoy route counts, even for an army that could move over land,
+/// unless the order explicitly forbids a convoy.
+pub fn uses_convoy(context: &Context<'_, impl Adjudicate>, order: &MappedMainOrder) -> bool {
+    order.is_move()
+        && !convoy::forbids_convoy(order)
+        && (context.rules.convoy_to_adjacent() == ConvoyToAdjacent::Kidnapping
+            || is_convoyed(context, order))
+}
+
 /// Two orders form a head-to-head battle when they are mirrored moves and no convoy exists to
 /// ferry one of the armies around the other one.
 pub fn is_head_to_head<'a>(
@@ -51,11 +98,12 @@ pub fn is_head_to_head<'a>(
         && order1.move_dest().map(|d| d.province()) == Some(order2.region.province())
         && order2.move_dest().map(|d| d.province()) == Some(order1.region.province()))
     // Then check to see if a convoy route enables the two to avoid head-to-head battle
-        && !convoy::route_exists(context, resolver, order1)
-        && !convoy::route_exists(context, resolver, order2)
+        && !(uses_convoy(context, order1) && convoy::route_exists(context, resolver, order1))
+        && !(uses_convoy(context, order2) && convoy::route_exists(context, resolver, order2))
 }
 
-fn prevent_result<'a>(
+/// Get the prevent strength of `order`, or `None` if it isn't a move.
+pub fn prevent_result<'a>(
     context: &Context<'a, impl Adjudicate>,
     resolver: &mut ResolverState<'a>,
     order: &'a MappedMainOrder,
@@ -66,7 +114,7 @@ fn prevent_result<'a>(
         } else {
             // A unit that lost a head-to-head cannot prevent.
             if let Some(h2h) = context
-                .orders()
+                .moves_to_province(order.region.province())
                 .find(|o| is_head_to_head(context, resolver, o, order))
             {
                 if resolver.resolve(context, h2h).into() {
@@ -91,8 +139,7 @@ pub fn prevent_results<'a>(
     province: &ProvinceKey,
 ) -> Vec<Prevent<'a>> {
     context
-        .orders()
-        .filter(|ord| ord.is_move_to_province(province))
+        .moves_to_province(province)
         .filter_map(|ord| prevent_result(context, resolver, ord))
         .collect()
 }
@@ -106,13 +153,13 @@ pub fn max_prevent_result<'a>(
         let mut best_prevent = None;
         let mut best_prevent_strength = 0;
         for order in context
-            .orders()
-            .filter(|ord| ord != &preventing && ord.is_move_to_province(dst.into()))
+            .moves_to_province(dst.province())
+            .filter(|ord| ord != &preventing)
         {
             if is_head_to_head(context, resolver, order, preventing)
                 && resolver.resolve(context, order).into()
             {
-                if best_prevent == None {
+                if best_prevent.is_none() {
                     best_prevent = Some(Prevent::LostHeadToHead);
                 }
                 continue;
@@ -129,6 +176,86 @@ pub fn max_prevent_result<'a>(
     })
 }
 
+/// The ATTACK strength of a move into its destination, with the supports counted toward it.
+///
+/// A move that can't reach its destination, or that is part of a paradox under
+/// `ParadoxRule::AllHold`, has no attack strength. If a unit stays in the destination, a move
+/// by the same nation has no attack strength, and supports from the staying unit's nation are
+/// not counted.
+pub fn attack_strength<'a>(
+    context: &Context<'a, impl Adjudicate>,
+    resolver: &mut ResolverState<'a>,
+    order: &'a MappedMainOrder,
+) -> Counted<'a> {
+    let dest = match order.move_dest() {
+        Some(dest) if dest != &order.region => dest,
+        _ => return (0, vec![]),
+    };
+
+    if (context.rules.paradox_rule() == ParadoxRule::AllHold && resolver.order_in_paradox(order))
+        || !path_exists(context, resolver, order)
+    {
+        return (0, vec![]);
+    }
+
+    let mut supports = support::find_for(context, resolver, order);
+    if let Some(occupier) = context.find_order_to_province(dest.province()) {
+        let stays = !occupier.is_move()
+            || is_head_to_head(context, resolver, order, occupier)
+            || resolver.resolve(context, occupier) == OrderState::Fails;
+
+        if stays && occupier.nation == order.nation {
+            return (0, vec![]);
+        } else if stays {
+            supports.retain(|sup| sup.nation != occupier.nation);
+        }
+    }
+
+    (1 + supports.len(), supports)
+}
+
+/// The HOLD strength of the province `order` started in, with the supports counted toward it.
+/// A unit that moved away doesn't hold the province, and one that failed to move away holds
+/// it without support.
+pub fn hold_strength<'a>(
+    context: &Context<'a, impl Adjudicate>,
+    resolver: &mut ResolverState<'a>,
+    order: &'a MappedMainOrder,
+) -> Counted<'a> {
+    if !order.is_move() {
+        let supports = support::find_for(context, resolver, order);
+        (1 + supports.len(), supports)
+    } else if resolver.resolve(context, order) == OrderState::Fails {
+        (1, vec![])
+    } else {
+        (0, vec![])
+    }
+}
+
+/// The DEFEND strength of a move, used against the other unit in a head-to-head battle, with
+/// the supports counted toward it. Every successful support is counted, whatever its nation.
+pub fn defend_strength<'a>(
+    context: &Context<'a, impl Adjudicate>,
+    resolver: &mut ResolverState<'a>,
+    order: &'a MappedMainOrder,
+) -> Counted<'a> {
+    let supports = support::find_for(context, resolver, order);
+    (1 + supports.len(), supports)
+}
+
+/// The PREVENT strength of a move, used against other moves to the same province, with the
+/// supports counted toward it.
+pub fn prevent_strength<'a>(
+    context: &Context<'a, impl Adjudicate>,
+    resolver: &mut ResolverState<'a>,
+    order: &'a MappedMainOrder,
+) -> Counted<'a> {
+    match prevent_result(context, resolver, order) {
+        Some(Prevent::Prevents(_, supports)) => (1 + supports.len(), supports),
+        _ => (0, vec![]),
+    }
+}
+
 /// Get the order that dislodges the provided order, if one exists.
 ///
 /// A DISLODGE decision of a unit results in 'dislodged' when:
@@ -140,10 +267,7 @@ pub fn dislodger_of<'a>(
     resolver: &mut ResolverState<'a>,
     order: &'a MappedMainOrder,
 ) -> Option<&'a MappedMainOrder> {
-    for would_be_dislodger in context
-        .orders()
-        .filter(|o| o.is_move_to_province(order.region.province()))
-    {
+    for would_be_dislodger in context.moves_to_province(order.region.province()) {
         // If we found someone trying to move into `order`'s old province, we
         // check to see if `order` vacated. If so, then it couldn't have been
         // dislodged.
@@ -180,7 +304,7 @@ mod tests {
         .map(|ord| ord.parse::<MappedMainOrder>().unwrap())
         .collect::<Vec<_>>();
 
-        let context = Context::new(crate::geo::standard_map(), Rulebook, &orders);
+        let context = Context::new(crate::geo::standard_map(), Rulebook::default(), &orders);
         let mut state = ResolverState::new();
 
         assert_eq!(
@@ -204,7 +328,7 @@ mod tests {
         .map(|ord| ord.parse::<MappedMainOrder>().unwrap())
         .collect::<Vec<_>>();
 
-        let context = Context::new(crate::geo::standard_map(), Rulebook, &orders);
+        let context = Context::new(crate::geo::standard_map(), Rulebook::default(), &orders);
         let mut state = ResolverState::new();
         let nth_prevent = max_prevent_result(&context, &mut state, &orders[3]);
         let swe_prevent = max_prevent_result(&context, &mut state, &orders[4]);
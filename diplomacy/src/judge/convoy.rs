@@ -2,6 +2,7 @@ use super::{Adjudicate, Context, MappedMainOrder, OrderState, ResolverState};
 use crate::geo::{Map, ProvinceKey};
 use crate::order::{Command, MainCommand};
 use crate::UnitType;
+use std::collections::{HashMap, VecDeque};
 
 /// Failure cases for convoy route lookup.
 pub enum ConvoyRouteError {
@@ -10,6 +11,9 @@ pub enum ConvoyRouteError {
 
     /// Hold, support, and convoy orders cannot be convoyed.
     CanOnlyConvoyMove,
+
+    /// The move order explicitly forbade the use of a convoy.
+    ConvoyForbidden,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -42,61 +46,91 @@ impl From<ConvoyOutcome<'_>> for OrderState {
 
 /// Checks whether `convoy` is a valid convoy that will carry `mv_ord` from
 /// its current location to the destination.
-fn is_convoy_for(convoy: &MappedMainOrder, mv_ord: &MappedMainOrder) -> bool {
+pub(super) fn is_convoy_for(convoy: &MappedMainOrder, mv_ord: &MappedMainOrder) -> bool {
     match &convoy.command {
         MainCommand::Convoy(ref cm) => cm == mv_ord,
         _ => false,
     }
 }
 
-/// Find all routes from `origin` to `dest` given a set of valid convoys.
+/// Checks whether `mv_ord` is a move which explicitly forbids the use of a convoy.
+pub(super) fn forbids_convoy(mv_ord: &MappedMainOrder) -> bool {
+    match &mv_ord.command {
+        MainCommand::Move(cmd) => cmd.forbids_convoy(),
+        _ => false,
+    }
+}
+
+/// Find the route with the fewest fleets from `origin` to `dest` given a set of valid convoys.
+/// The route is listed from the fleet next to `origin` outward.
+///
+/// This is a breadth-first search over the convoying fleets, so each fleet is visited at most
+/// once no matter how many routes pass through it.
 fn route_steps<'a>(
     map: &Map,
     convoys: &[&'a MappedMainOrder],
     origin: &ProvinceKey,
     dest: &ProvinceKey,
-    working_path: Vec<&'a MappedMainOrder>,
-) -> Vec<Vec<&'a MappedMainOrder>> {
-    let adjacent_regions = map.find_bordering(origin);
-    // if we've got a convoy going and there is one hop to the destination,
-    // we've found a valid solution.
-    if !working_path.is_empty() && adjacent_regions.iter().any(|&r| r == dest) {
-        vec![working_path]
-    } else {
-        let mut paths = vec![];
-        for convoy in convoys {
-            // move to adjacent, and don't allow backtracking/cycles
-            if !working_path.contains(&convoy) && adjacent_regions.contains(&&convoy.region) {
-                let mut next_path = working_path.clone();
-                next_path.push(&convoy);
-                let mut steps =
-                    route_steps(map, convoys, convoy.region.province(), dest, next_path);
-                if !steps.is_empty() {
-                    paths.append(&mut steps);
-                }
+) -> Option<Vec<&'a MappedMainOrder>> {
+    // The index in `convoys` of the fleet each visited fleet was reached from, if any.
+    let mut reached_from = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    let start = map.find_bordering(origin).collect::<Vec<_>>();
+    for (index, convoy) in convoys.iter().enumerate() {
+        if start.contains(&&convoy.region) {
+            reached_from.insert(index, None);
+            queue.push_back(index);
+        }
+    }
+
+    while let Some(index) = queue.pop_front() {
+        let adjacent_regions = map
+            .find_bordering(convoys[index].region.province())
+            .collect::<Vec<_>>();
+
+        // One hop from a convoying fleet to the destination completes the route.
+        if adjacent_regions.iter().any(|&r| dest == r) {
+            let mut route = vec![convoys[index]];
+            let mut step = reached_from[&index];
+            while let Some(previous) = step {
+                route.push(convoys[previous]);
+                step = reached_from[&previous];
             }
+
+            route.reverse();
+            return Some(route);
         }
 
-        paths
+        for (next, convoy) in convoys.iter().enumerate() {
+            if !reached_from.contains_key(&next) && adjacent_regions.contains(&&convoy.region) {
+                reached_from.insert(next, Some(index));
+                queue.push_back(next);
+            }
+        }
     }
+
+    None
 }
 
-/// Finds all valid convoy routes for a given move order.
-pub fn routes<'a>(
+/// Finds a convoy route for a given move order through the convoys that weren't disrupted,
+/// using as few fleets as possible. Returns `Ok(None)` if no such route exists.
+pub fn route<'a>(
     ctx: &Context<'a, impl Adjudicate>,
     state: &mut ResolverState<'a>,
     mv_ord: &MappedMainOrder,
-) -> Result<Vec<Vec<&'a MappedMainOrder>>, ConvoyRouteError> {
+) -> Result<Option<Vec<&'a MappedMainOrder>>, ConvoyRouteError> {
     if mv_ord.unit_type == UnitType::Fleet {
         Err(ConvoyRouteError::CanOnlyConvoyArmy)
+    } else if forbids_convoy(mv_ord) {
+        Err(ConvoyRouteError::ConvoyForbidden)
     } else if let Some(dst) = mv_ord.move_dest() {
         // Get the convoy orders that can ferry the provided move order and are
         // successful. Per http://uk.diplom.org/pouch/Zine/S2009M/Kruijswijk/DipMath_Chp6.htm
-        // we resolve all convoy orders eagerly to avoid wild recursion during the depth-first
-        // search.
+        // we resolve all convoy orders eagerly to avoid wild recursion during the search.
         let mut convoy_steps = vec![];
-        for order in ctx.orders() {
-            if is_convoy_for(order, mv_ord) && state.resolve(ctx, order).into() {
+        for order in ctx.convoys_for(mv_ord) {
+            if state.resolve(ctx, order).into() {
                 convoy_steps.push(order);
             }
         }
@@ -106,7 +140,6 @@ pub fn routes<'a>(
             &convoy_steps,
             mv_ord.region.province(),
             dst.province(),
-            vec![],
         ))
     } else {
         Err(ConvoyRouteError::CanOnlyConvoyMove)
@@ -119,15 +152,13 @@ pub fn route_exists<'a>(
     state: &mut ResolverState<'a>,
     mv_ord: &MappedMainOrder,
 ) -> bool {
-    routes(ctx, state, mv_ord)
-        .map(|r| !r.is_empty())
-        .unwrap_or(false)
+    matches!(route(ctx, state, mv_ord), Ok(Some(_)))
 }
 
 #[cfg(test)]
 mod test {
     use crate::geo::{self, ProvinceKey, RegionKey};
-    use crate::judge::MappedMainOrder;
+    use crate::judge::{ConvoyToAdjacent, MappedMainOrder, Rulebook, Submission};
     use crate::order::{ConvoyedMove, Order};
     use crate::UnitType;
 
@@ -146,27 +177,81 @@ mod test {
 
     #[test]
     fn pathfinder() {
-        let convoys = vec![
+        let convoys = [
             convoy("ska", "lon", "swe"),
             convoy("eng", "lon", "swe"),
             convoy("nth", "lon", "swe"),
             convoy("nwg", "lon", "swe"),
         ];
 
-        let routes = super::route_steps(
+        let route = super::route_steps(
             geo::standard_map(),
             &convoys.iter().collect::<Vec<_>>(),
             &ProvinceKey::new("lon"),
             &ProvinceKey::new("swe"),
-            vec![],
         );
-        for r in &routes {
-            println!("CHAIN");
-            for o in r.iter() {
-                println!("  {}", o);
-            }
-        }
 
-        assert_eq!(2, routes.len());
+        assert_eq!(Some(vec![&convoys[2], &convoys[0]]), route);
+    }
+
+    #[test]
+    fn pathfinder_without_route() {
+        let convoys = [convoy("eng", "lon", "swe"), convoy("nwg", "lon", "swe")];
+
+        let route = super::route_steps(
+            geo::standard_map(),
+            &convoys.iter().collect::<Vec<_>>(),
+            &ProvinceKey::new("lon"),
+            &ProvinceKey::new("swe"),
+        );
+
+        assert_eq!(None, route);
+    }
+
+    #[test]
+    fn outcome_reports_route_used() {
+        let orders = vec![
+            "ENG: A lon -> tun",
+            "ENG: F eng convoys lon -> tun",
+            "ENG: F mao convoys lon -> tun",
+            "ENG: F wes convoys lon -> tun",
+            "ENG: F nth convoys lon -> tun",
+            "FRA: A par -> bur",
+        ]
+        .into_iter()
+        .map(|ord| ord.parse::<MappedMainOrder>().unwrap())
+        .collect::<Vec<_>>();
+
+        let submission = Submission::with_inferred_state(orders.clone());
+        let outcome = submission.adjudicate(geo::standard_map(), Rulebook::default());
+
+        assert_eq!(
+            Some(vec![&orders[1], &orders[2], &orders[3]]),
+            outcome.convoy_route(&orders[0])
+        );
+        assert_eq!(None, outcome.convoy_route(&orders[5]));
+    }
+
+    #[test]
+    fn outcome_reports_route_of_kidnapped_army() {
+        let orders = vec!["FRA: A bre -> pic", "ENG: F eng convoys bre -> pic"]
+            .into_iter()
+            .map(|ord| ord.parse::<MappedMainOrder>().unwrap())
+            .collect::<Vec<_>>();
+
+        let submission = Submission::with_inferred_state(orders.clone());
+        let kidnapping = Rulebook {
+            convoy_to_adjacent: ConvoyToAdjacent::Kidnapping,
+            ..Rulebook::default()
+        };
+        let outcome = submission.adjudicate(geo::standard_map(), kidnapping);
+        assert_eq!(Some(vec![&orders[1]]), outcome.convoy_route(&orders[0]));
+
+        let explicit = Rulebook {
+            convoy_to_adjacent: ConvoyToAdjacent::Explicit,
+            ..Rulebook::default()
+        };
+        let outcome = submission.adjudicate(geo::standard_map(), explicit);
+        assert_eq!(None, outcome.convoy_route(&orders[0]));
     }
 }
@@ -0,0 +1,172 @@
+//! Graphs of the dependencies between orders found while resolving a main phase.
+//!
+//! The graph renders as GraphViz DOT with `Display`. With the `serde` feature enabled it can
+//! also be serialized, for example to JSON for a web-based viewer.
+
+use super::{Adjudicate, MappedMainOrder, OrderState, Outcome};
+use crate::order::MainCommand;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// What an order depended on another order to learn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DependencyKind {
+    /// Whether a support was given, which affects the strength of the supported order.
+    Support,
+    /// Whether a move succeeded, which affects what it attacked, prevented or left behind.
+    Attack,
+    /// Whether a convoying fleet was dislodged, which affects the convoyed army's path.
+    ConvoyPath,
+    /// Whether a holding unit was dislodged.
+    Hold,
+}
+
+impl DependencyKind {
+    /// The kind of dependency on the outcome of `order`.
+    fn of(order: &MappedMainOrder) -> Self {
+        match order.command {
+            MainCommand::Support(_) => DependencyKind::Support,
+            MainCommand::Move(_) => DependencyKind::Attack,
+            MainCommand::Convoy(_) => DependencyKind::ConvoyPath,
+            MainCommand::Hold => DependencyKind::Hold,
+        }
+    }
+}
+
+impl fmt::Display for DependencyKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            DependencyKind::Support => "support",
+            DependencyKind::Attack => "attack",
+            DependencyKind::ConvoyPath => "convoy path",
+            DependencyKind::Hold => "hold",
+        })
+    }
+}
+
+/// An adjudicated order in a dependency graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DependencyNode {
+    pub order: MappedMainOrder,
+    pub state: OrderState,
+    /// The order was part of a paradox, and was resolved by the paradox rule.
+    pub paradox: bool,
+    /// The order was part of a dependency cycle.
+    pub in_cycle: bool,
+}
+
+/// An edge from an order to an order whose outcome it depended on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DependencyEdge {
+    pub from: MappedMainOrder,
+    pub to: MappedMainOrder,
+    pub kind: DependencyKind,
+    /// Both orders are part of the same dependency cycle.
+    pub in_cycle: bool,
+}
+
+/// The dependencies between orders found while resolving a main phase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DependencyGraph {
+    /// Every adjudicated order, in sorted order.
+    pub nodes: Vec<DependencyNode>,
+    /// Every dependency, in sorted order.
+    pub edges: Vec<DependencyEdge>,
+    /// The dependency cycles the resolver had to break, each with its orders in sorted order.
+    /// This includes cycles found while evaluating guesses that were later discarded.
+    pub cycles: Vec<Vec<MappedMainOrder>>,
+}
+
+impl DependencyGraph {
+    pub(in crate::judge) fn new<A: Adjudicate>(outcome: &Outcome<'_, A>) -> Self {
+        let cycles = outcome.resolver.cycles().into_iter().collect::<Vec<_>>();
+        let in_same_cycle = |a: &MappedMainOrder, b: &MappedMainOrder| {
+            cycles
+                .iter()
+                .any(|cycle| cycle.contains(a) && cycle.contains(b))
+        };
+
+        let paradoxical = outcome
+            .resolver
+            .paradoxical_orders()
+            .collect::<BTreeSet<_>>();
+
+        let mut nodes = outcome
+            .context
+            .orders()
+            .map(|order| DependencyNode {
+                order: order.clone(),
+                state: outcome
+                    .orders
+                    .get(order)
+                    .map(OrderState::from)
+                    .unwrap_or(OrderState::Fails),
+                paradox: paradoxical.contains(order),
+                in_cycle: in_same_cycle(order, order),
+            })
+            .collect::<Vec<_>>();
+        nodes.sort_by(|a, b| a.order.cmp(&b.order));
+
+        let edges = outcome
+            .resolver
+            .dependencies()
+            .into_iter()
+            .map(|(from, to)| DependencyEdge {
+                kind: DependencyKind::of(&to),
+                in_cycle: in_same_cycle(&from, &to),
+                from,
+                to,
+            })
+            .collect();
+
+        Self {
+            nodes,
+            edges,
+            cycles,
+        }
+    }
+}
+
+/// Render the graph in GraphViz DOT format. Nodes are labelled with their order state and edges
+/// with the kind of dependency. Orders and dependencies in a cycle are drawn in orange, and
+/// paradoxical orders are filled in red.
+impl fmt::Display for DependencyGraph {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "digraph G {{")?;
+        for node in &self.nodes {
+            write!(
+                f,
+                r#"  "{}" [label="{}\n{:?}""#,
+                node.order, node.order, node.state
+            )?;
+            if node.in_cycle {
+                write!(f, ", color=orange, penwidth=2")?;
+            }
+
+            if node.paradox {
+                write!(f, ", style=filled, fillcolor=red")?;
+            }
+
+            writeln!(f, "]")?;
+        }
+
+        for edge in &self.edges {
+            write!(
+                f,
+                r#"  "{}" -> "{}" [label="{}""#,
+                edge.from, edge.to, edge.kind
+            )?;
+            if edge.in_cycle {
+                write!(f, ", color=orange, penwidth=2")?;
+            }
+
+            writeln!(f, "]")?;
+        }
+
+        writeln!(f, "}}")
+    }
+}
@@ -0,0 +1,530 @@
+//! Human-readable explanations of main-phase outcomes.
+//!
+//! An outcome says whether an order succeeded, and for some failures which order was
+//! responsible. An `Explanation` goes further: it lists the strengths that decided the order,
+//! which supports were counted in each strength, and why any other supports were not. A
+//! dislodged unit's explanation includes the explanation of the move that dislodged it.
+//!
+//! `OrderStrengths` lists the four strengths of an order defined by the DATC, for tools that
+//! want the numbers without the narrative.
+//!
+//! Explanations own their orders, so they can outlive the submission. They render as indented
+//! plain text with `Display`, and with the `serde` feature enabled they can be serialized.
+
+use super::strength::Counted;
+use super::{
+    calc, report, Adjudicate, AttackOutcome, MappedMainOrder, OrderOutcome, Outcome, Prevent,
+    ResolverState, SupportOutcome,
+};
+use crate::order::Command;
+use crate::ShortName;
+use std::fmt;
+
+/// The outcome of an order and the reasons for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Explanation {
+    pub order: MappedMainOrder,
+    pub outcome: report::OrderOutcome,
+    pub reasons: Vec<Reason>,
+}
+
+/// One reason contributing to the outcome of an order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Reason {
+    /// The strength of the order itself, or of an order opposing it.
+    Strength(StrengthReport),
+    /// The support order was cut by a move into the supporting unit's province.
+    CutBy(MappedMainOrder),
+    /// The unit was dislodged by a move, which is explained in turn.
+    DislodgedBy(Box<Explanation>),
+}
+
+/// The kind of strength used in a battle over a province.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StrengthKind {
+    /// The strength of a move trying to enter the province.
+    Attack,
+    /// The strength of another move to the same province, stopping the attack from entering.
+    Prevent,
+    /// The strength of a unit in the province moving head-to-head against the attack.
+    Defend,
+    /// The strength of a unit staying in the province.
+    Hold,
+}
+
+impl fmt::Display for StrengthKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            StrengthKind::Attack => "attack",
+            StrengthKind::Prevent => "prevent",
+            StrengthKind::Defend => "defend",
+            StrengthKind::Hold => "hold",
+        })
+    }
+}
+
+/// The strength of an order in a battle, with the supports that were and weren't counted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StrengthReport {
+    pub kind: StrengthKind,
+    pub order: MappedMainOrder,
+    pub strength: usize,
+    /// The support orders counted in the strength.
+    pub supports: Vec<MappedMainOrder>,
+    /// Support orders for `order` that were not counted. A support that was given but isn't
+    /// counted was for an attack on a unit of the supporting nation, or of the attacker's own.
+    pub uncounted: Vec<report::OrderReport<MappedMainOrder, report::SupportOutcome>>,
+}
+
+/// A strength, following the DATC definitions, and the supports counted toward it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CountedStrength {
+    pub strength: usize,
+    pub supports: Vec<MappedMainOrder>,
+}
+
+impl From<Counted<'_>> for CountedStrength {
+    fn from((strength, supports): Counted<'_>) -> Self {
+        Self {
+            strength,
+            supports: supports.into_iter().cloned().collect(),
+        }
+    }
+}
+
+/// The strengths of an adjudicated order, following the terminology of the DATC.
+///
+/// The attack, defend and prevent strengths only apply to moves. The hold strength is that of
+/// the province the unit started in; it's zero for a unit that moved away.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrderStrengths {
+    pub order: MappedMainOrder,
+    /// The strength with which the move tries to enter its destination.
+    pub attack: Option<CountedStrength>,
+    /// The strength with which the unit keeps others out of the province it started in.
+    pub hold: CountedStrength,
+    /// The strength with which the move resists the other unit in a head-to-head battle.
+    pub defend: Option<CountedStrength>,
+    /// The strength with which the move stops other units entering its destination.
+    pub prevent: Option<CountedStrength>,
+}
+
+impl Explanation {
+    fn write(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "{:indent$}{}: {}",
+            "",
+            self.order,
+            describe(&self.outcome),
+            indent = depth * 2
+        )?;
+
+        for reason in &self.reasons {
+            reason.write(f, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+impl Reason {
+    fn write(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        let indent = depth * 2;
+        match self {
+            Reason::Strength(report) => {
+                write!(
+                    f,
+                    "{:indent$}{} strength {}",
+                    "", report.kind, report.strength
+                )?;
+                if report.kind != StrengthKind::Attack {
+                    write!(f, " from {}", report.order)?;
+                }
+
+                if !report.supports.is_empty() {
+                    let supporters = report
+                        .supports
+                        .iter()
+                        .map(unit_name)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    write!(f, " (supported by {})", supporters)?;
+                }
+
+                writeln!(f)?;
+
+                for support in &report.uncounted {
+                    writeln!(
+                        f,
+                        "{:indent$}support from {} {}",
+                        "",
+                        unit_name(&support.order),
+                        describe_uncounted(&support.outcome),
+                        indent = indent + 2
+                    )?;
+                }
+
+                Ok(())
+            }
+            Reason::CutBy(order) => writeln!(f, "{:indent$}cut by {}", "", order),
+            Reason::DislodgedBy(explanation) => {
+                writeln!(f, "{:indent$}dislodged by", "")?;
+                explanation.write(f, depth + 1)
+            }
+        }
+    }
+}
+
+/// The unit type and region of an order, such as "A ruh".
+fn unit_name(order: &MappedMainOrder) -> String {
+    format!(
+        "{} {}",
+        order.unit_type.short_name(),
+        order.region.short_name()
+    )
+}
+
+fn describe(outcome: &report::OrderOutcome) -> String {
+    use super::report::{
+        AttackOutcome as Atk, ConvoyOutcome as Cvy, HoldOutcome as Hld, OrderOutcome as Oo,
+        SupportOutcome as Sup,
+    };
+
+    match outcome {
+        Oo::Invalid(reason) => return format!("invalid ({:?})", reason),
+        Oo::Rewritten { order, outcome, .. } => {
+            return format!("{} (adjudicated as {})", describe(outcome), order)
+        }
+        Oo::Hold(Hld::Succeeds) => "succeeds",
+        Oo::Hold(Hld::Dislodged(_)) | Oo::Convoy(Cvy::Dislodged(_)) => "dislodged",
+        Oo::Move(Atk::MoveToSelf) => "moves to its own province",
+        Oo::Move(Atk::NoPath) => "no path to destination",
+        Oo::Move(Atk::FriendlyFire) => "can't dislodge a unit of its own nation",
+        Oo::Move(Atk::Prevented(_)) => "bounced",
+        Oo::Move(Atk::LostHeadToHead) => "lost head-to-head battle",
+        Oo::Move(Atk::OccupierDefended) => "occupier defended",
+        Oo::Move(Atk::Paradox) | Oo::Convoy(Cvy::Paradox) => "paradox",
+        Oo::Move(Atk::Succeeds)
+        | Oo::Support(Sup::NotDisrupted)
+        | Oo::Convoy(Cvy::NotDisrupted) => "succeeds",
+        Oo::Support(Sup::SupportingSelf) => "supports its own province",
+        Oo::Support(Sup::CantReach) => "can't reach",
+        Oo::Support(Sup::CutBy(_)) => "cut",
+        Oo::Support(Sup::Paradox) => "not given to resolve a paradox",
+        Oo::Convoy(Cvy::NotAtSea) => "not at sea",
+    }
+    .to_string()
+}
+
+fn describe_uncounted(outcome: &report::SupportOutcome) -> String {
+    match outcome {
+        report::SupportOutcome::NotDisrupted => {
+            "doesn't count against the unit in the destination".to_string()
+        }
+        report::SupportOutcome::SupportingSelf => "supports its own province".to_string(),
+        report::SupportOutcome::CantReach => "can't reach".to_string(),
+        report::SupportOutcome::CutBy(order) => format!("was cut by {}", order),
+        report::SupportOutcome::Paradox => "was not given to resolve a paradox".to_string(),
+    }
+}
+
+/// Builds explanations and strengths from an outcome, sharing one resolver state between them.
+pub(in crate::judge) struct Explainer<'o, 'a, A> {
+    outcome: &'o Outcome<'a, A>,
+    state: ResolverState<'a>,
+}
+
+impl<'o, 'a, A: Adjudicate> Explainer<'o, 'a, A> {
+    pub(in crate::judge) fn new(outcome: &'o Outcome<'a, A>) -> Self {
+        Self {
+            outcome,
+            state: outcome.resolver.clone(),
+        }
+    }
+
+    pub(in crate::judge) fn explain(&mut self, order: &'a MappedMainOrder) -> Option<Explanation> {
+        let result = self.outcome.orders.get(order)?;
+
+        // A rewritten order is explained by the order adjudicated in its place.
+        if let OrderOutcome::Rewritten(rewrite) = result {
+            return Some(Explanation {
+                order: order.clone(),
+                outcome: result.into(),
+                reasons: self.explain(rewrite.order)?.reasons,
+            });
+        }
+
+        let mut reasons = vec![];
+
+        match result {
+            OrderOutcome::Invalid(_) | OrderOutcome::Rewritten(_) => {}
+            OrderOutcome::Move(attack) => self.explain_move(order, attack, &mut reasons),
+            OrderOutcome::Support(SupportOutcome::CutBy(by)) => {
+                reasons.push(Reason::CutBy((*by).clone()))
+            }
+            OrderOutcome::Hold(_) | OrderOutcome::Support(_) | OrderOutcome::Convoy(_) => {}
+        }
+
+        if let OrderOutcome::Invalid(_) = result {
+            // Invalid orders weren't adjudicated, so the unit was never in a battle.
+        } else if let Some(by) = calc::dislodger_of(&self.outcome.context, &mut self.state, order) {
+            let dislodger = self.explain(by)?;
+            reasons.push(Reason::DislodgedBy(Box::new(dislodger)));
+        } else if !order.is_move() && self.is_attacked(order) {
+            let hold = self.hold_strength(order);
+            reasons.push(self.strength(StrengthKind::Hold, order, hold));
+        }
+
+        Some(Explanation {
+            order: order.clone(),
+            outcome: result.into(),
+            reasons,
+        })
+    }
+
+    /// Calculate the strengths of an adjudicated order.
+    pub(in crate::judge) fn strengths(&mut self, order: &'a MappedMainOrder) -> OrderStrengths {
+        let (attack, defend, prevent) = if order.is_move() {
+            (
+                Some(self.attack_strength(order).into()),
+                Some(self.defend_strength(order).into()),
+                Some(self.prevent_strength(order).into()),
+            )
+        } else {
+            (None, None, None)
+        };
+
+        OrderStrengths {
+            order: order.clone(),
+            attack,
+            hold: self.hold_strength(order).into(),
+            defend,
+            prevent,
+        }
+    }
+
+    fn attack_strength(&mut self, order: &'a MappedMainOrder) -> Counted<'a> {
+        calc::attack_strength(&self.outcome.context, &mut self.state, order)
+    }
+
+    fn hold_strength(&mut self, order: &'a MappedMainOrder) -> Counted<'a> {
+        calc::hold_strength(&self.outcome.context, &mut self.state, order)
+    }
+
+    fn defend_strength(&mut self, order: &'a MappedMainOrder) -> Counted<'a> {
+        calc::defend_strength(&self.outcome.context, &mut self.state, order)
+    }
+
+    fn prevent_strength(&mut self, order: &'a MappedMainOrder) -> Counted<'a> {
+        calc::prevent_strength(&self.outcome.context, &mut self.state, order)
+    }
+
+    fn explain_move(
+        &mut self,
+        order: &'a MappedMainOrder,
+        attack: &AttackOutcome<'a>,
+        reasons: &mut Vec<Reason>,
+    ) {
+        if matches!(
+            attack,
+            AttackOutcome::MoveToSelf | AttackOutcome::NoPath | AttackOutcome::Paradox
+        ) {
+            return;
+        }
+
+        let ctx = &self.outcome.context;
+        let dest = match order.move_dest() {
+            Some(dest) => dest,
+            None => return,
+        };
+
+        let resistance = match ctx.find_order_to_province(dest.province()) {
+            Some(occupier) if calc::is_head_to_head(ctx, &mut self.state, order, occupier) => {
+                let defend = self.defend_strength(occupier);
+                Some(self.strength(StrengthKind::Defend, occupier, defend))
+            }
+            Some(occupier) => {
+                let hold = self.hold_strength(occupier);
+                if hold.0 > 0 {
+                    Some(self.strength(StrengthKind::Hold, occupier, hold))
+                } else {
+                    None
+                }
+            }
+            None => None,
+        };
+
+        let attack = self.attack_strength(order);
+        reasons.push(self.strength(StrengthKind::Attack, order, attack));
+
+        if let Some(Prevent::Prevents(preventer, supports)) =
+            calc::max_prevent_result(ctx, &mut self.state, order)
+        {
+            let prevent = (1 + supports.len(), supports);
+            reasons.push(self.strength(StrengthKind::Prevent, preventer, prevent));
+        }
+
+        reasons.extend(resistance);
+    }
+
+    /// Returns true if any order tries to move into the province of `order`.
+    fn is_attacked(&self, order: &MappedMainOrder) -> bool {
+        self.outcome
+            .context
+            .moves_to_province(order.region.province())
+            .next()
+            .is_some()
+    }
+
+    fn strength(
+        &self,
+        kind: StrengthKind,
+        order: &MappedMainOrder,
+        (strength, supports): Counted<'a>,
+    ) -> Reason {
+        // A unit that failed to move away holds without support, so the supports for its move
+        // have no bearing on its hold strength.
+        let has_supports = strength > 0 && !(kind == StrengthKind::Hold && order.is_move());
+        let uncounted = self
+            .outcome
+            .context
+            .supports_for(order)
+            .filter(|sup| has_supports && !supports.contains(sup))
+            .filter_map(|sup| match self.outcome.orders.get(sup) {
+                Some(OrderOutcome::Support(outcome)) => Some(report::OrderReport {
+                    order: sup.clone(),
+                    outcome: outcome.into(),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        Reason::Strength(StrengthReport {
+            kind,
+            order: order.clone(),
+            strength,
+            supports: supports.into_iter().cloned().collect(),
+            uncounted,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Reason, StrengthKind};
+    use crate::geo::standard_map;
+    use crate::judge::{ConvoyToAdjacent, MappedMainOrder, ParadoxRule, Rulebook, Submission};
+
+    fn ord(s: &str) -> MappedMainOrder {
+        s.parse().unwrap()
+    }
+
+    fn submit(orders: &[&str]) -> Submission {
+        Submission::with_inferred_state(orders.iter().map(|o| ord(o)).collect())
+    }
+
+    /// The kinds of strength listed in the explanation of `order`.
+    fn strength_kinds(submission: &Submission, rules: Rulebook, order: &str) -> Vec<StrengthKind> {
+        let outcome = submission.adjudicate(standard_map(), rules);
+        outcome
+            .explain(&ord(order))
+            .unwrap()
+            .reasons
+            .into_iter()
+            .filter_map(|reason| match reason {
+                Reason::Strength(report) => Some(report.kind),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// DATC 6.F.14: under the "all hold" rule, the fleet moving to the English Channel is part
+    /// of the paradox, so it has no attack strength.
+    #[test]
+    fn paradox_rule_decides_attack_strength() {
+        let submission = submit(&[
+            "ENG: F lon Supports F wal -> eng",
+            "ENG: F wal -> eng",
+            "FRA: A bre -> lon",
+            "FRA: F eng convoys bre -> lon",
+        ]);
+        let attack = |rules| {
+            let outcome = submission.adjudicate(standard_map(), rules);
+            outcome
+                .strengths(&ord("ENG: F wal -> eng"))
+                .unwrap()
+                .attack
+                .unwrap()
+        };
+
+        let szykman = attack(Rulebook::with_paradox_rule(ParadoxRule::Szykman));
+        assert_eq!(2, szykman.strength);
+        assert_eq!(
+            vec![ord("ENG: F lon Supports F wal -> eng")],
+            szykman.supports
+        );
+
+        let all_hold = attack(Rulebook::with_paradox_rule(ParadoxRule::AllHold));
+        assert_eq!(0, all_hold.strength);
+        assert!(all_hold.supports.is_empty());
+    }
+
+    /// Two armies swap places using a foreign convoy. Only when the convoy can carry an army
+    /// to an adjacent province do they avoid a head-to-head battle.
+    #[test]
+    fn convoy_to_adjacent_decides_head_to_head() {
+        let submission = submit(&[
+            "FRA: A bel -> hol",
+            "GER: A hol -> bel",
+            "ENG: F nth convoys bel -> hol",
+        ]);
+        let rules = |convoy_to_adjacent| Rulebook {
+            convoy_to_adjacent,
+            ..Rulebook::default()
+        };
+
+        assert_eq!(
+            vec![StrengthKind::Attack],
+            strength_kinds(
+                &submission,
+                rules(ConvoyToAdjacent::Kidnapping),
+                "FRA: A bel -> hol"
+            )
+        );
+        assert_eq!(
+            vec![StrengthKind::Attack, StrengthKind::Defend],
+            strength_kinds(
+                &submission,
+                rules(ConvoyToAdjacent::Explicit),
+                "FRA: A bel -> hol"
+            )
+        );
+    }
+
+    #[test]
+    fn hold_strength_counts_supports() {
+        let submission = submit(&[
+            "FRA: A bur -> mun",
+            "GER: A mun Holds",
+            "GER: A ber Supports A mun",
+        ]);
+        let outcome = submission.adjudicate(standard_map(), Rulebook::default());
+        let hold = outcome.strengths(&ord("GER: A mun Holds")).unwrap().hold;
+
+        assert_eq!(2, hold.strength);
+        assert_eq!(vec![ord("GER: A ber Supports A mun")], hold.supports);
+    }
+}
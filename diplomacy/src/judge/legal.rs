@@ -0,0 +1,364 @@
+//! Generators for the orders a unit or nation could legally be given in each phase.
+//!
+//! An order is legal if it's well-formed for the board: moves follow a passable border or a
+//! chain of fleets at sea, supports and convoys name a unit on the board, and so on. A legal
+//! order may still fail when adjudicated. The generators only consider the map and the positions
+//! of units, never the orders given to other units.
+//!
+//! Every generator returns its results sorted, so they can be shown to players as-is.
+
+use super::{retreat, sanity, MappedBuildOrder, MappedMainOrder, MappedRetreatOrder};
+use crate::geo::{Map, ProvinceKey, RegionKey, SupplyCenter, Terrain};
+use crate::order::{
+    BuildCommand, ConvoyedMove, MainCommand, MoveCommand, RetreatCommand, SupportedOrder,
+};
+use crate::{
+    Nation, Order, ShortName, SupplyCenterOwnership, UnitPosition, UnitPositions, UnitType,
+};
+use std::collections::BTreeSet;
+
+/// Every main-phase command the unit at `unit` could legally be given, given the positions of
+/// every unit on the board.
+///
+/// The commands are holding, moving to an adjacent region or to a coastal province reachable
+/// through a chain of fleets at sea, supporting another unit to hold or to make one of its own
+/// legal moves, and, for fleets at sea, convoying an army along a chain that includes the fleet.
+pub fn main_commands(
+    world: &Map,
+    positions: &impl UnitPositions<RegionKey>,
+    unit: &UnitPosition<'_>,
+) -> Vec<MainCommand<RegionKey>> {
+    let units = positions.unit_positions();
+    let fleets = fleets_at_sea(world, &units);
+    let mut commands = BTreeSet::new();
+
+    commands.insert(MainCommand::Hold);
+
+    for dest in move_destinations(world, &fleets, unit.unit.unit_type(), unit.region, None) {
+        commands.insert(MoveCommand::new(dest).into());
+    }
+
+    for other in &units {
+        if other.region.province() == unit.region.province() {
+            continue;
+        }
+
+        let other_type = other.unit.unit_type();
+        if can_reach(world, unit, other.region.province()) {
+            commands.insert(SupportedOrder::Hold(other_type, other.region.clone()).into());
+        }
+
+        // A supported move can't rely on the supporting unit to convoy it.
+        for dest in move_destinations(world, &fleets, other_type, other.region, Some(unit.region)) {
+            if dest.province() != unit.region.province() && can_reach(world, unit, dest.province())
+            {
+                commands
+                    .insert(SupportedOrder::Move(other_type, other.region.clone(), dest).into());
+            }
+        }
+
+        if other_type == UnitType::Army && fleets.contains(&unit.region) {
+            let carriers = sanity::reachable_fleets(world, &fleets, other.region.province());
+            if !carriers.contains(unit.region) {
+                continue;
+            }
+
+            for dest in convoy_destinations(world, &fleets, other.region.province()) {
+                if sanity::reachable_fleets(world, &fleets, dest.province()).contains(unit.region) {
+                    commands.insert(ConvoyedMove::new(other.region.clone(), dest).into());
+                }
+            }
+        }
+    }
+
+    commands.into_iter().collect()
+}
+
+/// Every main-phase order the unit at `unit` could legally be given. See `main_commands`.
+pub fn main_orders(
+    world: &Map,
+    positions: &impl UnitPositions<RegionKey>,
+    unit: &UnitPosition<'_>,
+) -> Vec<MappedMainOrder> {
+    main_commands(world, positions, unit)
+        .into_iter()
+        .map(|command| Order::new_from_position(unit.clone().into_owned(), command))
+        .collect()
+}
+
+/// Every retreat-phase command the dislodged unit at `unit` could legally be given: disbanding,
+/// or retreating to any available destination. Units that weren't dislodged can't be given any
+/// retreat-phase commands.
+pub fn retreat_commands(
+    start: &retreat::Start<'_>,
+    unit: &UnitPosition<'_>,
+) -> Vec<RetreatCommand<RegionKey>> {
+    match start.retreat_destinations().get(unit) {
+        Some(dests) => std::iter::once(RetreatCommand::Hold)
+            .chain(
+                dests
+                    .available()
+                    .into_iter()
+                    .map(|region| RetreatCommand::Move(region.clone())),
+            )
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// Every retreat-phase order the dislodged unit at `unit` could legally be given. See
+/// `retreat_commands`.
+pub fn retreat_orders(
+    start: &retreat::Start<'_>,
+    unit: &UnitPosition<'_>,
+) -> Vec<MappedRetreatOrder> {
+    retreat_commands(start, unit)
+        .into_iter()
+        .map(|command| Order::new_from_position(unit.clone().into_owned(), command))
+        .collect()
+}
+
+/// Every build-phase order `nation` could legally be given, based on the supply center
+/// ownership for the build phase and the units on the board.
+///
+/// A nation with more supply centers than units can build any unit type in any region of a
+/// vacant home supply center it owns. A nation with more units than supply centers can disband
+/// any of its units. Otherwise, the nation has no orders to give.
+pub fn build_orders(
+    world: &Map,
+    ownership: &SupplyCenterOwnership,
+    positions: &impl UnitPositions<RegionKey>,
+    nation: &Nation,
+) -> Vec<MappedBuildOrder> {
+    let units = positions
+        .unit_positions()
+        .into_iter()
+        .filter(|unit| unit.nation() == nation)
+        .collect::<Vec<_>>();
+    let centers = ownership.center_count(nation);
+
+    let mut orders = BTreeSet::new();
+    if centers > units.len() {
+        let homes = world
+            .provinces()
+            .filter(|province| province.supply_center == SupplyCenter::Home(nation.clone()))
+            .map(ProvinceKey::from)
+            .filter(|province| {
+                ownership.owner(province) == Some(nation)
+                    && positions.find_province_occupier(province).is_none()
+            })
+            .collect::<BTreeSet<_>>();
+
+        for region in world.regions().filter(|r| homes.contains(r.province())) {
+            for &unit_type in &[UnitType::Army, UnitType::Fleet] {
+                if unit_type.can_occupy(region.terrain()) {
+                    orders.insert(Order::new(
+                        nation.clone(),
+                        unit_type,
+                        RegionKey::from(region),
+                        BuildCommand::Build,
+                    ));
+                }
+            }
+        }
+    } else if centers < units.len() {
+        for unit in units {
+            orders.insert(Order::new(
+                nation.clone(),
+                unit.unit.unit_type(),
+                unit.region.clone(),
+                BuildCommand::Disband,
+            ));
+        }
+    }
+
+    orders.into_iter().collect()
+}
+
+/// Find the regions of fleets at sea, which can form convoy chains.
+pub(in crate::judge) fn fleets_at_sea<'a>(
+    world: &Map,
+    units: &[UnitPosition<'a>],
+) -> Vec<&'a RegionKey> {
+    units
+        .iter()
+        .filter(|u| u.unit.unit_type() == UnitType::Fleet && sanity::is_at_sea(world, u.region))
+        .map(|u| u.region)
+        .collect()
+}
+
+/// Returns true if the unit could support a unit in, or moving to, `province`.
+pub(in crate::judge) fn can_reach(
+    world: &Map,
+    unit: &UnitPosition<'_>,
+    province: &ProvinceKey,
+) -> bool {
+    world
+        .find_borders_between(unit.region, province)
+        .any(|b| b.is_passable_by(unit.unit.unit_type()))
+}
+
+/// Find every region a unit of type `unit_type` in `from` could move to, either directly or, for
+/// armies, through a chain of fleets. The fleet in `excluding`, if any, is left out of chains.
+pub(in crate::judge) fn move_destinations(
+    world: &Map,
+    fleets: &[&RegionKey],
+    unit_type: UnitType,
+    from: &RegionKey,
+    excluding: Option<&RegionKey>,
+) -> BTreeSet<RegionKey> {
+    let mut dests = world
+        .borders_containing(from)
+        .filter(|border| border.is_passable_by(unit_type))
+        .filter_map(|border| border.dest_from(from))
+        .filter(|dest| dest.province() != from.province())
+        .cloned()
+        .collect::<BTreeSet<_>>();
+
+    if unit_type == UnitType::Army {
+        let fleets = fleets
+            .iter()
+            .copied()
+            .filter(|&fleet| Some(fleet) != excluding)
+            .collect::<Vec<_>>();
+        dests.extend(convoy_destinations(world, &fleets, from.province()));
+    }
+
+    dests
+}
+
+/// Find the regions an army in `origin` could be convoyed to by a chain of `fleets`.
+pub(in crate::judge) fn convoy_destinations(
+    world: &Map,
+    fleets: &[&RegionKey],
+    origin: &ProvinceKey,
+) -> BTreeSet<RegionKey> {
+    sanity::reachable_fleets(world, fleets, origin)
+        .into_iter()
+        .flat_map(|fleet| world.find_bordering(fleet.province()))
+        .map(|region| RegionKey::new(region.province().clone(), None))
+        .filter(|region| region.province() != origin)
+        .filter(|region| {
+            world
+                .find_region(&region.short_name())
+                .map(|r| r.terrain() != Terrain::Sea)
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geo::standard_map;
+
+    fn units(units: &[&str]) -> Vec<UnitPosition<'static, RegionKey>> {
+        units.iter().map(|unit| unit.parse().unwrap()).collect()
+    }
+
+    fn commands(units: &[UnitPosition<'static, RegionKey>], unit: usize) -> Vec<String> {
+        let unit = &units[unit];
+        main_commands(
+            standard_map(),
+            &units.to_vec(),
+            &UnitPosition::new(unit.unit.clone(), &unit.region),
+        )
+        .into_iter()
+        .map(|command| command.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn army_moves_and_supports() {
+        let units = units(&["GER: A mun", "FRA: A bur"]);
+        let commands = commands(&units, 0);
+
+        assert!(commands.contains(&"holds".to_string()));
+        assert!(commands.contains(&"-> boh".to_string()));
+        assert!(commands.contains(&"supports A bur".to_string()));
+        assert!(commands.contains(&"supports A bur -> ruh".to_string()));
+        assert!(!commands.contains(&"-> adr".to_string()));
+        // Munich can't support a move into its own province.
+        assert!(!commands.contains(&"supports A bur -> mun".to_string()));
+        // Munich doesn't border Picardy.
+        assert!(!commands.contains(&"supports A bur -> pic".to_string()));
+    }
+
+    #[test]
+    fn convoys_through_fleet_chains() {
+        let units = units(&["ENG: A lon", "ENG: F nth", "ENG: F nwg", "FRA: F gol"]);
+
+        let army = commands(&units, 0);
+        assert!(army.contains(&"-> nwy".to_string()));
+        assert!(army.contains(&"-> edi".to_string()));
+        assert!(!army.contains(&"-> bar".to_string()));
+        assert!(!army.contains(&"-> stp".to_string()));
+
+        let fleet = commands(&units, 2);
+        assert!(fleet.contains(&"convoys A lon -> nwy".to_string()));
+
+        // The Gulf of Lyon isn't part of any chain from London.
+        assert!(!commands(&units, 3)
+            .iter()
+            .any(|command| command.starts_with("convoys")));
+
+        // The North Sea can't support the army into Holland if it's also convoying it.
+        let nth = commands(&units, 1);
+        assert!(nth.contains(&"convoys A lon -> hol".to_string()));
+        assert!(!nth.contains(&"supports A lon -> hol".to_string()));
+    }
+
+    #[test]
+    fn retreats_to_available_regions() {
+        use crate::judge::{Rulebook, Submission};
+
+        let orders = [
+            "AUS: A ser -> bul",
+            "AUS: A rum supports A ser -> bul",
+            "TUR: A bul Hold",
+        ]
+        .iter()
+        .map(|order| order.parse().unwrap())
+        .collect();
+        let submission = Submission::with_inferred_state(orders);
+        let outcome = submission.adjudicate(standard_map(), Rulebook::default());
+        let start = outcome.to_retreat_start();
+
+        let bul = units(&["TUR: A bul"]).remove(0);
+        assert_eq!(
+            vec![
+                RetreatCommand::Hold,
+                RetreatCommand::Move(RegionKey::new("con", None)),
+                RetreatCommand::Move(RegionKey::new("gre", None)),
+            ],
+            retreat_commands(&start, &UnitPosition::new(bul.unit.clone(), &bul.region))
+        );
+    }
+
+    #[test]
+    fn builds_on_vacant_owned_homes() {
+        let ownership = SupplyCenterOwnership::initial(standard_map());
+        let units = units(&["RUS: A mos"]);
+        let orders = build_orders(standard_map(), &ownership, &units, &Nation::from("RUS"))
+            .into_iter()
+            .map(|order| order.to_string())
+            .collect::<Vec<_>>();
+
+        assert!(orders.contains(&"RUS: F stp(nc) build".to_string()));
+        assert!(orders.contains(&"RUS: A stp build".to_string()));
+        assert!(orders.contains(&"RUS: F sev build".to_string()));
+        assert!(!orders.contains(&"RUS: F stp build".to_string()));
+        assert!(!orders.iter().any(|order| order.contains("mos")));
+        assert!(!orders.contains(&"RUS: F war build".to_string()));
+    }
+
+    #[test]
+    fn disbands_when_over_centers() {
+        let ownership = SupplyCenterOwnership::default();
+        let units = units(&["RUS: A mos", "RUS: F sev"]);
+        assert_eq!(
+            2,
+            build_orders(standard_map(), &ownership, &units, &Nation::from("RUS")).len()
+        );
+    }
+}
@@ -3,28 +3,37 @@
 pub mod build;
 mod calc;
 mod convoy;
+#[cfg(feature = "dependency-graph")]
+pub mod dependency_graph;
+pub mod explain;
+pub mod legal;
+mod normalize;
 mod outcome;
+pub mod report;
 mod resolver;
 pub mod retreat;
 mod rulebook;
+mod sanity;
 mod state_type;
 mod strength;
 pub mod support;
+pub mod validate;
 
-pub use self::outcome::{InvalidOrder, OrderOutcome, Outcome};
+pub use self::outcome::{
+    InterimPositions, InvalidOrder, OrderOutcome, Outcome, OwnedOutcome, Rewrite, RewriteReason,
+};
 pub use self::state_type::OrderState;
 
 pub use self::convoy::ConvoyOutcome;
 pub use self::rulebook::AttackOutcome;
 pub use self::rulebook::HoldOutcome;
-pub(self) use self::strength::Prevent;
+use self::strength::Prevent;
 pub use self::support::SupportOutcome;
 
 pub use self::resolver::{Context, ResolverState, Submission};
-pub use self::rulebook::Rulebook;
-use crate::geo::{Border, RegionKey, Terrain};
+pub use self::rulebook::{ConvoyToAdjacent, MultipleOrders, ParadoxRule, Rulebook};
+use crate::geo::RegionKey;
 use crate::order::{BuildOrder, MainCommand, Order, RetreatOrder};
-use crate::UnitType;
 
 pub type MappedMainOrder = Order<RegionKey, MainCommand<RegionKey>>;
 pub type MappedBuildOrder = BuildOrder<RegionKey>;
@@ -32,6 +41,32 @@ pub type MappedRetreatOrder = RetreatOrder<RegionKey>;
 
 /// A clonable container for a rulebook which can be used to adjudicate a turn.
 pub trait Adjudicate: Sized {
+    /// The rule used to break convoy paradoxes that the resolver can't otherwise settle.
+    fn paradox_rule(&self) -> ParadoxRule {
+        ParadoxRule::default()
+    }
+
+    /// When an army may be convoyed to a province it could also reach over land.
+    fn convoy_to_adjacent(&self) -> ConvoyToAdjacent {
+        ConvoyToAdjacent::default()
+    }
+
+    /// Whether a unit dislodged by an army convoyed from an adjacent province may retreat to
+    /// the province the army came from.
+    fn retreat_to_convoy_origin(&self) -> bool {
+        true
+    }
+
+    /// Which order is followed when a unit receives more than one.
+    fn multiple_orders(&self) -> MultipleOrders {
+        MultipleOrders::default()
+    }
+
+    /// Whether orders that could never succeed are replaced by holds before adjudication.
+    fn illegal_orders_hold(&self) -> bool {
+        false
+    }
+
     /// Determine the success of an order.
     fn adjudicate<'a>(
         &self,
@@ -47,19 +82,3 @@ pub trait Adjudicate: Sized {
         order: &'a MappedMainOrder,
     ) -> OrderOutcome<'a>;
 }
-
-impl Border {
-    fn is_passable_by(&self, unit_type: UnitType) -> bool {
-        unit_type.can_occupy(self.terrain())
-    }
-}
-
-impl UnitType {
-    fn can_occupy(self, terrain: Terrain) -> bool {
-        match terrain {
-            Terrain::Coast => true,
-            Terrain::Land => self == UnitType::Army,
-            Terrain::Sea => self == UnitType::Fleet,
-        }
-    }
-}
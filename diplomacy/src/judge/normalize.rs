@@ -0,0 +1,313 @@
+//! Coast-aware normalization of main-phase orders before adjudication.
+//!
+//! Players frequently omit coasts, name a coast for an army, or name the wrong coast for the
+//! province their fleet is in. The DATC (section 4.B) prefers that these orders are repaired
+//! where the intent is unambiguous, and rejected where it is not. This module applies those
+//! rewrites so the resolver only ever compares exact `RegionKey` values.
+
+use super::{InvalidOrder, MappedMainOrder, RewriteReason};
+use crate::geo::{Map, RegionKey};
+use crate::order::{Command, ConvoyedMove, MainCommand, SupportedOrder};
+use crate::{UnitPositions, UnitType};
+
+/// The result of normalizing a single submitted order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(in crate::judge) enum Normalized {
+    /// The order was already well-formed.
+    Unchanged,
+    /// The order was rewritten; the new order should be adjudicated in place of the submitted
+    /// one, and the reason recorded against the submitted order.
+    Rewritten(MappedMainOrder, RewriteReason),
+    /// The order cannot be repaired and should be treated as invalid.
+    Rejected(InvalidOrder),
+}
+
+/// Normalize coasts in `orders` against `world`, using `positions` to find units whose order
+/// names the wrong coast of the province they occupy.
+///
+/// The returned vector has one entry per order, in the same sequence as `orders`.
+pub(in crate::judge) fn normalize_orders(
+    world: &Map,
+    positions: &impl UnitPositions<RegionKey>,
+    orders: &[MappedMainOrder],
+) -> Vec<Normalized> {
+    // Moves are normalized first, so that supports can be matched against the coast a
+    // supported fleet will actually be moving to.
+    let mut working = orders
+        .iter()
+        .map(|order| normalize_unit_and_move(world, positions, order))
+        .collect::<Vec<_>>();
+
+    let moves = working
+        .iter()
+        .filter_map(|w| match w {
+            Working::Kept(order, _) => Some(order.clone()),
+            Working::Rejected(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    for item in &mut working {
+        if let Working::Kept(order, reason) = item {
+            if let Some(support_reason) = normalize_support(world, positions, &moves, order) {
+                reason.get_or_insert(support_reason);
+            }
+        }
+    }
+
+    working
+        .into_iter()
+        .zip(orders)
+        .map(|(item, submitted)| match item {
+            Working::Rejected(reason) => Normalized::Rejected(reason),
+            Working::Kept(order, Some(reason)) if order != *submitted => {
+                Normalized::Rewritten(order, reason)
+            }
+            Working::Kept(..) => Normalized::Unchanged,
+        })
+        .collect()
+}
+
+/// In-progress normalization of an order, carrying the first reason it was changed.
+enum Working {
+    Kept(MappedMainOrder, Option<RewriteReason>),
+    Rejected(InvalidOrder),
+}
+
+fn normalize_unit_and_move(
+    world: &Map,
+    positions: &impl UnitPositions<RegionKey>,
+    order: &MappedMainOrder,
+) -> Working {
+    let mut order = order.clone();
+    let mut reason = None;
+
+    // DATC 6.B.10 and 6.B.11: a wrong coast for the ordered unit is ignored, but does not
+    // change which coast the unit is on.
+    if order.unit_type == UnitType::Army {
+        if let Some(region) = without_coast(&order.region) {
+            order.region = region;
+            reason = Some(RewriteReason::CoastIgnored);
+        }
+    } else if positions.find_region_occupier(&order.region).is_none() {
+        if let Some(actual) = positions.find_province_occupier(order.region.province()) {
+            if actual.nation() == &order.nation && actual.unit.unit_type() == order.unit_type {
+                order.region = actual.region.clone();
+                reason = Some(RewriteReason::CoastIgnored);
+            }
+        }
+    }
+
+    match &mut order.command {
+        MainCommand::Move(cmd) => {
+            if order.unit_type == UnitType::Army {
+                // DATC 6.B.12: armies ignore coasts on their destination.
+                if let Some(dest) = without_coast(cmd.dest()) {
+                    *cmd.dest_mut() = dest;
+                    reason.get_or_insert(RewriteReason::CoastIgnored);
+                }
+            } else if cmd.dest().coast().is_none() {
+                let mut coasts = reachable_coasts(world, &order.region, cmd.dest());
+                match coasts.len() {
+                    0 => {}
+                    // DATC 6.B.2: when only one coast is reachable, that coast is meant.
+                    1 => {
+                        *cmd.dest_mut() = coasts.remove(0);
+                        reason.get_or_insert(RewriteReason::CoastInferred);
+                    }
+                    // DATC 6.B.1: the order doesn't say which of several coasts is meant.
+                    _ => return Working::Rejected(InvalidOrder::AmbiguousCoast),
+                }
+            }
+        }
+        MainCommand::Convoy(mv) => {
+            // Only armies are convoyed, so coasts on either end of the route are meaningless.
+            let from = without_coast(mv.from());
+            let to = without_coast(mv.to());
+            if from.is_some() || to.is_some() {
+                *mv = ConvoyedMove::new(
+                    from.unwrap_or_else(|| mv.from().clone()),
+                    to.unwrap_or_else(|| mv.to().clone()),
+                );
+                reason.get_or_insert(RewriteReason::CoastIgnored);
+            }
+        }
+        MainCommand::Hold | MainCommand::Support(..) => {}
+    }
+
+    Working::Kept(order, reason)
+}
+
+/// Rewrite the coasts named in a support order so they match the supported unit and its
+/// order. Returns the reason for the rewrite if anything changed.
+fn normalize_support(
+    world: &Map,
+    positions: &impl UnitPositions<RegionKey>,
+    moves: &[MappedMainOrder],
+    order: &mut MappedMainOrder,
+) -> Option<RewriteReason> {
+    let supported = if let MainCommand::Support(supported) = &mut order.command {
+        supported
+    } else {
+        return None;
+    };
+
+    let mut reason = None;
+
+    match supported {
+        SupportedOrder::Hold(UnitType::Army, region) => {
+            if let Some(fixed) = without_coast(region) {
+                *region = fixed;
+                reason = Some(RewriteReason::CoastIgnored);
+            }
+        }
+        SupportedOrder::Hold(UnitType::Fleet, region) => {
+            if let Some(fixed) = actual_fleet_region(positions, region) {
+                *region = fixed;
+                reason = Some(RewriteReason::CoastIgnored);
+            }
+        }
+        SupportedOrder::Move(UnitType::Army, from, to) => {
+            let fixed_from = without_coast(from);
+            let fixed_to = without_coast(to);
+            if fixed_from.is_some() || fixed_to.is_some() {
+                reason = Some(RewriteReason::CoastIgnored);
+            }
+            if let Some(fixed) = fixed_from {
+                *from = fixed;
+            }
+            if let Some(fixed) = fixed_to {
+                *to = fixed;
+            }
+        }
+        SupportedOrder::Move(UnitType::Fleet, from, to) => {
+            if let Some(fixed) = actual_fleet_region(positions, from) {
+                *from = fixed;
+                reason = Some(RewriteReason::CoastIgnored);
+            }
+
+            // DATC 6.B.7 and 6.B.8: support to an unspecified coast covers a move to whichever
+            // coast the supported fleet chose. If the supported fleet isn't moving there, fall
+            // back to the only coast it could reach.
+            if to.coast().is_none() {
+                let ordered_dest = moves
+                    .iter()
+                    .find(|mv| {
+                        mv.unit_type == UnitType::Fleet
+                            && mv.region == *from
+                            && mv.is_move_to_province(to.province())
+                    })
+                    .and_then(|mv| mv.command.move_dest())
+                    .filter(|dest| dest.coast().is_some())
+                    .cloned();
+
+                let inferred = ordered_dest.or_else(|| {
+                    let mut coasts = reachable_coasts(world, from, to);
+                    if coasts.len() == 1 {
+                        Some(coasts.remove(0))
+                    } else {
+                        None
+                    }
+                });
+
+                if let Some(fixed) = inferred {
+                    *to = fixed;
+                    reason = Some(RewriteReason::CoastInferred);
+                }
+            }
+        }
+    }
+
+    reason
+}
+
+/// Get the coasts of `dest`'s province that a fleet in `from` could move to directly.
+fn reachable_coasts(world: &Map, from: &RegionKey, dest: &RegionKey) -> Vec<RegionKey> {
+    let mut coasts = world
+        .find_borders_between(from, dest.province())
+        .filter(|border| border.is_passable_by(UnitType::Fleet))
+        .filter_map(|border| border.dest_from(from))
+        .filter(|region| region.coast().is_some())
+        .cloned()
+        .collect::<Vec<_>>();
+    coasts.sort();
+    coasts.dedup();
+    coasts
+}
+
+/// Find the region a fleet actually occupies when `region` names the wrong coast of its
+/// province. Returns `None` if `region` is already correct or no fleet is in the province.
+fn actual_fleet_region(
+    positions: &impl UnitPositions<RegionKey>,
+    region: &RegionKey,
+) -> Option<RegionKey> {
+    let occupier = positions.find_province_occupier(region.province())?;
+    if occupier.unit.unit_type() == UnitType::Fleet && occupier.region != region {
+        Some(occupier.region.clone())
+    } else {
+        None
+    }
+}
+
+/// Get the coast-less form of `region`, or `None` if it has no coast to remove.
+fn without_coast(region: &RegionKey) -> Option<RegionKey> {
+    region
+        .coast()
+        .map(|_| RegionKey::new(region.province().clone(), None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_orders, Normalized};
+    use crate::geo::standard_map;
+    use crate::judge::{InvalidOrder, MappedMainOrder, RewriteReason};
+
+    fn ord(s: &str) -> MappedMainOrder {
+        s.parse().unwrap()
+    }
+
+    fn normalize(orders: &[&str]) -> Vec<Normalized> {
+        let orders = orders.iter().map(|o| ord(o)).collect::<Vec<_>>();
+        normalize_orders(standard_map(), &orders, &orders)
+    }
+
+    #[test]
+    fn infers_only_reachable_coast() {
+        assert_eq!(
+            normalize(&["FRA: F gas -> spa"]),
+            vec![Normalized::Rewritten(
+                ord("FRA: F gas -> spa(nc)"),
+                RewriteReason::CoastInferred
+            )]
+        );
+    }
+
+    #[test]
+    fn rejects_ambiguous_coast() {
+        assert_eq!(
+            normalize(&["FRA: F por -> spa"]),
+            vec![Normalized::Rejected(InvalidOrder::AmbiguousCoast)]
+        );
+    }
+
+    #[test]
+    fn leaves_wrong_coast_alone() {
+        assert_eq!(
+            normalize(&["FRA: F gas -> spa(sc)"]),
+            vec![Normalized::Unchanged]
+        );
+    }
+
+    #[test]
+    fn support_follows_supported_move() {
+        assert_eq!(
+            normalize(&["FRA: F por Supports F mao -> spa", "FRA: F mao -> spa(nc)"]),
+            vec![
+                Normalized::Rewritten(
+                    ord("FRA: F por Supports F mao -> spa(nc)"),
+                    RewriteReason::CoastInferred
+                ),
+                Normalized::Unchanged
+            ]
+        );
+    }
+}
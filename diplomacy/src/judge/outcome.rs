@@ -1,7 +1,13 @@
+#[cfg(feature = "dependency-graph")]
+use super::dependency_graph::DependencyGraph;
+use super::explain::{Explainer, Explanation, OrderStrengths};
 use super::{
-    retreat, Adjudicate, AttackOutcome, Context, ConvoyOutcome, HoldOutcome, MappedMainOrder,
-    OrderState, ResolverState, SupportOutcome,
+    calc, convoy, report, retreat, Adjudicate, AttackOutcome, Context, ConvoyOutcome, HoldOutcome,
+    MappedMainOrder, OrderState, ResolverState, SupportOutcome,
 };
+use crate::geo::{ProvinceKey, RegionKey};
+use crate::order::Command;
+use crate::{Unit, UnitPosition, UnitPositions};
 use from_variants::FromVariants;
 use std::collections::HashMap;
 use std::fmt;
@@ -15,12 +21,14 @@ pub enum OrderOutcome<'a> {
     Move(AttackOutcome<'a>),
     Support(SupportOutcome<'a>),
     Convoy(ConvoyOutcome<'a>),
+    Rewritten(Rewrite<'a>),
 }
 
 impl From<&'_ OrderOutcome<'_>> for OrderState {
     fn from(other: &OrderOutcome<'_>) -> Self {
         match other {
             OrderOutcome::Invalid(i) => i.into(),
+            OrderOutcome::Rewritten(r) => r.outcome.as_ref().into(),
             OrderOutcome::Hold(h) => h.into(),
             OrderOutcome::Move(m) => m.into(),
             OrderOutcome::Support(s) => s.into(),
@@ -43,6 +51,7 @@ impl fmt::Debug for OrderOutcome<'_> {
             OrderOutcome::Move(oo) => oo.fmt(f),
             OrderOutcome::Support(oo) => oo.fmt(f),
             OrderOutcome::Convoy(oo) => oo.fmt(f),
+            OrderOutcome::Rewritten(oo) => oo.fmt(f),
         }
     }
 }
@@ -55,6 +64,7 @@ impl PartialEq<OrderState> for OrderOutcome<'_> {
 
 /// Outcome for an order that was invalid and not considered during adjudication.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InvalidOrder {
     /// There is no unit in position to act on the order.
     NoUnit,
@@ -64,6 +74,12 @@ pub enum InvalidOrder {
     /// The owning nation issued multiple orders to the same unit, and this order was discarded
     /// as a result.
     MultipleToSameUnit,
+    /// The order moves a fleet into a province with multiple coasts without naming one, and
+    /// more than one of those coasts is reachable.
+    AmbiguousCoast,
+    /// The order could never succeed, such as a move to a province the unit can't reach, and
+    /// the rules in use treat such orders as illegal. The unit held instead.
+    Impossible,
 }
 
 impl From<&'_ InvalidOrder> for OrderState {
@@ -72,6 +88,28 @@ impl From<&'_ InvalidOrder> for OrderState {
     }
 }
 
+/// Outcome for a submitted order whose coasts were corrected before adjudication. The
+/// rewritten order was adjudicated in its place, and its outcome is the outcome of the
+/// submitted order.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Rewrite<'a> {
+    /// The order that was adjudicated in place of the submitted order.
+    pub order: &'a MappedMainOrder,
+    pub reason: RewriteReason,
+    pub outcome: Box<OrderOutcome<'a>>,
+}
+
+/// The reason a submitted order was rewritten during coast normalization.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RewriteReason {
+    /// The order omitted a coast that could be determined from the map or the supported order.
+    CoastInferred,
+    /// The order named a coast that doesn't apply, such as a coast for an army or the wrong
+    /// coast for the ordered fleet.
+    CoastIgnored,
+}
+
 /// Contains information about the outcome of a turn, used for reporting back
 /// to players and for setting up the next turn.
 pub struct Outcome<'a, A> {
@@ -83,7 +121,7 @@ pub struct Outcome<'a, A> {
 impl<'a, A: Adjudicate> Outcome<'a, A> {
     pub(in crate::judge) fn new(context: Context<'a, A>, resolver: ResolverState<'a>) -> Self {
         let mut state = resolver.clone();
-        let orders = context
+        let mut orders = context
             .orders()
             .map(|ord| (ord, context.rules.explain(&context, &mut state, ord)))
             .chain(
@@ -92,7 +130,19 @@ impl<'a, A: Adjudicate> Outcome<'a, A> {
                     .iter()
                     .map(|(&ord, &reason)| (ord, reason.into())),
             )
-            .collect();
+            .collect::<HashMap<_, _>>();
+        for (&submitted, &(order, reason)) in &context.rewritten_orders {
+            let outcome = Box::new(context.rules.explain(&context, &mut state, order));
+            orders.insert(
+                submitted,
+                Rewrite {
+                    order,
+                    reason,
+                    outcome,
+                }
+                .into(),
+            );
+        }
 
         Self {
             context,
@@ -117,27 +167,245 @@ impl<'a, A: Adjudicate> Outcome<'a, A> {
         self.orders.get(order)
     }
 
+    /// Create an owned report of the outcome of every order.
+    pub fn to_report(&self) -> report::MainPhaseReport {
+        let mut orders = self
+            .orders
+            .iter()
+            .map(|(&order, outcome)| report::OrderReport {
+                order: order.clone(),
+                outcome: outcome.into(),
+            })
+            .collect::<Vec<_>>();
+        report::sort_reports(&mut orders);
+
+        report::MainPhaseReport { orders }
+    }
+
+    /// Explain the outcome of `order` in terms of the strengths and supports that decided it.
+    /// Returns `None` if the order is not known to the outcome.
+    pub fn explain(&'a self, order: &MappedMainOrder) -> Option<Explanation> {
+        let (&order, _) = self.orders.get_key_value(order)?;
+        Explainer::new(self).explain(order)
+    }
+
+    /// Explain the outcome of every order known to the outcome, in sorted order.
+    pub fn explanations(&'a self) -> Vec<Explanation> {
+        let mut orders = self.orders.keys().copied().collect::<Vec<_>>();
+        orders.sort();
+
+        let mut explainer = Explainer::new(self);
+        orders
+            .into_iter()
+            .filter_map(|order| explainer.explain(order))
+            .collect()
+    }
+
+    /// Calculate the attack, hold, defend and prevent strengths of `order`, with the supports
+    /// counted toward each. Returns `None` if the order wasn't adjudicated.
+    pub fn strengths(&'a self, order: &MappedMainOrder) -> Option<OrderStrengths> {
+        let order = self.context.orders().find(|o| *o == order)?;
+        Some(Explainer::new(self).strengths(order))
+    }
+
+    /// Calculate the strengths of every adjudicated order, in the order they were provided.
+    pub fn all_strengths(&'a self) -> Vec<OrderStrengths> {
+        let mut explainer = Explainer::new(self);
+        self.context
+            .orders()
+            .map(|order| explainer.strengths(order))
+            .collect()
+    }
+
+    /// The convoying fleets that carried `order` to its destination, starting with the fleet
+    /// next to the army. The route is reported even if the army was then stopped from entering
+    /// its destination. Returns `None` if `order` isn't a move by convoy, or no convoy route
+    /// remained open.
+    ///
+    /// Whether a move to an adjacent province is by convoy depends on the rulebook's
+    /// `ConvoyToAdjacent` rule. When several routes were open, the one with the fewest fleets
+    /// is reported.
+    pub fn convoy_route(&'a self, order: &MappedMainOrder) -> Option<Vec<&'a MappedMainOrder>> {
+        let order = self.context.orders().find(|o| *o == order)?;
+        if !calc::uses_convoy(&self.context, order) {
+            return None;
+        }
+
+        convoy::route(&self.context, &mut self.resolver.clone(), order)
+            .ok()
+            .flatten()
+    }
+
     /// Calculate retreat phase starting data based on this main-phase outcome.
     pub fn to_retreat_start(&'a self) -> retreat::Start<'a> {
         retreat::Start::new(self)
     }
 
+    /// Calculate the position of every unit at the end of this main phase, including the
+    /// dislodged units that have yet to retreat.
+    pub fn to_interim_positions(&'a self) -> InterimPositions<'a> {
+        InterimPositions::new(self, &self.dislodged())
+    }
+
+    /// Map of dislodged orders to the orders that dislodged them.
+    pub(in crate::judge) fn dislodged(
+        &'a self,
+    ) -> HashMap<&'a MappedMainOrder, &'a MappedMainOrder> {
+        let mut state = self.resolver.clone();
+        self.context
+            .orders()
+            .filter_map(|order| {
+                calc::dislodger_of(&self.context, &mut state, order).map(|by| (order, by))
+            })
+            .collect()
+    }
+
+    /// Create a copy of the outcome which owns its orders, so it can outlive the submission
+    /// and map it was adjudicated from. The retreat phase starting data is calculated up front.
+    pub fn into_owned(self) -> OwnedOutcome {
+        let retreat_start = retreat::Start::new(&self).into_owned();
+        let outcomes = self
+            .orders
+            .iter()
+            .map(|(&order, outcome)| (order.clone(), outcome.into()))
+            .collect();
+
+        OwnedOutcome {
+            orders: self.orders().cloned().collect(),
+            outcomes,
+            retreat_start,
+        }
+    }
+
+    /// Get the graph of dependencies between orders found while resolving this outcome.
     #[cfg(feature = "dependency-graph")]
-    pub fn dependencies(&self) -> impl fmt::Display {
-        struct Dependencies(std::collections::BTreeSet<(MappedMainOrder, MappedMainOrder)>);
-
-        impl fmt::Display for Dependencies {
-            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                writeln!(f, "digraph G {{")?;
-                for (src, dest) in &self.0 {
-                    writeln!(f, r#"  "{}" -> "{}""#, src, dest)?;
-                }
+    pub fn dependencies(&self) -> DependencyGraph {
+        DependencyGraph::new(self)
+    }
+}
 
-                writeln!(f, "}}")
+/// The state of the world between the main phase and retreat phases of a season.
+///
+/// To calculate valid retreat destinations, it's necessary to know which provinces are
+/// occupied when the retreats take place. It's therefore useful to compute positions
+/// based on order outcomes, so that moved units have vacated their old provinces and
+/// fill their new ones.
+///
+/// This approach creates a problem, however: Where are dislodged units? They haven't retreated
+/// yet, so logically it seems that they're in their old positions. However, reporting them
+/// there would mean multiple units are concurrently in the same province. To mitigate this,
+/// dislodged units are returned by `unit_positions` and `dislodged`, but they are never found
+/// by `find_province_occupier` or `find_region_occupier`.
+#[derive(Debug, Clone)]
+pub struct InterimPositions<'a> {
+    occupiers: HashMap<&'a ProvinceKey, UnitPosition<'a>>,
+    dislodged: Vec<UnitPosition<'a>>,
+}
+
+impl<'a> InterimPositions<'a> {
+    pub(in crate::judge) fn new<A: Adjudicate>(
+        outcome: &Outcome<'a, A>,
+        dislodged: &HashMap<&MappedMainOrder, &MappedMainOrder>,
+    ) -> Self {
+        let mut occupiers = HashMap::new();
+        let mut dislodged_units = vec![];
+        for order in outcome.context.orders() {
+            if dislodged.contains_key(order) {
+                dislodged_units.push(order.unit_position());
+                continue;
+            }
+
+            let moved = order.is_move()
+                && outcome
+                    .orders
+                    .get(order)
+                    .is_some_and(|result| *result == OrderState::Succeeds);
+
+            if moved {
+                let new_position = order.move_dest().unwrap();
+                occupiers.insert(
+                    new_position.province(),
+                    UnitPosition::new(Unit::from(order), new_position),
+                );
+            } else {
+                occupiers.insert(order.region.province(), order.unit_position());
             }
         }
 
-        Dependencies(self.resolver.dependencies())
+        Self {
+            occupiers,
+            dislodged: dislodged_units,
+        }
+    }
+
+    /// The dislodged units, in the regions they were dislodged from.
+    pub fn dislodged(&self) -> &[UnitPosition<'a>] {
+        &self.dislodged
+    }
+
+    /// The units that weren't dislodged, keyed by the province they occupy.
+    pub(in crate::judge) fn into_occupiers(self) -> HashMap<&'a ProvinceKey, UnitPosition<'a>> {
+        self.occupiers
+    }
+}
+
+impl UnitPositions<RegionKey> for InterimPositions<'_> {
+    fn unit_positions(&self) -> Vec<UnitPosition<'_>> {
+        let mut positions = self.occupiers.unit_positions();
+        positions.extend(self.dislodged.iter().cloned());
+        positions
+    }
+
+    fn find_province_occupier(&self, province: &ProvinceKey) -> Option<UnitPosition<'_>> {
+        self.occupiers.find_province_occupier(province)
+    }
+
+    fn find_region_occupier(&self, region: &RegionKey) -> Option<Unit<'_>> {
+        self.occupiers.find_region_occupier(region)
+    }
+}
+
+/// The outcome of a main phase, owning its orders so it can outlive the submission it was
+/// adjudicated from. Orders referenced by an outcome, such as the unit that cut a support,
+/// are cloned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedOutcome {
+    orders: Vec<MappedMainOrder>,
+    outcomes: HashMap<MappedMainOrder, report::OrderOutcome>,
+    retreat_start: retreat::OwnedStart,
+}
+
+impl OwnedOutcome {
+    /// The orders that participated in resolution, in the order they were provided. This does not
+    /// include invalid orders.
+    pub fn orders(&self) -> impl Iterator<Item = &MappedMainOrder> {
+        self.orders.iter()
+    }
+
+    /// The union of all orders known to the outcome. This will include any invalid orders and the hold
+    /// orders generated to ensure all units had an order during adjudication.
+    pub fn all_orders(&self) -> impl Iterator<Item = &MappedMainOrder> {
+        self.outcomes.keys()
+    }
+
+    pub fn get(&self, order: &MappedMainOrder) -> Option<&report::OrderOutcome> {
+        self.outcomes.get(order)
+    }
+
+    /// Retreat phase starting data based on this main-phase outcome.
+    pub fn to_retreat_start(&self) -> retreat::Start<'_> {
+        self.retreat_start.to_start()
+    }
+}
+
+#[allow(clippy::implicit_hasher)]
+impl From<OwnedOutcome> for HashMap<MappedMainOrder, OrderState> {
+    fn from(other: OwnedOutcome) -> Self {
+        other
+            .outcomes
+            .iter()
+            .map(|(ord, outcome)| (ord.clone(), outcome.into()))
+            .collect()
     }
 }
 
@@ -145,8 +413,7 @@ impl<'a, A: Adjudicate> Outcome<'a, A> {
 impl<A: Adjudicate> From<Outcome<'_, A>> for HashMap<MappedMainOrder, OrderState> {
     fn from(other: Outcome<'_, A>) -> Self {
         other
-            .context
-            .orders()
+            .all_orders()
             .map(|ord| {
                 (
                     ord.clone(),
@@ -0,0 +1,292 @@
+//! Owned reports of adjudication results for every phase.
+//!
+//! The outcomes produced by the judge borrow the orders they were given, and explain failures
+//! by pointing at other orders. Reports clone those orders instead, so they can outlive the
+//! submission. With the `serde` feature enabled, every report can be serialized, for example
+//! to send adjudication results to a web client as JSON.
+//!
+//! Orders in a report are listed in sorted order, so reports of the same adjudication are equal.
+
+use super::{
+    build, retreat, InvalidOrder, MappedBuildOrder, MappedMainOrder, MappedRetreatOrder,
+    OrderState, RewriteReason,
+};
+use crate::geo::RegionKey;
+use crate::{UnitPosition, UnitType};
+
+/// An order and the result of adjudicating it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrderReport<O, R> {
+    pub order: O,
+    pub outcome: R,
+}
+
+/// The outcome of a main-phase order. This mirrors `judge::OrderOutcome`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OrderOutcome {
+    Invalid(InvalidOrder),
+    Hold(HoldOutcome),
+    Move(AttackOutcome),
+    Support(SupportOutcome),
+    Convoy(ConvoyOutcome),
+    /// The submitted order's coasts were corrected, and `order` was adjudicated in its place
+    /// with `outcome`.
+    Rewritten {
+        order: MappedMainOrder,
+        reason: RewriteReason,
+        outcome: Box<OrderOutcome>,
+    },
+}
+
+/// The outcome of a hold order. This mirrors `judge::HoldOutcome`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HoldOutcome {
+    Succeeds,
+    Dislodged(MappedMainOrder),
+}
+
+/// The outcome of a move order. This mirrors `judge::AttackOutcome`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AttackOutcome {
+    MoveToSelf,
+    NoPath,
+    FriendlyFire,
+    Prevented(Prevent),
+    LostHeadToHead,
+    OccupierDefended,
+    Paradox,
+    Succeeds,
+}
+
+/// The strongest attempt by another unit to prevent a move into its destination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Prevent {
+    /// The preventing unit cannot reach its destination.
+    NoPath,
+    /// The preventing unit lost a head-to-head battle.
+    LostHeadToHead,
+    /// The order prevented the move, with the support of `supporters`.
+    Prevents {
+        order: MappedMainOrder,
+        supporters: Vec<MappedMainOrder>,
+    },
+}
+
+/// The outcome of a support order. This mirrors `judge::SupportOutcome`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SupportOutcome {
+    NotDisrupted,
+    SupportingSelf,
+    CantReach,
+    CutBy(MappedMainOrder),
+    Paradox,
+}
+
+/// The outcome of a convoy order. This mirrors `judge::ConvoyOutcome`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConvoyOutcome {
+    NotAtSea,
+    Dislodged(MappedMainOrder),
+    Paradox,
+    NotDisrupted,
+}
+
+/// The outcome of a retreat-phase order. This mirrors `retreat::OrderOutcome`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RetreatOutcome {
+    Prevented(MappedRetreatOrder),
+    InvalidDestination(retreat::DestStatus),
+    InvalidRecipient,
+    Moves,
+    DisbandsAsOrdered,
+}
+
+/// The results of a main phase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MainPhaseReport {
+    /// Every order known to the outcome, including invalid orders and generated hold orders.
+    pub orders: Vec<OrderReport<MappedMainOrder, OrderOutcome>>,
+}
+
+/// The dislodged units at the start of a retreat phase, and where each of them can go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RetreatStartReport {
+    pub dislodged: Vec<Dislodgement>,
+}
+
+/// A unit dislodged in a main phase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dislodgement {
+    /// The main-phase order of the dislodged unit.
+    pub order: MappedMainOrder,
+    /// The order that dislodged the unit.
+    pub dislodger: MappedMainOrder,
+    /// The regions adjacent to the unit and whether it can retreat to each of them.
+    pub destinations: Vec<(RegionKey, retreat::DestStatus)>,
+}
+
+/// The results of a retreat phase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RetreatPhaseReport {
+    pub orders: Vec<OrderReport<MappedRetreatOrder, RetreatOutcome>>,
+    /// The units on the board after the retreat phase, in order of their regions.
+    pub units: Vec<UnitPosition<'static, RegionKey>>,
+}
+
+/// The results of a build phase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BuildPhaseReport {
+    pub orders: Vec<OrderReport<MappedBuildOrder, build::OrderOutcome>>,
+    /// The units disbanded because their nation didn't issue enough disband orders.
+    pub civil_disorder: Vec<(UnitType, RegionKey)>,
+    /// The units on the board after the build phase, in order of their regions.
+    pub units: Vec<UnitPosition<'static, RegionKey>>,
+}
+
+impl From<&'_ OrderOutcome> for OrderState {
+    fn from(other: &OrderOutcome) -> Self {
+        let succeeds = match other {
+            OrderOutcome::Invalid(_) => false,
+            OrderOutcome::Hold(outcome) => *outcome == HoldOutcome::Succeeds,
+            OrderOutcome::Move(outcome) => *outcome == AttackOutcome::Succeeds,
+            OrderOutcome::Support(outcome) => *outcome == SupportOutcome::NotDisrupted,
+            OrderOutcome::Convoy(outcome) => *outcome == ConvoyOutcome::NotDisrupted,
+            OrderOutcome::Rewritten { outcome, .. } => return outcome.as_ref().into(),
+        };
+
+        if succeeds {
+            OrderState::Succeeds
+        } else {
+            OrderState::Fails
+        }
+    }
+}
+
+impl From<&'_ super::OrderOutcome<'_>> for OrderOutcome {
+    fn from(other: &super::OrderOutcome<'_>) -> Self {
+        match other {
+            super::OrderOutcome::Invalid(reason) => OrderOutcome::Invalid(*reason),
+            super::OrderOutcome::Hold(outcome) => OrderOutcome::Hold(outcome.into()),
+            super::OrderOutcome::Move(outcome) => OrderOutcome::Move(outcome.into()),
+            super::OrderOutcome::Support(outcome) => OrderOutcome::Support(outcome.into()),
+            super::OrderOutcome::Convoy(outcome) => OrderOutcome::Convoy(outcome.into()),
+            super::OrderOutcome::Rewritten(rewrite) => OrderOutcome::Rewritten {
+                order: rewrite.order.clone(),
+                reason: rewrite.reason,
+                outcome: Box::new(rewrite.outcome.as_ref().into()),
+            },
+        }
+    }
+}
+
+impl From<&'_ super::HoldOutcome<'_>> for HoldOutcome {
+    fn from(other: &super::HoldOutcome<'_>) -> Self {
+        match other {
+            super::HoldOutcome::Succeeds => HoldOutcome::Succeeds,
+            super::HoldOutcome::Dislodged(by) => HoldOutcome::Dislodged((*by).clone()),
+        }
+    }
+}
+
+impl From<&'_ super::AttackOutcome<'_>> for AttackOutcome {
+    fn from(other: &super::AttackOutcome<'_>) -> Self {
+        use super::AttackOutcome as A;
+        match other {
+            A::MoveToSelf => AttackOutcome::MoveToSelf,
+            A::NoPath => AttackOutcome::NoPath,
+            A::FriendlyFire => AttackOutcome::FriendlyFire,
+            A::Prevented(prevent) => AttackOutcome::Prevented(prevent.into()),
+            A::LostHeadToHead => AttackOutcome::LostHeadToHead,
+            A::OccupierDefended => AttackOutcome::OccupierDefended,
+            A::Paradox => AttackOutcome::Paradox,
+            A::Succeeds => AttackOutcome::Succeeds,
+        }
+    }
+}
+
+impl From<&'_ super::Prevent<'_>> for Prevent {
+    fn from(other: &super::Prevent<'_>) -> Self {
+        match other {
+            super::Prevent::NoPath => Prevent::NoPath,
+            super::Prevent::LostHeadToHead => Prevent::LostHeadToHead,
+            super::Prevent::Prevents(order, supporters) => Prevent::Prevents {
+                order: (*order).clone(),
+                supporters: sorted(supporters.iter().map(|&s| s.clone())),
+            },
+        }
+    }
+}
+
+impl From<&'_ super::SupportOutcome<'_>> for SupportOutcome {
+    fn from(other: &super::SupportOutcome<'_>) -> Self {
+        use super::SupportOutcome as S;
+        match other {
+            S::NotDisrupted => SupportOutcome::NotDisrupted,
+            S::SupportingSelf => SupportOutcome::SupportingSelf,
+            S::CantReach => SupportOutcome::CantReach,
+            S::CutBy(by) => SupportOutcome::CutBy((*by).clone()),
+            S::Paradox => SupportOutcome::Paradox,
+        }
+    }
+}
+
+impl From<&'_ super::ConvoyOutcome<'_>> for ConvoyOutcome {
+    fn from(other: &super::ConvoyOutcome<'_>) -> Self {
+        use super::ConvoyOutcome as C;
+        match other {
+            C::NotAtSea => ConvoyOutcome::NotAtSea,
+            C::Dislodged(by) => ConvoyOutcome::Dislodged((*by).clone()),
+            C::Paradox => ConvoyOutcome::Paradox,
+            C::NotDisrupted => ConvoyOutcome::NotDisrupted,
+        }
+    }
+}
+
+impl From<&'_ retreat::OrderOutcome<'_>> for RetreatOutcome {
+    fn from(other: &retreat::OrderOutcome<'_>) -> Self {
+        use retreat::OrderOutcome as R;
+        match other {
+            R::Prevented(by) => RetreatOutcome::Prevented((*by).clone()),
+            R::InvalidDestination(status) => RetreatOutcome::InvalidDestination(*status),
+            R::InvalidRecipient => RetreatOutcome::InvalidRecipient,
+            R::Moves => RetreatOutcome::Moves,
+            R::DisbandsAsOrdered => RetreatOutcome::DisbandsAsOrdered,
+        }
+    }
+}
+
+fn sorted<T: Ord>(items: impl IntoIterator<Item = T>) -> Vec<T> {
+    let mut items = items.into_iter().collect::<Vec<_>>();
+    items.sort();
+    items
+}
+
+/// Sort order reports by their orders.
+pub(in crate::judge) fn sort_reports<O: Ord, R>(reports: &mut [OrderReport<O, R>]) {
+    reports.sort_by(|a, b| a.order.cmp(&b.order));
+}
+
+/// Clone unit positions into owned positions, sorted by region.
+pub(in crate::judge) fn owned_positions<'a>(
+    positions: impl IntoIterator<Item = UnitPosition<'a>>,
+) -> Vec<UnitPosition<'static, RegionKey>> {
+    let mut units = positions
+        .into_iter()
+        .map(UnitPosition::into_owned)
+        .collect::<Vec<_>>();
+    units.sort_by(|a, b| a.region.cmp(&b.region));
+    units
+}
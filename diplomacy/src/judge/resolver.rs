@@ -1,17 +1,38 @@
-use super::{Adjudicate, InvalidOrder, MappedMainOrder, OrderState, Outcome, Rulebook};
+use super::convoy;
+use super::normalize::{self, Normalized};
+use super::sanity;
+use super::{
+    Adjudicate, InvalidOrder, MappedMainOrder, MultipleOrders, OrderState, Outcome, ParadoxRule,
+    RewriteReason, Rulebook,
+};
 use crate::geo::{Map, ProvinceKey, RegionKey};
-use crate::order::{Command, MainCommand, Order};
+use crate::order::{Command, MainCommand, Order, SupportedOrder};
 use crate::{Unit, UnitPosition, UnitPositions};
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
 #[cfg(feature = "dependency-graph")]
 use std::{cell::RefCell, collections::BTreeSet, rc::Rc};
 
 pub struct Submission {
     submitted_orders: Vec<MappedMainOrder>,
+    /// The unit addressed by each submitted order, or `None` if the order doesn't address one of
+    /// the issuer's units. Orders are matched to units by province, so a fleet ordered with the
+    /// wrong coast is still found. This is index-aligned with `submitted_orders`.
+    units: Vec<Option<UnitPosition<'static, RegionKey>>>,
+    /// The digest of the map first passed to `Submission::adjudicate`, and the coast
+    /// normalization of each submitted order against that map. The normalization is
+    /// index-aligned with `submitted_orders`.
+    normalized: OnceLock<(u64, Vec<Normalized>)>,
+    /// Hold orders for the unit addressed by each submitted order, used when the rules replace
+    /// that order with a hold. This is index-aligned with `submitted_orders`.
+    fallback_holds: Vec<MappedMainOrder>,
     civil_disorder_orders: Vec<MappedMainOrder>,
     /// A map of indexes in `submitted_orders` to the reason those orders are invalid.
     // This uses indices because Rust doesn't support self-referential structs.
     invalid_orders: HashMap<usize, InvalidOrder>,
+    /// The unit addressed by each adjudicated order, keyed by province for fast lookup.
+    positions: HashMap<ProvinceKey, UnitPosition<'static, RegionKey>>,
 }
 
 impl Submission {
@@ -35,43 +56,194 @@ impl Submission {
     ) -> Self {
         let mut temp = Submission {
             submitted_orders: orders,
+            units: vec![],
+            normalized: OnceLock::new(),
+            fallback_holds: vec![],
             civil_disorder_orders: vec![],
             invalid_orders: HashMap::new(),
+            positions: HashMap::new(),
         };
 
-        let (invalid_orders, missing_orders) = if let Some(start) = start {
-            temp.finish_creation(start)
+        if let Some(start) = start {
+            temp.finish_creation(start);
         } else {
-            temp.finish_creation(&temp.submitted_orders)
-        };
-        temp.invalid_orders = invalid_orders;
-        temp.civil_disorder_orders = missing_orders;
+            let inferred = temp.submitted_orders.clone();
+            temp.finish_creation(&inferred);
+        }
 
         temp
     }
 
-    /// Adjudicate the submission using the provided map and rules
+    /// Adjudicate the submission using the provided map and rules.
+    ///
+    /// Before adjudication, coasts in the submitted orders are normalized against `world`.
+    /// Missing coasts are filled in when only one coast is reachable, coasts are dropped from
+    /// army orders, and a wrong coast for the ordered fleet's own position is ignored. The
+    /// rewritten order is adjudicated in place of the submitted one, and the submitted order's
+    /// outcome is `OrderOutcome::Rewritten`. Orders that name none of several reachable coasts
+    /// are rejected with `InvalidOrder::AmbiguousCoast`, and the unit holds.
+    ///
+    /// Normalization happens on the first call and is reused by later ones, so a submission
+    /// must always be adjudicated against the same map. Passing a map that differs from the
+    /// first one panics.
     pub fn adjudicate<'a, A: Adjudicate>(&'a self, world: &'a Map, rules: A) -> Outcome<'a, A> {
-        let invalid_orders = self
-            .invalid_orders
+        let (normalized_against, normalized) = self.normalized.get_or_init(|| {
+            let normalized = normalize::normalize_orders(world, self, &self.submitted_orders);
+            (world.digest(), normalized)
+        });
+        assert!(
+            *normalized_against == world.digest(),
+            "A submission must be adjudicated against the same map on every call"
+        );
+
+        let mut invalid_orders = self.invalid_orders.clone();
+        let mut held = HashSet::new();
+
+        let mut rejected = vec![];
+        for (idx, result) in normalized.iter().enumerate() {
+            if let Normalized::Rejected(reason) = result {
+                if self.units[idx].is_some() {
+                    invalid_orders.insert(idx, *reason);
+                    rejected.push(idx);
+                }
+            }
+        }
+
+        self.apply_multiple_orders_rule(rules.multiple_orders(), &mut invalid_orders, &mut held);
+
+        // A unit whose only orders were rejected during normalization holds instead.
+        let mut ordered_units = (0..self.submitted_orders.len())
+            .filter(|idx| held.contains(idx) || !invalid_orders.contains_key(idx))
+            .filter_map(|idx| self.units[idx].as_ref())
+            .collect::<HashSet<_>>();
+        for idx in rejected {
+            if let Some(unit) = &self.units[idx] {
+                if ordered_units.insert(unit) {
+                    held.insert(idx);
+                }
+            }
+        }
+
+        if rules.illegal_orders_hold() {
+            let positions = self.orders_to_adjudicate(normalized, &invalid_orders, &held);
+            for idx in 0..self.submitted_orders.len() {
+                if !invalid_orders.contains_key(&idx)
+                    && !held.contains(&idx)
+                    && sanity::is_impossible(
+                        world,
+                        &positions,
+                        self.effective_order(normalized, idx),
+                    )
+                {
+                    invalid_orders.insert(idx, InvalidOrder::Impossible);
+                    held.insert(idx);
+                }
+            }
+        }
+
+        let orders = self.orders_to_adjudicate(normalized, &invalid_orders, &held);
+        let rewritten_orders = normalized
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !invalid_orders.contains_key(idx) && !held.contains(idx))
+            .filter_map(|(idx, result)| match result {
+                Normalized::Rewritten(order, reason) => {
+                    Some((&self.submitted_orders[idx], (order, *reason)))
+                }
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+        let invalid_orders = invalid_orders
             .iter()
             .map(|(idx, reason)| (&self.submitted_orders[*idx], *reason))
             .collect::<HashMap<_, _>>();
 
-        let mut context = Context::new(
-            world,
-            rules,
-            self.submitted_orders
-                .iter()
-                .filter(|o| !invalid_orders.contains_key(o))
-                .chain(&self.civil_disorder_orders),
-        );
+        let mut context = Context::new(world, rules, orders);
 
         context.invalid_orders = invalid_orders;
+        context.rewritten_orders = rewritten_orders;
 
         context.resolve()
     }
 
+    /// Choose which of several orders to the same unit is followed. `Submission` creation follows
+    /// the first order; other rules override that choice here. Orders that were rejected during
+    /// normalization are not considered.
+    fn apply_multiple_orders_rule(
+        &self,
+        rule: MultipleOrders,
+        invalid_orders: &mut HashMap<usize, InvalidOrder>,
+        held: &mut HashSet<usize>,
+    ) {
+        let mut by_unit = HashMap::<_, Vec<usize>>::new();
+        for idx in 0..self.submitted_orders.len() {
+            let reason = invalid_orders.get(&idx);
+            if reason.is_none() || reason == Some(&InvalidOrder::MultipleToSameUnit) {
+                if let Some(unit) = &self.units[idx] {
+                    by_unit.entry(unit).or_default().push(idx);
+                }
+            }
+        }
+
+        for indices in by_unit.values() {
+            if let [only] = indices.as_slice() {
+                invalid_orders.remove(only);
+                continue;
+            }
+
+            for idx in indices {
+                invalid_orders.insert(*idx, InvalidOrder::MultipleToSameUnit);
+            }
+
+            match rule {
+                MultipleOrders::First => {
+                    invalid_orders.remove(&indices[0]);
+                }
+                MultipleOrders::Last => {
+                    invalid_orders.remove(&indices[indices.len() - 1]);
+                }
+                MultipleOrders::Hold => {
+                    held.insert(indices[0]);
+                }
+            }
+        }
+    }
+
+    /// Get the orders to adjudicate given the invalid orders and the indices of submitted orders
+    /// whose unit should hold instead.
+    fn orders_to_adjudicate<'a>(
+        &'a self,
+        normalized: &'a [Normalized],
+        invalid_orders: &HashMap<usize, InvalidOrder>,
+        held: &HashSet<usize>,
+    ) -> Vec<&'a MappedMainOrder> {
+        (0..self.submitted_orders.len())
+            .filter_map(|idx| {
+                if held.contains(&idx) {
+                    Some(&self.fallback_holds[idx])
+                } else if !invalid_orders.contains_key(&idx) {
+                    Some(self.effective_order(normalized, idx))
+                } else {
+                    None
+                }
+            })
+            .chain(&self.civil_disorder_orders)
+            .collect()
+    }
+
+    /// The order that is adjudicated for a submitted order: its rewrite, if normalization
+    /// produced one, or the submitted order itself.
+    fn effective_order<'a>(
+        &'a self,
+        normalized: &'a [Normalized],
+        idx: usize,
+    ) -> &'a MappedMainOrder {
+        match normalized.get(idx) {
+            Some(Normalized::Rewritten(order, _)) => order,
+            _ => &self.submitted_orders[idx],
+        }
+    }
+
     /// The exact orders that were provided at submission time, including invalid orders and
     /// excluding orders generated due to civil disorder.
     pub fn submitted_orders(&self) -> impl Iterator<Item = &MappedMainOrder> {
@@ -83,90 +255,136 @@ impl Submission {
         self.civil_disorder_orders.iter()
     }
 
-    /// The orders that are used for the remainder of adjudication. This contains exactly one
-    /// well-formed order for each unit in play.
+    /// The orders that are used for the remainder of adjudication under the default rules. This
+    /// contains exactly one order for each unit in play.
+    ///
+    /// Rules which pick a different order when a unit receives several, or which replace
+    /// impossible orders with holds, adjust this set during `Submission::adjudicate`. Coasts are
+    /// also normalized there, so these orders are as submitted.
     pub fn adjudicated_orders(&self) -> impl Iterator<Item = &MappedMainOrder> {
-        let invalid = self
-            .invalid_orders
-            .keys()
-            .map(|idx| &self.submitted_orders[*idx])
-            .collect::<HashSet<_>>();
-
-        self.submitted_orders()
-            .filter(move |ord| !invalid.contains(ord))
-            .chain(&self.civil_disorder_orders)
+        self.orders_to_adjudicate(&[], &self.invalid_orders, &HashSet::new())
+            .into_iter()
     }
 
     /// After we create the struct we have to finish up the creation process by removing
     /// invalid orders and injecting holds for units that are missing orders.
-    fn finish_creation(
-        &self,
-        start: &impl UnitPositions<RegionKey>,
-    ) -> (HashMap<usize, InvalidOrder>, Vec<MappedMainOrder>) {
-        let mut invalid_orders = HashMap::new();
-        let mut inserted_orders = vec![];
-
+    ///
+    /// An order that names the wrong coast for the issuer's unit in a province is matched to
+    /// that unit; `Submission::adjudicate` then corrects the order's coast.
+    fn finish_creation(&mut self, start: &impl UnitPositions<RegionKey>) {
         let positions = start.unit_positions().into_iter().collect::<HashSet<_>>();
+        let occupied_regions = self
+            .submitted_orders
+            .iter()
+            .map(|order| &order.region)
+            .collect::<HashSet<_>>();
         let mut ordered_units = HashSet::new();
 
         // Reject any invalid orders to prevent them being considered for the rest of
         // the resolution process.
         for (index, order) in self.submitted_orders.iter().enumerate() {
-            if !positions.contains(&order.unit_position()) {
-                if self.find_region_occupier(&order.region).is_some() {
-                    invalid_orders.insert(index, InvalidOrder::ForeignUnit);
-                } else {
-                    invalid_orders.insert(index, InvalidOrder::NoUnit);
+            let unit = if positions.contains(&order.unit_position()) {
+                Some(order.unit_position())
+            } else {
+                start
+                    .find_province_occupier(order.region.province())
+                    .filter(|unit| {
+                        unit.nation() == &order.nation && unit.unit.unit_type() == order.unit_type
+                    })
+            };
+
+            match unit {
+                Some(unit) => {
+                    if !ordered_units.insert(unit.clone()) {
+                        self.invalid_orders
+                            .insert(index, InvalidOrder::MultipleToSameUnit);
+                    }
+                    self.units.push(Some(unit.into_owned()));
+                }
+                None => {
+                    if occupied_regions.contains(&order.region) {
+                        self.invalid_orders.insert(index, InvalidOrder::ForeignUnit);
+                    } else {
+                        self.invalid_orders.insert(index, InvalidOrder::NoUnit);
+                    }
+                    self.units.push(None);
                 }
-            } else if !ordered_units.insert(order) {
-                invalid_orders.insert(index, InvalidOrder::MultipleToSameUnit);
             }
         }
 
-        let invalids = invalid_orders
-            .keys()
-            .map(|idx| &self.submitted_orders[*idx])
-            .collect::<HashSet<_>>();
-
-        // Having rejected invalid orders, we figure out which positions still
-        let positions_with_valid_orders = self
-            .submitted_orders()
-            .filter(|o| !invalids.contains(o))
-            .map(|o| o.unit_position())
-            .collect::<HashSet<_>>();
-
         // Issue hold orders to any units that don't have orders.
-        for position in positions.difference(&positions_with_valid_orders) {
-            inserted_orders.push(Order::new(
-                position.nation().clone(),
-                position.unit.unit_type(),
-                position.region.clone(),
-                MainCommand::Hold,
-            ));
+        for position in &positions {
+            if !ordered_units.contains(position) {
+                self.civil_disorder_orders.push(Order::new(
+                    position.nation().clone(),
+                    position.unit.unit_type(),
+                    position.region.clone(),
+                    MainCommand::Hold,
+                ));
+            }
         }
 
-        (invalid_orders, inserted_orders)
+        self.fallback_holds = self
+            .submitted_orders
+            .iter()
+            .zip(&self.units)
+            .map(|(order, unit)| {
+                let region = unit.as_ref().map_or(&order.region, |unit| &unit.region);
+                Order::new(
+                    order.nation.clone(),
+                    order.unit_type,
+                    region.clone(),
+                    MainCommand::Hold,
+                )
+            })
+            .collect();
+
+        for (index, unit) in self.units.iter().enumerate() {
+            if let Some(unit) = unit {
+                if !self.invalid_orders.contains_key(&index) {
+                    self.positions
+                        .entry(unit.region.province().clone())
+                        .or_insert_with(|| unit.clone());
+                }
+            }
+        }
+        for order in &self.civil_disorder_orders {
+            self.positions
+                .entry(order.region.province().clone())
+                .or_insert_with(|| order.unit_position().into_owned());
+        }
     }
 }
 
 /// Unit positions at the start of the turn.
 impl UnitPositions<RegionKey> for Submission {
     fn unit_positions(&self) -> Vec<UnitPosition<'_>> {
-        self.adjudicated_orders()
-            .map(|ord| ord.unit_position())
+        (0..self.submitted_orders.len())
+            .filter(|idx| !self.invalid_orders.contains_key(idx))
+            .filter_map(|idx| self.units[idx].as_ref())
+            .map(UnitPosition::as_region_ref)
+            .chain(
+                self.civil_disorder_orders
+                    .iter()
+                    .map(|order| order.unit_position()),
+            )
             .collect()
     }
 
     fn find_province_occupier(&self, province: &ProvinceKey) -> Option<UnitPosition<'_>> {
-        self.adjudicated_orders()
-            .find(|ord| ord.region.province() == province)
-            .map(|ord| ord.unit_position())
+        self.positions.get(province).map(|position| {
+            UnitPosition::new(
+                Unit::new(Cow::Borrowed(position.nation()), position.unit.unit_type()),
+                &position.region,
+            )
+        })
     }
 
     fn find_region_occupier(&self, region: &RegionKey) -> Option<Unit<'_>> {
-        self.adjudicated_orders()
-            .find(|ord| ord.region == *region)
-            .map(|ord| ord.unit_position().unit)
+        self.positions
+            .get(region.province())
+            .filter(|position| position.region == *region)
+            .map(|position| Unit::new(Cow::Borrowed(position.nation()), position.unit.unit_type()))
     }
 }
 
@@ -181,12 +399,20 @@ pub struct Context<'a, A> {
     /// Set of orders which were issued during this turn.
     orders: Vec<&'a MappedMainOrder>,
 
+    /// Lookups of related orders, used by the resolver in place of scanning `orders`.
+    index: OrderIndex<'a>,
+
     pub rules: A,
 
     /// The map against which orders were issued.
     pub world_map: &'a Map,
 
     pub(in crate::judge) invalid_orders: HashMap<&'a MappedMainOrder, InvalidOrder>,
+
+    /// Submitted orders that were rewritten during coast normalization, with the order
+    /// adjudicated in their place and the reason for the rewrite.
+    pub(in crate::judge) rewritten_orders:
+        HashMap<&'a MappedMainOrder, (&'a MappedMainOrder, RewriteReason)>,
 }
 
 impl<'a, A: Adjudicate> Context<'a, A> {
@@ -196,11 +422,14 @@ impl<'a, A: Adjudicate> Context<'a, A> {
         rules: A,
         orders: impl IntoIterator<Item = &'a MappedMainOrder>,
     ) -> Self {
+        let orders = orders.into_iter().collect::<Vec<_>>();
         Context {
             world_map,
             rules,
-            orders: orders.into_iter().collect(),
+            index: OrderIndex::new(&orders),
+            orders,
             invalid_orders: HashMap::new(),
+            rewritten_orders: HashMap::new(),
         }
     }
 
@@ -231,8 +460,105 @@ impl<'a, A: Adjudicate> Context<'a, A> {
         Outcome::new(self, rs)
     }
 
+    /// Get the order given to the unit in province `p`.
     pub fn find_order_to_province(&self, p: &ProvinceKey) -> Option<&'a MappedMainOrder> {
-        self.orders().find(|o| &o.region == p)
+        self.index.by_province.get(p).copied()
+    }
+
+    /// Get the moves into province `p`, in the order they were submitted.
+    pub fn moves_to_province<'b>(
+        &'b self,
+        p: &ProvinceKey,
+    ) -> impl 'b + Iterator<Item = &'a MappedMainOrder>
+    where
+        'a: 'b,
+    {
+        OrderIndex::lookup(&self.index.moves_to, p)
+    }
+
+    /// Get the support orders naming `supported`, whether or not they are legal or successful.
+    pub fn supports_for<'b>(
+        &'b self,
+        supported: &'b MappedMainOrder,
+    ) -> impl 'b + Iterator<Item = &'a MappedMainOrder>
+    where
+        'a: 'b,
+    {
+        OrderIndex::lookup(&self.index.supports_for, supported.region.province()).filter(
+            move |support| match &support.command {
+                MainCommand::Support(beneficiary) => beneficiary == supported,
+                _ => false,
+            },
+        )
+    }
+
+    /// Get the convoy orders naming `mv_ord`, whether or not they are successful.
+    pub fn convoys_for<'b>(
+        &'b self,
+        mv_ord: &'b MappedMainOrder,
+    ) -> impl 'b + Iterator<Item = &'a MappedMainOrder>
+    where
+        'a: 'b,
+    {
+        OrderIndex::lookup(&self.index.convoys_for, mv_ord.region.province())
+            .filter(move |convoy| convoy::is_convoy_for(convoy, mv_ord))
+    }
+}
+
+/// Orders grouped by the province they relate to. Each group keeps the submission order, so
+/// lookups return orders in the same order as a scan of every order would.
+struct OrderIndex<'a> {
+    /// The order given to the unit in each province.
+    by_province: HashMap<&'a ProvinceKey, &'a MappedMainOrder>,
+    /// Moves into each province.
+    moves_to: HashMap<&'a ProvinceKey, Vec<&'a MappedMainOrder>>,
+    /// Supports for the unit in each province.
+    supports_for: HashMap<&'a ProvinceKey, Vec<&'a MappedMainOrder>>,
+    /// Convoys for the army in each province.
+    convoys_for: HashMap<&'a ProvinceKey, Vec<&'a MappedMainOrder>>,
+}
+
+impl<'a> OrderIndex<'a> {
+    fn new(orders: &[&'a MappedMainOrder]) -> Self {
+        let mut index = OrderIndex {
+            by_province: HashMap::new(),
+            moves_to: HashMap::new(),
+            supports_for: HashMap::new(),
+            convoys_for: HashMap::new(),
+        };
+
+        for &order in orders {
+            index
+                .by_province
+                .entry(order.region.province())
+                .or_insert(order);
+
+            let group = match &order.command {
+                MainCommand::Hold => None,
+                MainCommand::Move(cmd) => Some((&mut index.moves_to, cmd.dest())),
+                MainCommand::Support(SupportedOrder::Hold(_, region))
+                | MainCommand::Support(SupportedOrder::Move(_, region, _)) => {
+                    Some((&mut index.supports_for, region))
+                }
+                MainCommand::Convoy(mv) => Some((&mut index.convoys_for, mv.from())),
+            };
+
+            if let Some((group, region)) = group {
+                group.entry(region.province()).or_default().push(order);
+            }
+        }
+
+        index
+    }
+
+    fn lookup<'b>(
+        group: &'b HashMap<&'a ProvinceKey, Vec<&'a MappedMainOrder>>,
+        province: &ProvinceKey,
+    ) -> impl 'b + Iterator<Item = &'a MappedMainOrder>
+    where
+        'a: 'b,
+    {
+        group.get(province).into_iter().flatten().copied()
     }
 }
 
@@ -271,11 +597,24 @@ impl std::fmt::Debug for ResolutionState {
     }
 }
 
+/// A change to the resolver state made while a guess is open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Undo<'a> {
+    /// The resolution state was changed; this holds the state it had before.
+    State(&'a MappedMainOrder, Option<ResolutionState>),
+    /// The order was added to the paradoxical orders.
+    Paradox(&'a MappedMainOrder),
+    /// An order was pushed onto the dependency chain.
+    Push,
+    /// The order was popped off the dependency chain.
+    Pop(&'a MappedMainOrder),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ResolverState<'a> {
     state: HashMap<&'a MappedMainOrder, ResolutionState>,
-    /// Orders which form part of a paradox. These should only be convoy orders, and will
-    /// be treated as hold orders to advance resolution.
+    /// Orders which form part of a paradox. How these are treated depends on the paradox rule;
+    /// under the default Szykman rule these are only convoy orders, which are treated as failed.
     paradoxical_orders: HashSet<&'a MappedMainOrder>,
 
     /// A dependency chain which adds every order as soon as a guess is made. This is used
@@ -285,9 +624,17 @@ pub struct ResolverState<'a> {
     /// A set containing directed edges in a graph of order dependencies.
     #[cfg(feature = "dependency-graph")]
     deps: Rc<RefCell<BTreeSet<(MappedMainOrder, MappedMainOrder)>>>,
+    /// Every dependency cycle the resolver tried to break, with its orders in sorted order.
+    #[cfg(feature = "dependency-graph")]
+    cycles: Rc<RefCell<BTreeSet<Vec<MappedMainOrder>>>>,
     /// The conservative dependency chain used to trigger cycle detection. This contains
     /// guesses that have been visited twice, indicating that a cycle has been found.
     dependency_chain: Vec<&'a MappedMainOrder>,
+    /// Changes made since the outermost open guess, newest last, so that a guess which doesn't
+    /// work out can be taken back. This is empty when no guess is open.
+    undo_log: Vec<Undo<'a>>,
+    /// The number of guesses currently being evaluated.
+    open_guesses: usize,
 
     pub(in crate::judge) invalid_orders: HashMap<&'a MappedMainOrder, InvalidOrder>,
 }
@@ -300,8 +647,11 @@ impl<'a> ResolverState<'a> {
             ResolverState {
                 state: HashMap::new(),
                 deps: Rc::new(RefCell::new(BTreeSet::default())),
+                cycles: Rc::new(RefCell::new(BTreeSet::default())),
                 greedy_chain: vec![],
                 dependency_chain: vec![],
+                undo_log: vec![],
+                open_guesses: 0,
                 paradoxical_orders: HashSet::new(),
                 invalid_orders: HashMap::new(),
             }
@@ -312,92 +662,182 @@ impl<'a> ResolverState<'a> {
             ResolverState {
                 state: HashMap::new(),
                 dependency_chain: vec![],
+                undo_log: vec![],
+                open_guesses: 0,
                 paradoxical_orders: HashSet::new(),
                 invalid_orders: HashMap::new(),
             }
         }
     }
 
-    fn clear_state(&mut self, order: &MappedMainOrder) {
-        self.state.remove(order);
+    /// Record a change if a guess is open, so that it can be undone.
+    fn record(&mut self, change: Undo<'a>) {
+        if self.open_guesses > 0 {
+            self.undo_log.push(change);
+        }
+    }
+
+    fn replace_state(&mut self, order: &'a MappedMainOrder, resolution: Option<ResolutionState>) {
+        let previous = match resolution {
+            Some(resolution) => self.state.insert(order, resolution),
+            None => self.state.remove(order),
+        };
+
+        self.record(Undo::State(order, previous));
+    }
+
+    fn clear_state(&mut self, order: &'a MappedMainOrder) {
+        self.replace_state(order, None);
     }
 
     fn set_state(&mut self, order: &'a MappedMainOrder, resolution: ResolutionState) {
-        self.state.insert(order, resolution);
+        self.replace_state(order, Some(resolution));
     }
 
     fn knows_outcome_of(&self, order: &MappedMainOrder) -> bool {
-        if let Some(ResolutionState::Known(_)) = self.state.get(order) {
-            true
-        } else {
-            false
-        }
+        matches!(self.state.get(order), Some(ResolutionState::Known(_)))
     }
 
     pub(crate) fn order_in_paradox(&self, order: &'a MappedMainOrder) -> bool {
         self.paradoxical_orders.contains(order)
     }
 
-    /// Create a clone of the resolver state, add a guess at the success or failure
-    /// of the given order, then adjudicate the order with the amended resolver.
+    fn mark_paradoxical(&mut self, order: &'a MappedMainOrder) {
+        if self.paradoxical_orders.insert(order) {
+            self.record(Undo::Paradox(order));
+        }
+    }
+
+    fn push_dependency(&mut self, order: &'a MappedMainOrder) {
+        self.dependency_chain.push(order);
+        self.record(Undo::Push);
+    }
+
+    fn pop_dependency(&mut self) {
+        if let Some(order) = self.dependency_chain.pop() {
+            self.record(Undo::Pop(order));
+        }
+    }
+
+    /// Add a guess at the success or failure of the given order, then adjudicate the order
+    /// with the guess in place.
     ///
-    /// This returns the entire guesser because in some cases the calling resolver needs
-    /// the dependency chain and the entire state generated during the post-guess adjudication.
-    fn with_guess<'b>(
-        &self,
-        context: &'b Context<'a, impl Adjudicate>,
+    /// The guess and everything learned while adjudicating with it stay in the resolver state,
+    /// so the caller can read the dependency chain that resulted. The caller must then either
+    /// keep the guess with `keep_guess` or take it back with `undo_guess`, passing the returned
+    /// checkpoint.
+    fn with_guess(
+        &mut self,
+        context: &Context<'a, impl Adjudicate>,
         order: &'a MappedMainOrder,
         guess: OrderState,
-    ) -> (ResolverState<'a>, OrderState) {
-        let mut guesser = self.clone();
+    ) -> (usize, OrderState) {
+        let checkpoint = self.undo_log.len();
+        self.open_guesses += 1;
+
+        #[cfg(feature = "dependency-graph")]
+        {
+            self.greedy_chain.push(order);
+        }
+
+        self.set_state(order, ResolutionState::Guessing(guess));
+        let result = context.rules.adjudicate(context, self, order);
 
         #[cfg(feature = "dependency-graph")]
         {
-            guesser.greedy_chain.push(order);
+            self.greedy_chain.pop();
         }
 
-        guesser.set_state(order, ResolutionState::Guessing(guess));
-        let result = context.rules.adjudicate(context, &mut guesser, order);
-        (guesser, result)
+        (checkpoint, result)
     }
 
-    /// Take durable state from another `ResolverState`, leaving behind intermediate calculations.
-    ///
-    /// In the original C implementation, this wasn't necessary because hypotheticals directly modified
-    /// the speculating resolver state. That requires the C implementation to unwind guesses that don't
-    /// work out, but allows it to do nothing to leverage successful resolutions.
+    /// Keep the state from the innermost open guess, leaving its changes to be undone along
+    /// with any enclosing guess.
+    fn keep_guess(&mut self) {
+        self.open_guesses -= 1;
+        if self.open_guesses == 0 {
+            self.undo_log.clear();
+        }
+    }
+
+    /// Undo every change made since `checkpoint`, closing the innermost open guess.
     ///
-    /// The Rust implementation does the opposite, so it needs to "snap to" a resolution state that it
-    /// wants to keep.
-    fn snap_to(&mut self, other: Self) {
-        self.state = other.state;
-        self.paradoxical_orders = other.paradoxical_orders;
-        self.dependency_chain = other.dependency_chain;
+    /// This follows the original C implementation, where hypotheticals directly modify the
+    /// speculating resolver state and unwind guesses that don't work out. Guesses that do work
+    /// out need no further work.
+    fn undo_guess(&mut self, checkpoint: usize) {
+        while self.undo_log.len() > checkpoint {
+            match self.undo_log.pop() {
+                Some(Undo::State(order, Some(previous))) => {
+                    self.state.insert(order, previous);
+                }
+                Some(Undo::State(order, None)) => {
+                    self.state.remove(order);
+                }
+                Some(Undo::Paradox(order)) => {
+                    self.paradoxical_orders.remove(order);
+                }
+                Some(Undo::Push) => {
+                    self.dependency_chain.pop();
+                }
+                Some(Undo::Pop(order)) => self.dependency_chain.push(order),
+                None => unreachable!("undo log is longer than the checkpoint"),
+            }
+        }
+
+        self.open_guesses -= 1;
     }
 
     /// When a dependency cycle is detected, attempt to resolve all orders in the cycle.
-    fn resolve_dependency_cycle(&mut self, cycle: &[&'a MappedMainOrder]) {
+    fn resolve_dependency_cycle(&mut self, rule: ParadoxRule, cycle: &[&'a MappedMainOrder]) {
         use self::ResolutionState::*;
         use super::OrderState::*;
 
+        #[cfg(feature = "dependency-graph")]
+        {
+            let mut orders = cycle.iter().map(|&o| o.clone()).collect::<Vec<_>>();
+            orders.sort();
+            self.cycles.borrow_mut().insert(orders);
+        }
+
         // if every order in the cycle is a move, then this is a circular move
         if cycle.iter().all(|o| o.is_move()) {
             for o in cycle {
                 self.set_state(o, Known(Succeeds));
             }
-        } else {
-            for o in cycle {
-                self.dependency_chain.pop();
-                if self.knows_outcome_of(o) {
-                    continue;
-                }
+            return;
+        }
 
-                if let MainCommand::Convoy(_) = o.command {
-                    self.paradoxical_orders.insert(o);
-                    self.set_state(o, ResolutionState::Known(OrderState::Fails));
-                } else {
+        // The DPTG rule shields supports in the paradox from being cut by convoyed armies,
+        // then resolves the cycle again. If that still leaves a paradox, fall back to Szykman.
+        let shields_supports = rule == ParadoxRule::Dptg
+            && cycle
+                .iter()
+                .any(|o| matches!(o.command, MainCommand::Convoy(_)))
+            && cycle.iter().any(|o| {
+                matches!(o.command, MainCommand::Support(_)) && !self.paradoxical_orders.contains(o)
+            });
+
+        for o in cycle {
+            self.pop_dependency();
+            if self.knows_outcome_of(o) {
+                continue;
+            }
+
+            match o.command {
+                MainCommand::Convoy(_) if !shields_supports => {
+                    self.mark_paradoxical(o);
+                    self.set_state(o, Known(Fails));
+                }
+                MainCommand::Support(_) if shields_supports => {
+                    self.mark_paradoxical(o);
                     self.clear_state(o);
                 }
+                MainCommand::Move(_) | MainCommand::Support(_) if rule == ParadoxRule::AllHold => {
+                    self.mark_paradoxical(o);
+                    self.set_state(o, Known(Fails));
+                }
+                _ => self.clear_state(o),
             }
         }
     }
@@ -412,10 +852,6 @@ impl<'a> ResolverState<'a> {
         use self::ResolutionState::*;
         use super::OrderState::*;
 
-        // The Rust debugger doesn't use fmt::Debug when showing values, so we create
-        // this string to give us a better way to see which order we're resolving.
-        let _order = format!("{}", order);
-
         #[cfg(feature = "dependency-graph")]
         {
             if !self.greedy_chain.is_empty() {
@@ -436,7 +872,7 @@ impl<'a> ResolverState<'a> {
             Some(&Guessing(order_state)) => {
                 // In recursive cases, we accumulate dependencies
                 if !self.dependency_chain.contains(&order) {
-                    self.dependency_chain.push(order)
+                    self.push_dependency(order);
                 }
 
                 order_state
@@ -444,31 +880,36 @@ impl<'a> ResolverState<'a> {
             None => {
                 // checkpoint the resolver and tell it to assume the order fails.
                 // get the order state based on that assumption.
-                let (first_resolver, first_result) = self.with_guess(context, order, Fails);
+                let known_dependencies = self.dependency_chain.len();
+                let (first_guess, first_result) = self.with_guess(context, order, Fails);
 
                 // If we found no new dependencies then this is a valid resolution!
-                // We now snap to the resolver state from the assumption so that we can
+                // We keep the resolver state from the assumption so that we can
                 // reuse it in future calculations.
-                if first_resolver.dependency_chain.len() == self.dependency_chain.len() {
-                    self.snap_to(first_resolver);
+                if self.dependency_chain.len() == known_dependencies {
+                    self.keep_guess();
                     self.set_state(order, Known(first_result));
                     first_result
                 } else {
-                    let next_dep = first_resolver.dependency_chain[self.dependency_chain.len()];
+                    let next_dep = self.dependency_chain[known_dependencies];
 
                     // if we depend on some new guess but we haven't hit a cycle,
-                    // then we cautiously proceed. We update state to match what we've learned
+                    // then we cautiously proceed. We keep what we've learned
                     // from the hypothetical and proceed with our guesses.
                     if next_dep != order {
-                        self.snap_to(first_resolver);
+                        self.keep_guess();
                         self.set_state(order, Guessing(first_result));
-                        self.dependency_chain.push(order);
+                        self.push_dependency(order);
                         first_result
                     }
                     // if the next dependency is the one we're already depending on, we're stuck.
                     else {
-                        let (_second_resolver, second_result) =
+                        let cycle = self.dependency_chain[known_dependencies..].to_vec();
+                        self.undo_guess(first_guess);
+
+                        let (second_guess, second_result) =
                             self.with_guess(context, order, Succeeds);
+                        self.undo_guess(second_guess);
 
                         // If there's a paradox but the outcome doesn't depend on this order,
                         // then all we've learned is the state of this one order.
@@ -476,10 +917,7 @@ impl<'a> ResolverState<'a> {
                             self.set_state(order, Known(first_result));
                             first_result
                         } else {
-                            let tail_start = self.dependency_chain.len();
-                            let tail = &first_resolver.dependency_chain[tail_start..];
-
-                            self.resolve_dependency_cycle(tail);
+                            self.resolve_dependency_cycle(context.rules.paradox_rule(), &cycle);
                             self.resolve(context, order)
                         }
                     }
@@ -493,6 +931,19 @@ impl<'a> ResolverState<'a> {
     pub(crate) fn dependencies(&self) -> BTreeSet<(MappedMainOrder, MappedMainOrder)> {
         self.deps.borrow().clone()
     }
+
+    /// Get the dependency cycles the resolver encountered, including those found while
+    /// evaluating guesses that were later discarded.
+    #[cfg(feature = "dependency-graph")]
+    pub(crate) fn cycles(&self) -> BTreeSet<Vec<MappedMainOrder>> {
+        self.cycles.borrow().clone()
+    }
+
+    /// Get the orders which were treated as part of a paradox.
+    #[cfg(feature = "dependency-graph")]
+    pub(crate) fn paradoxical_orders(&self) -> impl Iterator<Item = &'a MappedMainOrder> + '_ {
+        self.paradoxical_orders.iter().copied()
+    }
 }
 
 impl Default for ResolverState<'_> {
@@ -517,3 +968,103 @@ impl<'a> From<ResolverState<'a>> for HashMap<MappedMainOrder, OrderState> {
         out_map
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Context, ResolverState, Submission};
+    use crate::geo::{standard_map, Map};
+    use crate::judge::{MappedMainOrder, OrderState, Rulebook};
+
+    #[test]
+    fn indexed_lookups() {
+        let orders = vec![
+            "ENG: A nwy -> swe",                // 0
+            "ENG: A den Supports A nwy -> swe", // 1
+            "ENG: F bal Supports A nwy -> swe", // 2
+            "ENG: F nth -> nwy",                // 3
+            "RUS: A swe -> nwy via Convoy",     // 4
+            "RUS: F ska convoys swe -> nwy",    // 5
+            "RUS: F nwg Supports A swe -> nwy", // 6
+            "RUS: F bot Supports A swe",        // 7
+        ]
+        .into_iter()
+        .map(|ord| ord.parse::<MappedMainOrder>().unwrap())
+        .collect::<Vec<_>>();
+
+        let context = Context::new(crate::geo::standard_map(), Rulebook::default(), &orders);
+
+        assert_eq!(
+            Some(&orders[4]),
+            context.find_order_to_province(&"swe".into())
+        );
+        assert_eq!(None, context.find_order_to_province(&"lon".into()));
+        assert_eq!(
+            vec![&orders[3], &orders[4]],
+            context.moves_to_province(&"nwy".into()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![&orders[1], &orders[2]],
+            context.supports_for(&orders[0]).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![&orders[6]],
+            context.supports_for(&orders[4]).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![&orders[5]],
+            context.convoys_for(&orders[4]).collect::<Vec<_>>()
+        );
+        assert_eq!(0, context.convoys_for(&orders[0]).count());
+    }
+
+    /// DATC 6.F.14: the resolver has to guess both ways to find the paradox, and must close
+    /// every guess before it finishes.
+    #[test]
+    fn paradox_leaves_no_open_guesses() {
+        let orders = vec![
+            "ENG: F lon Supports F wal -> eng",
+            "ENG: F wal -> eng",
+            "FRA: A bre -> lon",
+            "FRA: F eng convoys bre -> lon",
+        ]
+        .into_iter()
+        .map(|ord| ord.parse::<MappedMainOrder>().unwrap())
+        .collect::<Vec<_>>();
+
+        let context = Context::new(crate::geo::standard_map(), Rulebook::default(), &orders);
+        let mut state = ResolverState::new();
+        for order in &orders {
+            state.resolve(&context, order);
+        }
+
+        assert_eq!(0, state.open_guesses);
+        assert!(state.undo_log.is_empty());
+        assert!(state.order_in_paradox(&orders[3]));
+        assert_eq!(OrderState::Fails, state.resolve(&context, &orders[3]));
+        assert_eq!(OrderState::Succeeds, state.resolve(&context, &orders[1]));
+    }
+
+    fn coastless_submission() -> Submission {
+        Submission::with_inferred_state(vec!["FRA: F mao -> spa".parse().unwrap()])
+    }
+
+    #[test]
+    fn readjudicate_against_equal_map() {
+        let submission = coastless_submission();
+        let first = submission
+            .adjudicate(standard_map(), Rulebook::default())
+            .to_report();
+        let second = submission
+            .adjudicate(&standard_map().clone(), Rulebook::default())
+            .to_report();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    #[should_panic(expected = "same map")]
+    fn readjudicate_against_different_map() {
+        let submission = coastless_submission();
+        submission.adjudicate(standard_map(), Rulebook::default());
+        submission.adjudicate(&Map::default(), Rulebook::default());
+    }
+}
@@ -8,4 +8,4 @@ mod resolver;
 mod start;
 
 pub use self::resolver::{Context, OrderOutcome, Outcome};
-pub use self::start::{DestStatus, Destinations, Start};
+pub use self::start::{DestStatus, Destinations, OwnedStart, Start};
@@ -1,5 +1,5 @@
 use super::{DestStatus, Start};
-use crate::judge::MappedRetreatOrder;
+use crate::judge::{report, MappedMainOrder, MappedRetreatOrder, Submission};
 use crate::order::{Command, RetreatCommand};
 use crate::{geo::ProvinceKey, geo::RegionKey, Unit, UnitPosition, UnitPositions};
 use std::collections::HashMap;
@@ -19,7 +19,7 @@ impl<'a> Context<'a> {
     }
 
     /// Adjudicate a retreat phase and determine which units move or are disbanded.
-    pub fn resolve(&self) -> Outcome {
+    pub fn resolve(&self) -> Outcome<'_> {
         let mut outcomes = HashMap::new();
         let mut destinations = HashMap::new();
 
@@ -91,22 +91,45 @@ impl<'a> Outcome<'a> {
         self.by_order.get(order)
     }
 
+    /// Create an owned report of the outcome of every order and the resulting unit positions.
+    pub fn to_report(&self) -> report::RetreatPhaseReport {
+        let mut orders = self
+            .by_order
+            .iter()
+            .map(|(&order, outcome)| report::OrderReport {
+                order: order.clone(),
+                outcome: outcome.into(),
+            })
+            .collect::<Vec<_>>();
+        report::sort_reports(&mut orders);
+
+        report::RetreatPhaseReport {
+            orders,
+            units: report::owned_positions(self.unit_positions()),
+        }
+    }
+
+    /// Start the next main phase from the unit positions after this retreat phase.
+    pub fn to_submission(&self, orders: Vec<MappedMainOrder>) -> Submission {
+        Submission::new(self, orders)
+    }
+
     /// Iterate over the outcomes for each retreat order.
-    pub fn order_outcomes(&self) -> impl Iterator<Item = (&MappedRetreatOrder, &OrderOutcome)> {
+    pub fn order_outcomes(&self) -> impl Iterator<Item = (&MappedRetreatOrder, &OrderOutcome<'_>)> {
         self.by_order.iter().map(|(k, v)| (*k, v))
     }
 }
 
 impl UnitPositions<RegionKey> for Outcome<'_> {
-    fn unit_positions(&self) -> Vec<UnitPosition> {
+    fn unit_positions(&self) -> Vec<UnitPosition<'_>> {
         self.unit_positions.unit_positions()
     }
 
-    fn find_province_occupier(&self, province: &ProvinceKey) -> Option<UnitPosition> {
+    fn find_province_occupier(&self, province: &ProvinceKey) -> Option<UnitPosition<'_>> {
         self.unit_positions.find_province_occupier(province)
     }
 
-    fn find_region_occupier(&self, region: &RegionKey) -> Option<Unit> {
+    fn find_region_occupier(&self, region: &RegionKey) -> Option<Unit<'_>> {
         self.unit_positions.find_region_occupier(region)
     }
 }
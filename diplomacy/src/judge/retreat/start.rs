@@ -1,9 +1,11 @@
 use crate::geo::{Border, ProvinceKey, RegionKey};
 use crate::judge::{
-    calc::dislodger_of, calc::prevent_results, convoy, retreat, Adjudicate, Context,
-    MappedMainOrder, OrderState, Outcome, Prevent, ResolverState,
+    calc::{self, prevent_results},
+    convoy, report, retreat, Adjudicate, Context, InterimPositions, MappedMainOrder, Outcome,
+    Prevent, ResolverState,
 };
-use crate::{order::Command, Unit, UnitPosition, UnitPositions};
+use crate::{Unit, UnitPosition, UnitPositions};
+use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::iter;
 
@@ -22,18 +24,8 @@ impl<'a> Start<'a> {
     /// able to go based on the outcome of a main phase adjudication.
     pub fn new(outcome: &'a Outcome<'a, impl Adjudicate>) -> Self {
         let mut state = outcome.resolver.clone();
-        let dislodged = {
-            let mut dislodged = HashMap::new();
-            for order in outcome.context.orders() {
-                if let Some(dl_ord) = dislodger_of(&outcome.context, &mut state, order) {
-                    dislodged.insert(order, dl_ord);
-                }
-            }
-
-            dislodged
-        };
-
-        let interim_positions = non_dislodged_positions(outcome, &dislodged);
+        let dislodged = outcome.dislodged();
+        let interim_positions = InterimPositions::new(outcome, &dislodged).into_occupiers();
         let retreat_destinations = dislodged
             .iter()
             .map(|(dislodged_order, dislodger)| {
@@ -43,7 +35,6 @@ impl<'a> Start<'a> {
                         .context
                         .world_map
                         .borders_containing(&dislodged_order.region)
-                        .into_iter()
                         .filter_map(|border| {
                             Some((
                                 border.dest_from(&dislodged_order.region)?,
@@ -81,11 +72,121 @@ impl<'a> Start<'a> {
         &self.retreat_destinations
     }
 
+    /// Create a copy of the retreat phase starting data which doesn't borrow from the main-phase
+    /// outcome.
+    pub fn into_owned(self) -> OwnedStart {
+        OwnedStart {
+            dislodged: self
+                .dislodged
+                .into_iter()
+                .map(|(order, dislodger)| (order.clone(), dislodger.clone()))
+                .collect(),
+            retreat_destinations: self
+                .retreat_destinations
+                .into_iter()
+                .map(|(unit, dests)| {
+                    (
+                        unit.into_owned(),
+                        dests
+                            .regions
+                            .into_iter()
+                            .map(|(region, status)| (region.clone(), status))
+                            .collect(),
+                    )
+                })
+                .collect(),
+            unit_positions: self
+                .unit_positions
+                .into_values()
+                .map(UnitPosition::into_owned)
+                .collect(),
+        }
+    }
+
+    /// Create an owned report of the dislodged units and their retreat destinations.
+    pub fn to_report(&self) -> report::RetreatStartReport {
+        let mut dislodged = self
+            .dislodged
+            .iter()
+            .map(|(&order, &dislodger)| report::Dislodgement {
+                order: order.clone(),
+                dislodger: dislodger.clone(),
+                destinations: self
+                    .retreat_destinations
+                    .get(&order.unit_position())
+                    .map(|dests| {
+                        dests
+                            .regions
+                            .iter()
+                            .map(|(&region, &status)| (region.clone(), status))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+        dislodged.sort_by(|a, b| a.order.cmp(&b.order));
+
+        report::RetreatStartReport { dislodged }
+    }
+
     /// Checks if there are any dislodged units and if any of those units have valid retreat destinations.
     pub fn needs_player_input(&self) -> bool {
         self.retreat_destinations()
             .values()
-            .any(|dests| !dests.is_any_available())
+            .any(|dests| dests.is_any_available())
+    }
+}
+
+/// Retreat phase starting data which owns its orders and unit positions, so that it can be
+/// stored or sent between threads. Use `OwnedStart::to_start` to adjudicate the retreat phase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedStart {
+    dislodged: HashMap<MappedMainOrder, MappedMainOrder>,
+    retreat_destinations:
+        HashMap<UnitPosition<'static, RegionKey>, BTreeMap<RegionKey, DestStatus>>,
+    unit_positions: Vec<UnitPosition<'static, RegionKey>>,
+}
+
+impl OwnedStart {
+    /// Map of dislodged units to the units that dislodged them
+    pub fn dislodged(&self) -> &HashMap<MappedMainOrder, MappedMainOrder> {
+        &self.dislodged
+    }
+
+    /// Create retreat phase starting data that borrows from this instance.
+    pub fn to_start(&self) -> Start<'_> {
+        Start {
+            dislodged: self.dislodged.iter().collect(),
+            retreat_destinations: self
+                .retreat_destinations
+                .iter()
+                .map(|(unit, dests)| {
+                    (
+                        UnitPosition::new(
+                            Unit::new(Cow::Borrowed(unit.nation()), unit.unit.unit_type()),
+                            &unit.region,
+                        ),
+                        dests
+                            .iter()
+                            .map(|(region, &status)| (region, status))
+                            .collect(),
+                    )
+                })
+                .collect(),
+            unit_positions: self
+                .unit_positions
+                .iter()
+                .map(|unit| {
+                    (
+                        unit.region.province(),
+                        UnitPosition::new(
+                            Unit::new(Cow::Borrowed(unit.nation()), unit.unit.unit_type()),
+                            &unit.region,
+                        ),
+                    )
+                })
+                .collect(),
+        }
     }
 }
 
@@ -109,11 +210,19 @@ fn is_valid_retreat_route<'a>(
     };
 
     // A unit cannot retreat to its dislodger's point of origin unless the dislodger was
-    // convoyed to the destination.
-    if dest.province() == dislodger.region.province()
-        && !convoy::route_exists(main_phase, state, dislodger)
-    {
-        return DestStatus::BlockedByDislodger;
+    // convoyed to the destination. Depending on the rules, an army convoyed from an adjacent
+    // province may still block the retreat.
+    if dest.province() == dislodger.region.province() {
+        let was_convoyed = calc::uses_convoy(main_phase, dislodger)
+            && convoy::route_exists(main_phase, state, dislodger);
+        let from_adjacent = main_phase
+            .world_map
+            .find_borders_between(&dislodger.region, retreater.region.province())
+            .any(|b| b.is_passable_by(dislodger.unit_type));
+
+        if !was_convoyed || (from_adjacent && !main_phase.rules.retreat_to_convoy_origin()) {
+            return DestStatus::BlockedByDislodger;
+        }
     }
 
     // A unit cannot retreat to a position that is occupied at the end of the main phase
@@ -188,6 +297,7 @@ impl<'a> iter::FromIterator<(&'a RegionKey, DestStatus)> for Destinations<'a> {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DestStatus {
     /// The region is a viable retreat destination.
     Available,
@@ -215,38 +325,37 @@ impl PartialEq<retreat::OrderOutcome<'_>> for DestStatus {
     }
 }
 
-/// The state of the world between the main phase and retreat phases of a season, ignoring
-/// dislodged units.
-///
-/// To calculate valid retreat destinations, it's necessary to know which provinces are
-/// occupied when the retreats take place. It's therefore useful to compute positions
-/// based on order outcomes, so that moved units have vacated their old provinces and
-/// fill their new ones.
-///
-/// This approach creates a problem, however: Where are dislodged units? They haven't retreated
-/// yet, so logically it seems that they're in their old positions. However, reporting them
-/// there would mean multiple units are concurrently in the same province, which might
-/// create some unforeseen weirdness. To mitigate this, we ignore those units.
-fn non_dislodged_positions<'a, A>(
-    outcome: &Outcome<'a, A>,
-    dislodged: &HashMap<&MappedMainOrder, &MappedMainOrder>,
-) -> HashMap<&'a ProvinceKey, UnitPosition<'a>> {
-    let mut positions = HashMap::new();
-    for (order, result) in &outcome.orders {
-        if dislodged.contains_key(order) {
-            continue;
-        }
+#[cfg(test)]
+mod tests {
+    use crate::geo::standard_map;
+    use crate::judge::{MappedMainOrder, Rulebook, Submission};
 
-        if order.is_move() && *result == OrderState::Succeeds {
-            let new_position = order.move_dest().unwrap();
-            positions.insert(
-                new_position.province(),
-                UnitPosition::new(Unit::from(*order), new_position),
-            );
-        } else {
-            positions.insert(order.region.province(), UnitPosition::from(*order));
-        }
+    /// Serbia dislodges the Turkish army in Bulgaria with support from Rumania, and the army in
+    /// Bulgaria holds. Its other land neighbors are occupied by the units in `occupied`.
+    fn attack_on_bulgaria(occupied: &[&str]) -> Submission {
+        let orders = ["AUS: A ser -> bul", "AUS: A rum supports A ser -> bul"]
+            .iter()
+            .chain(&["TUR: A bul Hold"])
+            .chain(occupied)
+            .map(|ord| ord.parse::<MappedMainOrder>().unwrap())
+            .collect();
+        Submission::with_inferred_state(orders)
     }
 
-    positions
+    #[test]
+    fn needs_player_input_with_a_retreat() {
+        let submission = attack_on_bulgaria(&["AUS: A gre Hold"]);
+        let outcome = submission.adjudicate(standard_map(), Rulebook::default());
+        assert!(outcome.to_retreat_start().needs_player_input());
+    }
+
+    #[test]
+    fn no_player_input_without_a_retreat() {
+        let submission = attack_on_bulgaria(&["AUS: A gre Hold", "AUS: A con Hold"]);
+        let outcome = submission.adjudicate(standard_map(), Rulebook::default());
+        let start = outcome.to_retreat_start();
+
+        assert_eq!(1, start.retreat_destinations().len());
+        assert!(!start.needs_player_input());
+    }
 }
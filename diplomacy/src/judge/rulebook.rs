@@ -1,4 +1,7 @@
-use super::calc::{dislodger_of, is_head_to_head, max_prevent_result, path_exists};
+use super::calc::{
+    attack_strength, defend_strength, dislodger_of, hold_strength, is_head_to_head,
+    max_prevent_result, path_exists,
+};
 use super::convoy::ConvoyOutcome;
 use super::resolver::{Context, ResolverState};
 use super::support::{self, SupportOutcome};
@@ -8,11 +11,139 @@ use crate::judge::strength::{Prevent, Strength};
 use crate::order::Command;
 use crate::ShortName;
 
-/// The standard Diplomacy rules.
-#[derive(Debug, Clone, Default)]
-pub struct Rulebook;
+/// The rule used to break a convoy paradox: a cycle where a convoyed army's attack decides
+/// whether one of the fleets convoying it is dislodged.
+///
+/// See DATC section 4.A.2 for a discussion of the options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParadoxRule {
+    /// Convoys in the paradox are disrupted; the convoyed armies stay in place and have no effect
+    /// on the province they attacked. This is the DATC preference.
+    #[default]
+    Szykman,
+    /// Every unit whose order is part of the paradox holds. Moves fail, supports and convoys
+    /// have no effect.
+    AllHold,
+    /// A convoyed army does not cut a support given to the province of a fleet that is convoying
+    /// it, whether that is a support to hold or a support to move.
+    Rulebook1971,
+    /// A convoyed army does not cut the support of a unit supporting an attack against one of
+    /// the fleets convoying it. This is also the rule in the 2000 rulebook.
+    Rulebook1982,
+    /// When a paradox occurs, convoyed armies in the paradox do not cut supports that are part of
+    /// the same paradox. Outside of paradoxes, convoyed armies cut support as normal.
+    Dptg,
+}
+
+/// When an army moving to an adjacent province may be convoyed there instead of moving over land.
+///
+/// See DATC section 4.A.3 for a discussion of the options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConvoyToAdjacent {
+    /// The army moves over land when it can, but any convoy route for its move, even one made
+    /// of foreign fleets, lets it avoid a head-to-head battle. Armies can be kidnapped.
+    #[default]
+    Kidnapping,
+    /// The army is convoyed when the order says "via convoy" or a fleet of the same nation is
+    /// ordered to convoy it. This is the rule in the 2000 rulebook.
+    Intent,
+    /// The army is only convoyed when the order says "via convoy".
+    Explicit,
+}
+
+/// Which order is followed when a nation issues more than one order to the same unit.
+///
+/// See DATC section 4.D for a discussion of the options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MultipleOrders {
+    /// The first order submitted for the unit is followed.
+    #[default]
+    First,
+    /// The last order submitted for the unit is followed.
+    Last,
+    /// None of the orders are followed, and the unit holds.
+    Hold,
+}
+
+/// The standard Diplomacy rules, with settings for the questions the DATC leaves open.
+///
+/// The default settings follow this crate's own interpretation of the rules. Presets are
+/// available for the 2000 rulebook, the DPTG and webDiplomacy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rulebook {
+    /// How convoy paradoxes are resolved (DATC 4.A.2).
+    pub paradox_rule: ParadoxRule,
+    /// When an army may be convoyed to an adjacent province (DATC 4.A.3).
+    pub convoy_to_adjacent: ConvoyToAdjacent,
+    /// Whether a unit dislodged by an army convoyed from an adjacent province may retreat to
+    /// the province the army came from (DATC 4.A.5).
+    pub retreat_to_convoy_origin: bool,
+    /// Which order is followed when a unit receives more than one (DATC 4.D).
+    pub multiple_orders: MultipleOrders,
+    /// Whether orders that could never succeed are treated as illegal, so that the ordered unit
+    /// holds instead (DATC 4.E).
+    pub illegal_orders_hold: bool,
+}
+
+impl Default for Rulebook {
+    fn default() -> Self {
+        Rulebook {
+            paradox_rule: ParadoxRule::default(),
+            convoy_to_adjacent: ConvoyToAdjacent::default(),
+            retreat_to_convoy_origin: true,
+            multiple_orders: MultipleOrders::default(),
+            illegal_orders_hold: false,
+        }
+    }
+}
 
 impl Rulebook {
+    /// Create the standard rules, resolving convoy paradoxes with `paradox_rule`.
+    pub fn with_paradox_rule(paradox_rule: ParadoxRule) -> Self {
+        Rulebook {
+            paradox_rule,
+            ..Rulebook::default()
+        }
+    }
+
+    /// The rules as written in the 2000 (4th edition) rulebook.
+    pub fn edition_2000() -> Self {
+        Rulebook {
+            paradox_rule: ParadoxRule::Rulebook1982,
+            convoy_to_adjacent: ConvoyToAdjacent::Intent,
+            retreat_to_convoy_origin: true,
+            multiple_orders: MultipleOrders::First,
+            illegal_orders_hold: true,
+        }
+    }
+
+    /// The rules preferred by the Diplomacy Programming Project Treaty Group.
+    pub fn dptg() -> Self {
+        Rulebook {
+            paradox_rule: ParadoxRule::Dptg,
+            convoy_to_adjacent: ConvoyToAdjacent::Intent,
+            retreat_to_convoy_origin: true,
+            multiple_orders: MultipleOrders::Last,
+            illegal_orders_hold: true,
+        }
+    }
+
+    /// The rules used by webDiplomacy, where each unit has one order that can be changed until
+    /// the deadline and convoys to adjacent provinces must be explicit.
+    pub fn web_diplomacy() -> Self {
+        Rulebook {
+            paradox_rule: ParadoxRule::Szykman,
+            convoy_to_adjacent: ConvoyToAdjacent::Explicit,
+            retreat_to_convoy_origin: true,
+            multiple_orders: MultipleOrders::Last,
+            illegal_orders_hold: true,
+        }
+    }
+
     /// Apply rules to determine hold outcome.
     fn adjudicate_hold<'a>(
         ctx: &Context<'a, Self>,
@@ -32,11 +163,12 @@ impl Rulebook {
     ) -> AttackOutcome<'a> {
         if ord.command.move_dest() == Some(&ord.region) {
             AttackOutcome::MoveToSelf
+        } else if ctx.rules.paradox_rule == ParadoxRule::AllHold && rslv.order_in_paradox(ord) {
+            AttackOutcome::Paradox
         } else if !path_exists(ctx, rslv, ord) {
             AttackOutcome::NoPath
         } else if ord.command.is_move() {
-            let mut atk_supports = support::find_for(ctx, rslv, ord);
-            let mut atk_strength = 1 + atk_supports.len();
+            let atk_strength = 1 + support::find_for(ctx, rslv, ord).len();
             let prevent = max_prevent_result(ctx, rslv, ord);
 
             // if the attack cannot overcome the prevent even in the best case,
@@ -53,37 +185,35 @@ impl Rulebook {
                     // no convoy is available to help one of the units move around the other.
                     let is_head_to_head = is_head_to_head(ctx, rslv, ord, occupier);
 
-                    let resistance = if !occupier.command.is_move() || is_head_to_head {
-                        // DEFEND and HOLD strengths include supports that may seek to thwart
-                        // other orders from the same nation.
-
-                        // Example:
-                        // France:
-                        // A Belgium Supports A Burgundy - Ruhr
-                        // A Holland Supports A Burgundy - Ruhr
-                        // A Burgundy - Ruhr
-                        // A Munich Supports A Ruhr - Burgundy
-                        // A Marseilles - Burgundy
-
-                        // Germany:
-                        // A Ruhr - Burgundy
-                        // In this example the French army in Munich supports the move of the German army
-                        // in Ruhr instead of the French army in Burgundy. This makes that the ATTACK STRENGTH,
-                        // the PREVENT STRENGTH and the DEFEND STRENGTH of the German army in Ruhr are all different.
-                        // The ATTACK STRENGTH is one, because the French support should not be counted for the attack.
-                        // The PREVENT STRENGTH is zero, because it is dislodged by the French army in Burgundy
-                        // and therefore it can not prevent the army in Marseilles to go to Burgundy. However, the
-                        // DEFEND STRENGTH contains all supports and is therefore two. Still this DEFEND STRENGTH
-                        // is insufficient in the head to head battle, since the French army in Burgundy has an
-                        // ATTACK STRENGTH of three.
-                        1 + support::find_for(ctx, rslv, occupier).len()
-                    }
-                    // failed exits resist with strength 1 (the unit trapped in the province)
-                    else if rslv.resolve(ctx, occupier) == OrderState::Fails {
-                        1
-                    // successful exits mount no resistance
+                    // DEFEND and HOLD strengths include supports that may seek to thwart
+                    // other orders from the same nation.
+
+                    // Example:
+                    // France:
+                    // A Belgium Supports A Burgundy - Ruhr
+                    // A Holland Supports A Burgundy - Ruhr
+                    // A Burgundy - Ruhr
+                    // A Munich Supports A Ruhr - Burgundy
+                    // A Marseilles - Burgundy
+
+                    // Germany:
+                    // A Ruhr - Burgundy
+                    // In this example the French army in Munich supports the move of the German army
+                    // in Ruhr instead of the French army in Burgundy. This makes that the ATTACK STRENGTH,
+                    // the PREVENT STRENGTH and the DEFEND STRENGTH of the German army in Ruhr are all different.
+                    // The ATTACK STRENGTH is one, because the French support should not be counted for the attack.
+                    // The PREVENT STRENGTH is zero, because it is dislodged by the French army in Burgundy
+                    // and therefore it can not prevent the army in Marseilles to go to Burgundy. However, the
+                    // DEFEND STRENGTH contains all supports and is therefore two. Still this DEFEND STRENGTH
+                    // is insufficient in the head to head battle, since the French army in Burgundy has an
+                    // ATTACK STRENGTH of three.
+                    //
+                    // Failed exits resist with strength 1 (the unit trapped in the province), and
+                    // successful exits mount no resistance.
+                    let (resistance, _) = if is_head_to_head {
+                        defend_strength(ctx, rslv, occupier)
                     } else {
-                        0
+                        hold_strength(ctx, rslv, occupier)
                     };
 
                     // A unit can not dislodge a unit of the same player.
@@ -91,14 +221,10 @@ impl Rulebook {
                     if resistance > 0 && ord.nation == occupier.nation {
                         return AttackOutcome::FriendlyFire;
                     } else if resistance > 0 {
-                        // Supports to a foreign unit can not be used to dislodge an own unit.
-                        // Therefore, we remove any move supports from the nation whose unit
-                        // is resisting the move.
-                        atk_supports = atk_supports
-                            .into_iter()
-                            .filter(|sup| sup.nation != occupier.nation)
-                            .collect();
-                        atk_strength = 1 + atk_supports.len();
+                        // Supports to a foreign unit can not be used to dislodge an own unit,
+                        // so the attack strength leaves out supports from the nation whose
+                        // unit is resisting the move.
+                        let (atk_strength, _) = attack_strength(ctx, rslv, ord);
 
                         if atk_strength <= resistance {
                             if is_head_to_head {
@@ -126,6 +252,8 @@ impl Rulebook {
             SupportOutcome::SupportingSelf
         } else if !support::can_reach(ctx.world_map, ord) {
             SupportOutcome::CantReach
+        } else if ctx.rules.paradox_rule == ParadoxRule::AllHold && rslv.order_in_paradox(ord) {
+            SupportOutcome::Paradox
         } else {
             match support::find_cutting_order(ctx, rslv, ord) {
                 Some(cutter) => SupportOutcome::CutBy(cutter),
@@ -168,6 +296,26 @@ impl Rulebook {
 }
 
 impl Adjudicate for Rulebook {
+    fn paradox_rule(&self) -> ParadoxRule {
+        self.paradox_rule
+    }
+
+    fn convoy_to_adjacent(&self) -> ConvoyToAdjacent {
+        self.convoy_to_adjacent
+    }
+
+    fn retreat_to_convoy_origin(&self) -> bool {
+        self.retreat_to_convoy_origin
+    }
+
+    fn multiple_orders(&self) -> MultipleOrders {
+        self.multiple_orders
+    }
+
+    fn illegal_orders_hold(&self) -> bool {
+        self.illegal_orders_hold
+    }
+
     fn adjudicate<'a>(
         &self,
         context: &Context<'a, Self>,
@@ -246,6 +394,8 @@ pub enum AttackOutcome<'a> {
     /// The intended victim of the attack fended off the attacker, possibly with support from
     /// other units.
     OccupierDefended,
+    /// The move was part of a convoy paradox, and the unit was held in place to resolve it.
+    Paradox,
     Succeeds,
 }
 
@@ -0,0 +1,227 @@
+//! Sanity checks that find orders which could never succeed, regardless of the orders given to
+//! other units.
+//!
+//! DATC section 4.E discusses whether such orders should be adjudicated as written, or treated
+//! as illegal so that the unit holds instead. The checks here only consider the map and the
+//! positions of units, never the outcome of other orders.
+
+use super::{support, MappedMainOrder};
+use crate::geo::{Map, ProvinceKey, RegionKey, Terrain};
+use crate::order::{ConvoyedMove, MainCommand, SupportedOrder};
+use crate::{ShortName, UnitType};
+use std::collections::{HashSet, VecDeque};
+
+/// Returns true if `order` could never succeed given the map and the positions of the units
+/// ordered in `orders`.
+///
+/// Impossible moves include moves to the unit's own province, moves across no passable border,
+/// and army moves that no arrangement of fleets on the board could convoy or that forbid a
+/// convoy. Supports are
+/// impossible when the supporter can't reach the province it's supporting into, or when the
+/// supported move would itself be impossible without the supporting unit convoying it. Convoys
+/// are impossible when the fleet isn't at sea or couldn't be part of a chain of fleets between
+/// the ends of the convoyed move.
+pub(in crate::judge) fn is_impossible(
+    world: &Map,
+    orders: &[&MappedMainOrder],
+    order: &MappedMainOrder,
+) -> bool {
+    match &order.command {
+        MainCommand::Hold => false,
+        MainCommand::Move(cmd) if cmd.forbids_convoy() => {
+            order.region.province() == cmd.dest().province()
+                || !has_passable_border(world, order.unit_type, &order.region, cmd.dest())
+        }
+        MainCommand::Move(cmd) => !is_move_possible(
+            world,
+            orders,
+            order.unit_type,
+            &order.region,
+            cmd.dest(),
+            cmd.mandates_convoy(),
+            None,
+        ),
+        MainCommand::Support(supported) => {
+            !support::can_reach(world, order)
+                || match supported {
+                    SupportedOrder::Hold(..) => false,
+                    SupportedOrder::Move(unit_type, from, to) => !is_move_possible(
+                        world,
+                        orders,
+                        *unit_type,
+                        from,
+                        to,
+                        false,
+                        Some(&order.region),
+                    ),
+                }
+        }
+        MainCommand::Convoy(mv) => {
+            !is_at_sea(world, &order.region) || !is_on_convoy_chain(world, orders, order, mv)
+        }
+    }
+}
+
+/// Returns true if the fleet ordered by `convoy` could be part of a chain of fleets carrying an
+/// army along `mv`.
+fn is_on_convoy_chain(
+    world: &Map,
+    orders: &[&MappedMainOrder],
+    convoy: &MappedMainOrder,
+    mv: &ConvoyedMove<RegionKey>,
+) -> bool {
+    let fleets = orders
+        .iter()
+        .filter(|o| o.unit_type == UnitType::Fleet && is_at_sea(world, &o.region))
+        .map(|o| &o.region)
+        .collect::<Vec<_>>();
+
+    reachable_fleets(world, &fleets, mv.from().province()).contains(&convoy.region)
+        && reachable_fleets(world, &fleets, mv.to().province()).contains(&convoy.region)
+}
+
+/// Returns true if a unit of type `unit_type` in `from` could reach `to`, either directly or
+/// through a chain of fleets at sea. The fleet in `excluding`, if any, is left out of the chain.
+fn is_move_possible(
+    world: &Map,
+    orders: &[&MappedMainOrder],
+    unit_type: UnitType,
+    from: &RegionKey,
+    to: &RegionKey,
+    via_convoy: bool,
+    excluding: Option<&RegionKey>,
+) -> bool {
+    if from.province() == to.province() {
+        return false;
+    }
+
+    if !via_convoy && has_passable_border(world, unit_type, from, to) {
+        return true;
+    }
+
+    if unit_type != UnitType::Army
+        || world
+            .find_region(&to.short_name())
+            .map(|r| r.terrain() == Terrain::Sea)
+            .unwrap_or(true)
+    {
+        return false;
+    }
+
+    let fleets = orders
+        .iter()
+        .filter(|o| o.unit_type == UnitType::Fleet && is_at_sea(world, &o.region))
+        .map(|o| &o.region)
+        .filter(|region| Some(*region) != excluding)
+        .collect::<Vec<_>>();
+
+    convoy_chain_exists(world, &fleets, from.province(), to.province())
+}
+
+fn has_passable_border(world: &Map, unit_type: UnitType, from: &RegionKey, to: &RegionKey) -> bool {
+    world
+        .find_border_between(from, to)
+        .map(|b| b.is_passable_by(unit_type))
+        .unwrap_or(false)
+}
+
+/// Breadth-first search for a chain of fleets from `origin` to `dest`.
+fn convoy_chain_exists(
+    world: &Map,
+    fleets: &[&RegionKey],
+    origin: &ProvinceKey,
+    dest: &ProvinceKey,
+) -> bool {
+    reachable_fleets(world, fleets, origin)
+        .into_iter()
+        .any(|fleet| world.find_bordering(fleet.province()).any(|r| dest == r))
+}
+
+/// Find the fleets that could form part of a convoy chain starting in `origin`.
+pub(in crate::judge) fn reachable_fleets<'a>(
+    world: &Map,
+    fleets: &[&'a RegionKey],
+    origin: &ProvinceKey,
+) -> HashSet<&'a RegionKey> {
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+
+    let start = world.find_bordering(origin).collect::<Vec<_>>();
+    for fleet in fleets {
+        if start.contains(fleet) && visited.insert(*fleet) {
+            queue.push_back(*fleet);
+        }
+    }
+
+    while let Some(fleet) = queue.pop_front() {
+        let bordering = world.find_bordering(fleet.province()).collect::<Vec<_>>();
+        for next in fleets {
+            if bordering.contains(next) && visited.insert(*next) {
+                queue.push_back(*next);
+            }
+        }
+    }
+
+    visited
+}
+
+pub(in crate::judge) fn is_at_sea(world: &Map, region: &RegionKey) -> bool {
+    region.coast().is_none()
+        && world
+            .find_region(&region.short_name())
+            .map(|r| r.terrain() == Terrain::Sea)
+            .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_impossible;
+    use crate::geo::standard_map;
+    use crate::judge::MappedMainOrder;
+
+    fn check(orders: &[&str]) -> Vec<bool> {
+        let orders = orders
+            .iter()
+            .map(|o| o.parse::<MappedMainOrder>().unwrap())
+            .collect::<Vec<_>>();
+        let refs = orders.iter().collect::<Vec<_>>();
+        orders
+            .iter()
+            .map(|o| is_impossible(standard_map(), &refs, o))
+            .collect()
+    }
+
+    #[test]
+    fn fleet_move_without_border() {
+        assert_eq!(check(&["RUS: F rum -> hol"]), vec![true]);
+    }
+
+    #[test]
+    fn army_move_without_fleets() {
+        assert_eq!(check(&["GER: A yor -> hol"]), vec![true]);
+    }
+
+    #[test]
+    fn army_move_with_fleet_chain() {
+        assert_eq!(
+            check(&["ENG: A lon -> nwy", "ENG: F nth Hold"]),
+            vec![false, false]
+        );
+    }
+
+    #[test]
+    fn support_cannot_rely_on_supporter_to_convoy() {
+        assert_eq!(
+            check(&["AUS: A rum -> arm", "TUR: F bla Supports A rum -> arm"]),
+            vec![false, true]
+        );
+    }
+
+    #[test]
+    fn convoy_off_the_route() {
+        assert_eq!(
+            check(&["RUS: A swe -> nwy", "RUS: F bot convoys swe -> nwy"]),
+            vec![false, true]
+        );
+    }
+}
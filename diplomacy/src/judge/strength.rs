@@ -8,6 +8,9 @@ pub trait Strength {
 /// A collection of orders which support a specific order; used in strength calculations.
 pub type Supporters<'a> = Vec<&'a MappedMainOrder>;
 
+/// A strength and the supports counted toward it.
+pub type Counted<'a> = (usize, Supporters<'a>);
+
 /// The intermediate state for a prevent strength calculation. Prevent strength
 /// determines how much force is applied to stop any other units from entering the
 /// destination province.
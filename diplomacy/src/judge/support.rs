@@ -1,8 +1,8 @@
 //! Contains helper functions for evaluating the success of support commands
 //! during the main phase of a turn.
 
-use super::{calc, Adjudicate, Context, MappedMainOrder, OrderState, ResolverState};
-use crate::geo::Map;
+use super::{calc, Adjudicate, Context, MappedMainOrder, OrderState, ParadoxRule, ResolverState};
+use crate::geo::{Map, RegionKey};
 use crate::order::{Command, MainCommand, SupportedOrder};
 
 fn order_cuts<'a>(
@@ -41,22 +41,58 @@ fn order_cuts<'a>(
             return false;
         }
 
-        // We use the Szykman rule to deal with paradoxes created by convoying support cuts.
-        // Therefore, we don't worry about units being convoyed that cut support on attacks against
-        // their convoys; those situations will be handled by the cycle resolver.
+        // Some paradox rules stop convoyed armies from cutting certain supports outright. Under
+        // the Szykman and All Hold rules, we don't worry about units being convoyed that cut
+        // support on attacks against their convoys; those situations will be handled by the
+        // cycle resolver.
+        if is_shielded_from_convoyed_army(ctx, resolver, support_order, cutting_order) {
+            return false;
+        }
+
         calc::path_exists(ctx, resolver, cutting_order)
     } else {
         false
     }
 }
 
+/// Returns true if the paradox rule prevents `cutting_order`, as a convoyed army, from cutting
+/// `support_order`.
+fn is_shielded_from_convoyed_army<'a>(
+    ctx: &Context<'a, impl Adjudicate>,
+    resolver: &ResolverState<'a>,
+    support_order: &MappedMainOrder,
+    cutting_order: &MappedMainOrder,
+) -> bool {
+    let supported = match &support_order.command {
+        MainCommand::Support(supported) => supported,
+        _ => return false,
+    };
+
+    let targets_convoying_fleet = |target: &RegionKey| {
+        ctx.convoys_for(cutting_order)
+            .any(|convoy| convoy.region.province() == target.province())
+    };
+
+    let shielded = match (ctx.rules.paradox_rule(), supported) {
+        (ParadoxRule::Rulebook1971, SupportedOrder::Hold(_, target))
+        | (ParadoxRule::Rulebook1971, SupportedOrder::Move(_, _, target))
+        | (ParadoxRule::Rulebook1982, SupportedOrder::Move(_, _, target)) => {
+            targets_convoying_fleet(target)
+        }
+        (ParadoxRule::Dptg, _) => resolver.order_in_paradox(support_order),
+        _ => false,
+    };
+
+    shielded && calc::is_convoyed(ctx, cutting_order)
+}
+
 /// Find all orders which cut a specified support order.
 pub fn find_cutting_order<'a>(
     ctx: &Context<'a, impl Adjudicate>,
     resolver: &mut ResolverState<'a>,
     support_order: &MappedMainOrder,
 ) -> Option<&'a MappedMainOrder> {
-    ctx.orders()
+    ctx.moves_to_province(support_order.region.province())
         .find(|order| order_cuts(ctx, resolver, support_order, order))
 }
 
@@ -73,8 +109,8 @@ pub fn is_order_cut<'a>(
     resolver: &mut ResolverState<'a>,
     support_order: &MappedMainOrder,
 ) -> bool {
-    ctx.orders()
-        .any(|order| order_cuts(ctx, resolver, support_order, &order))
+    ctx.moves_to_province(support_order.region.province())
+        .any(|order| order_cuts(ctx, resolver, support_order, order))
 }
 
 pub fn is_supporting_self(support_order: &MappedMainOrder) -> bool {
@@ -100,7 +136,6 @@ pub fn can_reach(world_map: &Map, support_order: &MappedMainOrder) -> bool {
 
         world_map
             .find_borders_between(&support_order.region, needed_at)
-            .iter()
             .any(|b| b.is_passable_by(support_order.unit_type))
     } else {
         false
@@ -135,7 +170,7 @@ pub fn is_successful<'a>(
         is_legal(support_order)
             && beneficiary.is_legal()
             && beneficiary == supported
-            && can_reach(&ctx.world_map, support_order)
+            && can_reach(ctx.world_map, support_order)
             && resolver.resolve(ctx, support_order).into()
     } else {
         false
@@ -148,7 +183,7 @@ pub fn find_for<'a>(
     resolver: &mut ResolverState<'a>,
     supported: &MappedMainOrder,
 ) -> Vec<&'a MappedMainOrder> {
-    ctx.orders()
+    ctx.supports_for(supported)
         .filter(|order| is_successful(ctx, resolver, supported, order))
         .collect()
 }
@@ -162,6 +197,8 @@ pub enum SupportOutcome<'a> {
     /// Support cannot be convoyed, so reachability is a simple border check.
     CantReach,
     CutBy(&'a MappedMainOrder),
+    /// The support was part of a convoy paradox, and was not given in order to resolve it.
+    Paradox,
 }
 
 impl<'a> SupportOutcome<'a> {
@@ -222,7 +259,7 @@ mod test {
         assert_eq!(supp_com, orders[1]);
         assert!(super::can_reach(standard_map(), &orders[0]));
 
-        let resolver_ctx = Context::new(standard_map(), crate::judge::Rulebook, &orders);
+        let resolver_ctx = Context::new(standard_map(), crate::judge::Rulebook::default(), &orders);
         let mut res_state = ResolverState::new();
         let supporters = find_for(&resolver_ctx, &mut res_state, &orders[1]);
         assert!(!supporters.is_empty());
@@ -233,7 +270,7 @@ mod test {
         let fra = Nation::from("fra");
         let spa_nc = RegionKey::from_str("spa(nc)").unwrap();
         let supp_com = SupportedOrder::Move(UnitType::Fleet, reg("gas"), spa_nc.clone());
-        let orders = vec![
+        let orders = [
             Order::new(
                 fra.clone(),
                 UnitType::Fleet,
@@ -0,0 +1,330 @@
+//! Checks for main-phase orders that can run before adjudication, such as while a player is
+//! still writing their orders.
+//!
+//! Validation only considers the map and the positions of units on the board. An order without
+//! warnings may still fail when adjudicated, and the judge may still adjudicate an order with
+//! warnings; for example, a support of a unit that isn't there is adjudicated and cut as usual.
+
+use super::{legal, sanity, MappedMainOrder};
+use crate::geo::{Map, RegionKey};
+use crate::order::{MainCommand, SupportedOrder};
+use crate::{UnitPosition, UnitPositions, UnitType};
+use std::collections::HashSet;
+use std::fmt;
+
+/// A problem with a main-phase order found before adjudication.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OrderWarning {
+    /// There is no unit in the ordered province.
+    NoUnit,
+    /// The unit in the ordered province belongs to another nation.
+    ForeignUnit,
+    /// The unit in the ordered province is not of the type named in the order. The type of the
+    /// unit on the board is included.
+    WrongUnitType(UnitType),
+    /// Another order earlier in the list was issued to the same unit.
+    MultipleToSameUnit,
+    /// The order moves the unit to the province it's already in.
+    MoveToSelf,
+    /// The destination isn't across a border the unit can pass, and no chain of fleets at sea
+    /// could convoy the unit there. A move that forbids a convoy is only checked for a border,
+    /// and one that requires a convoy only for a chain of fleets.
+    Unreachable,
+    /// There is no unit of the named type in the region the order supports or convoys.
+    NoSupportedUnit,
+    /// The supporting unit can't move to the province where its support is needed.
+    SupportCantReach,
+    /// The supported move isn't across a border the supported unit can pass, and no chain of
+    /// fleets other than the supporting unit could convoy it there.
+    SupportedMoveUnreachable,
+    /// The order supports the unit's own province.
+    SupportingSelf,
+    /// The convoying unit is not a fleet at sea.
+    ConvoyNotAtSea,
+    /// The convoying fleet is not part of any chain of fleets from the army to its destination.
+    ConvoyOffRoute,
+}
+
+impl fmt::Display for OrderWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderWarning::NoUnit => write!(f, "There is no unit in this province"),
+            OrderWarning::ForeignUnit => write!(f, "The unit belongs to another nation"),
+            OrderWarning::WrongUnitType(actual) => {
+                write!(f, "The unit in this province is a {:?}", actual)
+            }
+            OrderWarning::MultipleToSameUnit => write!(f, "The unit already has an order"),
+            OrderWarning::MoveToSelf => write!(f, "The unit is already in this province"),
+            OrderWarning::Unreachable => write!(f, "The unit can't reach the destination"),
+            OrderWarning::NoSupportedUnit => {
+                write!(f, "There is no such unit to support or convoy")
+            }
+            OrderWarning::SupportCantReach => {
+                write!(f, "The unit can't reach the province it's supporting")
+            }
+            OrderWarning::SupportedMoveUnreachable => {
+                write!(f, "The supported unit can't reach its destination")
+            }
+            OrderWarning::SupportingSelf => write!(f, "A unit can't support itself"),
+            OrderWarning::ConvoyNotAtSea => write!(f, "Only fleets at sea can convoy"),
+            OrderWarning::ConvoyOffRoute => {
+                write!(f, "The fleet can't be part of a convoy along this route")
+            }
+        }
+    }
+}
+
+/// Check a single order against the map and the units on the board. This doesn't check for
+/// other orders to the same unit; use `validate_orders` to check a full set of orders.
+///
+/// Coasts are checked during adjudication rather than here, so a fleet order that omits or
+/// misnames a coast is validated against the fleet in the province.
+pub fn validate_order(
+    world: &Map,
+    positions: &impl UnitPositions<RegionKey>,
+    order: &MappedMainOrder,
+) -> Vec<OrderWarning> {
+    let mut warnings = vec![];
+
+    let unit = match positions.find_province_occupier(order.region.province()) {
+        Some(unit) => unit,
+        None => return vec![OrderWarning::NoUnit],
+    };
+
+    if *unit.nation() != order.nation {
+        warnings.push(OrderWarning::ForeignUnit);
+    }
+
+    if unit.unit.unit_type() != order.unit_type {
+        warnings.push(OrderWarning::WrongUnitType(unit.unit.unit_type()));
+        return warnings;
+    }
+
+    let units = positions.unit_positions();
+    let fleets = legal::fleets_at_sea(world, &units);
+
+    match &order.command {
+        MainCommand::Hold => {}
+        MainCommand::Move(cmd) => {
+            let dest = cmd.dest().province();
+            // A move that forbids a convoy needs a border, and one that requires a convoy needs
+            // a chain of fleets, even if the other way would reach the destination.
+            let reachable = if cmd.forbids_convoy() {
+                world
+                    .find_borders_between(unit.region, dest)
+                    .any(|border| border.is_passable_by(order.unit_type))
+            } else if cmd.mandates_convoy() {
+                order.unit_type == UnitType::Army
+                    && legal::convoy_destinations(world, &fleets, unit.region.province())
+                        .iter()
+                        .any(|region| region.province() == dest)
+            } else {
+                legal::move_destinations(world, &fleets, order.unit_type, unit.region, None)
+                    .iter()
+                    .any(|region| region.province() == dest)
+            };
+
+            if dest == unit.region.province() {
+                warnings.push(OrderWarning::MoveToSelf);
+            } else if !reachable {
+                warnings.push(OrderWarning::Unreachable);
+            }
+        }
+        MainCommand::Support(supported) => {
+            let (unit_type, region, dest) = match supported {
+                SupportedOrder::Hold(unit_type, region) => (*unit_type, region, region),
+                SupportedOrder::Move(unit_type, from, to) => (*unit_type, from, to),
+            };
+
+            if dest.province() == unit.region.province() {
+                warnings.push(OrderWarning::SupportingSelf);
+            } else if !legal::can_reach(world, &unit, dest.province()) {
+                warnings.push(OrderWarning::SupportCantReach);
+            }
+
+            match find_unit(positions, unit_type, region) {
+                None => warnings.push(OrderWarning::NoSupportedUnit),
+                Some(supported_unit) => {
+                    if let SupportedOrder::Move(..) = supported {
+                        let reachable = supported_unit.region.province() != dest.province()
+                            && legal::move_destinations(
+                                world,
+                                &fleets,
+                                unit_type,
+                                supported_unit.region,
+                                Some(unit.region),
+                            )
+                            .iter()
+                            .any(|d| d.province() == dest.province());
+
+                        if !reachable {
+                            warnings.push(OrderWarning::SupportedMoveUnreachable);
+                        }
+                    }
+                }
+            }
+        }
+        MainCommand::Convoy(mv) => {
+            if order.unit_type != UnitType::Fleet || !sanity::is_at_sea(world, unit.region) {
+                warnings.push(OrderWarning::ConvoyNotAtSea);
+            } else if find_unit(positions, UnitType::Army, mv.from()).is_none() {
+                warnings.push(OrderWarning::NoSupportedUnit);
+            } else if !sanity::reachable_fleets(world, &fleets, mv.from().province())
+                .contains(unit.region)
+                || !sanity::reachable_fleets(world, &fleets, mv.to().province())
+                    .contains(unit.region)
+            {
+                warnings.push(OrderWarning::ConvoyOffRoute);
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Check every order in a set of orders against the map and the units on the board. The result
+/// has the warnings for each order, in the same order as `orders`.
+///
+/// In addition to the checks made by `validate_order`, any order to a unit which already had an
+/// order earlier in the list is flagged with `OrderWarning::MultipleToSameUnit`.
+pub fn validate_orders(
+    world: &Map,
+    positions: &impl UnitPositions<RegionKey>,
+    orders: &[MappedMainOrder],
+) -> Vec<Vec<OrderWarning>> {
+    let mut ordered = HashSet::new();
+    orders
+        .iter()
+        .map(|order| {
+            let mut warnings = validate_order(world, positions, order);
+            if warnings.is_empty() && !ordered.insert(order.region.province()) {
+                warnings.push(OrderWarning::MultipleToSameUnit);
+            }
+
+            warnings
+        })
+        .collect()
+}
+
+/// Find the unit of type `unit_type` in the province of `region`.
+fn find_unit<'a>(
+    positions: &'a impl UnitPositions<RegionKey>,
+    unit_type: UnitType,
+    region: &RegionKey,
+) -> Option<UnitPosition<'a>> {
+    positions
+        .find_province_occupier(region.province())
+        .filter(|unit| unit.unit.unit_type() == unit_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_order, validate_orders, OrderWarning};
+    use crate::geo::{standard_map, RegionKey};
+    use crate::judge::MappedMainOrder;
+    use crate::{UnitPosition, UnitType};
+
+    fn units() -> Vec<UnitPosition<'static, RegionKey>> {
+        [
+            "ENG: A lon",
+            "ENG: F nth",
+            "FRA: A par",
+            "FRA: F bre",
+            "GER: A ruh",
+        ]
+        .iter()
+        .map(|unit| unit.parse().unwrap())
+        .collect()
+    }
+
+    fn check(order: &str) -> Vec<OrderWarning> {
+        let order = order.parse::<MappedMainOrder>().unwrap();
+        validate_order(standard_map(), &units(), &order)
+    }
+
+    #[test]
+    fn valid_orders() {
+        assert!(check("ENG: A lon -> nwy").is_empty());
+        assert!(check("ENG: F nth convoys lon -> bel").is_empty());
+        assert!(check("FRA: A par supports F bre -> pic").is_empty());
+        assert!(check("GER: A ruh supports A par -> bur").is_empty());
+    }
+
+    #[test]
+    fn wrong_unit() {
+        assert_eq!(vec![OrderWarning::NoUnit], check("ENG: A yor Hold"));
+        assert_eq!(vec![OrderWarning::ForeignUnit], check("GER: A par Hold"));
+        assert_eq!(
+            vec![OrderWarning::WrongUnitType(UnitType::Army)],
+            check("ENG: F lon -> eng")
+        );
+    }
+
+    #[test]
+    fn unreachable_moves() {
+        assert_eq!(vec![OrderWarning::Unreachable], check("FRA: A par -> mar"));
+        assert_eq!(vec![OrderWarning::MoveToSelf], check("FRA: A par -> par"));
+        assert_eq!(vec![OrderWarning::Unreachable], check("FRA: A par -> lon"));
+    }
+
+    #[test]
+    fn convoy_preferences() {
+        assert!(check("ENG: A lon -> bel").is_empty());
+        assert!(check("ENG: A lon -> wal no convoy").is_empty());
+        assert!(check("ENG: A lon -> yor via convoy").is_empty());
+        assert_eq!(
+            vec![OrderWarning::Unreachable],
+            check("ENG: A lon -> bel no convoy")
+        );
+        assert_eq!(
+            vec![OrderWarning::Unreachable],
+            check("FRA: A par -> bur via convoy")
+        );
+    }
+
+    #[test]
+    fn support_problems() {
+        assert_eq!(
+            vec![OrderWarning::NoSupportedUnit],
+            check("FRA: A par supports A bre")
+        );
+        assert_eq!(
+            vec![OrderWarning::SupportCantReach],
+            check("GER: A ruh supports A par -> gas")
+        );
+        assert_eq!(
+            vec![OrderWarning::SupportedMoveUnreachable],
+            check("FRA: A par supports F bre -> bur")
+        );
+        assert_eq!(
+            vec![OrderWarning::SupportedMoveUnreachable],
+            check("ENG: F nth supports A lon -> bel")
+        );
+    }
+
+    #[test]
+    fn convoy_problems() {
+        assert_eq!(
+            vec![OrderWarning::ConvoyNotAtSea],
+            check("FRA: F bre convoys par -> lon")
+        );
+        assert_eq!(
+            vec![OrderWarning::ConvoyOffRoute],
+            check("ENG: F nth convoys lon -> spa")
+        );
+    }
+
+    #[test]
+    fn multiple_orders_to_same_unit() {
+        let orders = ["FRA: A par Hold", "FRA: A par -> bur"]
+            .iter()
+            .map(|order| order.parse().unwrap())
+            .collect::<Vec<MappedMainOrder>>();
+
+        assert_eq!(
+            vec![vec![], vec![OrderWarning::MultipleToSameUnit]],
+            validate_orders(standard_map(), &units(), &orders)
+        );
+    }
+}
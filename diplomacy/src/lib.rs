@@ -2,19 +2,27 @@
 //! be fully compatible with the [Diplomacy Adjudicator Test Cases](http://web.inter.nl.net/users/L.B.Kruijswijk/).
 
 pub mod calendar;
+pub mod game;
 pub mod geo;
 pub mod judge;
 mod nation;
 pub mod order;
+mod ownership;
 pub mod parser;
+#[cfg(feature = "render")]
+pub mod render;
+pub mod scoring;
 mod time;
 mod unit;
 
 #[doc(inline)]
 pub use crate::calendar::{Calendar, Month};
+#[doc(inline)]
+pub use crate::game::Game;
 pub use crate::nation::Nation;
 #[doc(inline)]
 pub use crate::order::{Command, Order};
+pub use crate::ownership::{OwnershipChange, SupplyCenterOwnership};
 pub use crate::time::{Phase, Season, Time};
 pub use crate::unit::{Unit, UnitPosition, UnitPositions, UnitType};
 
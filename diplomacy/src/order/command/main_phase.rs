@@ -34,10 +34,7 @@ impl<L: Location> Command<L> for MainCommand<L> {
     }
 
     fn is_move(&self) -> bool {
-        match *self {
-            MainCommand::Move(..) => true,
-            _ => false,
-        }
+        matches!(*self, MainCommand::Move(..))
     }
 }
 
@@ -59,9 +56,6 @@ impl<L: Location> fmt::Display for MainCommand<L> {
 pub struct MoveCommand<L> {
     dest: L,
     /// Whether the order required, forbade, or didn't specify convoy usage.
-    ///
-    /// Right now, the adjudicator doesn't handle the forbid case, so there's no way to construct
-    /// such a command.
     use_convoy: Option<bool>,
 }
 
@@ -82,11 +76,25 @@ impl<L> MoveCommand<L> {
         }
     }
 
+    /// Create a new move command which forbids the use of a convoy. The unit will only
+    /// travel directly to the destination, even if fleets are ordered to convoy it.
+    pub fn with_forbidden_convoy(dest: L) -> Self {
+        Self {
+            dest,
+            use_convoy: Some(false),
+        }
+    }
+
     /// Get the move command's destination region.
     pub fn dest(&self) -> &L {
         &self.dest
     }
 
+    /// Get a mutable reference to the destination, keeping the convoy preference intact.
+    pub(crate) fn dest_mut(&mut self) -> &mut L {
+        &mut self.dest
+    }
+
     /// The order explicitly mandates the use of a convoy. If `true`, direct paths
     /// to the destination should not be considered when choosing a path.
     ///
@@ -97,6 +105,12 @@ impl<L> MoveCommand<L> {
         self.use_convoy == Some(true)
     }
 
+    /// The order explicitly forbids the use of a convoy. If `true`, convoy routes to the
+    /// destination should not be considered when choosing a path.
+    pub fn forbids_convoy(&self) -> bool {
+        self.use_convoy == Some(false)
+    }
+
     /// The order explicitly mentions convoys, either mandating or forbidding their use.
     pub fn mentions_convoy(&self) -> bool {
         self.use_convoy.is_some()
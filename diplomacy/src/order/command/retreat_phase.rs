@@ -3,7 +3,7 @@ use crate::{geo::Location, ShortName};
 use std::fmt;
 
 /// Valid commands for the retreat phase of a turn.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RetreatCommand<L> {
     Hold,
@@ -0,0 +1,195 @@
+use crate::geo::{Map, ProvinceKey, RegionKey, SupplyCenter};
+use crate::{Nation, UnitPositions};
+use std::collections::{BTreeMap, HashMap};
+use std::iter::FromIterator;
+
+/// The nation controlling each supply center at a point in the game.
+///
+/// Ownership only changes at the end of a fall turn, once retreats are done: each supply center
+/// with a unit in it belongs to that unit's nation, and vacant supply centers keep their owner.
+/// Call `SupplyCenterOwnership::update` with the unit positions at that point, before the build
+/// phase is adjudicated.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct SupplyCenterOwnership {
+    owners: BTreeMap<ProvinceKey, Nation>,
+}
+
+impl SupplyCenterOwnership {
+    /// The ownership at the start of a game, where each nation owns its home supply centers and
+    /// all other supply centers are unowned.
+    pub fn initial(map: &Map) -> Self {
+        map.provinces()
+            .filter_map(|province| match &province.supply_center {
+                SupplyCenter::Home(nation) => Some((province.into(), nation.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get the nation that owns a supply center, if any.
+    pub fn owner(&self, province: &ProvinceKey) -> Option<&Nation> {
+        self.owners.get(province)
+    }
+
+    /// Iterate over the owned supply centers and their owners, in order of province.
+    pub fn iter(&self) -> impl Iterator<Item = (&ProvinceKey, &Nation)> {
+        self.owners.iter()
+    }
+
+    /// Iterate over the supply centers owned by `nation`.
+    pub fn centers<'a>(&'a self, nation: &'a Nation) -> impl Iterator<Item = &'a ProvinceKey> {
+        self.owners
+            .iter()
+            .filter(move |(_, owner)| *owner == nation)
+            .map(|(province, _)| province)
+    }
+
+    /// The number of supply centers owned by `nation`.
+    pub fn center_count(&self, nation: &Nation) -> usize {
+        self.centers(nation).count()
+    }
+
+    /// The number of supply centers owned by each nation. Nations without supply centers are
+    /// not included.
+    pub fn center_counts(&self) -> BTreeMap<&Nation, usize> {
+        let mut counts = BTreeMap::new();
+        for nation in self.owners.values() {
+            *counts.entry(nation).or_insert(0) += 1;
+        }
+
+        counts
+    }
+
+    /// Give each occupied supply center on `map` to the nation of the unit occupying it. This
+    /// should be called with the unit positions at the end of a fall turn, after retreats.
+    pub fn update(&mut self, map: &Map, positions: &impl UnitPositions<RegionKey>) {
+        for province in map.provinces().filter(|p| p.is_supply_center()) {
+            let key = ProvinceKey::from(province);
+            if let Some(occupier) = positions.find_province_occupier(&key) {
+                self.owners.insert(key, occupier.nation().clone());
+            }
+        }
+    }
+
+    /// Find the supply centers which have changed hands since `previous`, in order of province.
+    pub fn changes_since(&self, previous: &SupplyCenterOwnership) -> Vec<OwnershipChange> {
+        let mut provinces = self
+            .owners
+            .keys()
+            .chain(previous.owners.keys())
+            .collect::<Vec<_>>();
+        provinces.sort();
+        provinces.dedup();
+
+        provinces
+            .into_iter()
+            .filter_map(|province| {
+                let from = previous.owner(province);
+                let to = self.owner(province);
+                if from == to {
+                    None
+                } else {
+                    Some(OwnershipChange {
+                        province: province.clone(),
+                        from: from.cloned(),
+                        to: to.cloned(),
+                    })
+                }
+            })
+            .collect()
+    }
+}
+
+impl FromIterator<(ProvinceKey, Nation)> for SupplyCenterOwnership {
+    fn from_iter<I: IntoIterator<Item = (ProvinceKey, Nation)>>(iter: I) -> Self {
+        Self {
+            owners: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[allow(clippy::implicit_hasher)]
+impl From<HashMap<ProvinceKey, Nation>> for SupplyCenterOwnership {
+    fn from(owners: HashMap<ProvinceKey, Nation>) -> Self {
+        owners.into_iter().collect()
+    }
+}
+
+/// Build-phase adjudication takes the previous ownership as a `HashMap`.
+#[allow(clippy::implicit_hasher)]
+impl From<&'_ SupplyCenterOwnership> for HashMap<ProvinceKey, Nation> {
+    fn from(ownership: &SupplyCenterOwnership) -> Self {
+        ownership
+            .iter()
+            .map(|(province, nation)| (province.clone(), nation.clone()))
+            .collect()
+    }
+}
+
+/// A supply center that changed hands between two points in the game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnershipChange {
+    pub province: ProvinceKey,
+    /// The previous owner, or `None` if the supply center was unowned.
+    pub from: Option<Nation>,
+    /// The new owner, or `None` if the supply center is no longer owned.
+    pub to: Option<Nation>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geo::standard_map;
+    use crate::UnitPosition;
+
+    fn units(units: &[&str]) -> Vec<UnitPosition<'static, RegionKey>> {
+        units.iter().map(|unit| unit.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn initial_ownership() {
+        let ownership = SupplyCenterOwnership::initial(standard_map());
+        assert_eq!(22, ownership.iter().count());
+        assert_eq!(3, ownership.center_count(&Nation::from("AUS")));
+        assert_eq!(4, ownership.center_count(&Nation::from("RUS")));
+        assert_eq!(None, ownership.owner(&ProvinceKey::from("bel")));
+    }
+
+    #[test]
+    fn update_after_fall() {
+        let previous = SupplyCenterOwnership::initial(standard_map());
+        let mut ownership = previous.clone();
+        ownership.update(
+            standard_map(),
+            &units(&["TUR: A bul", "RUS: A gal", "AUS: F tri", "ITA: A ven"]),
+        );
+
+        assert_eq!(Some(&Nation::from("TUR")), ownership.owner(&"bul".into()));
+        assert_eq!(None, ownership.owner(&"gal".into()));
+        assert_eq!(4, ownership.center_count(&Nation::from("TUR")));
+
+        ownership.update(standard_map(), &units(&["ITA: A tri"]));
+        assert_eq!(
+            vec![
+                OwnershipChange {
+                    province: "bul".into(),
+                    from: None,
+                    to: Some(Nation::from("TUR")),
+                },
+                OwnershipChange {
+                    province: "tri".into(),
+                    from: Some(Nation::from("AUS")),
+                    to: Some(Nation::from("ITA")),
+                },
+            ],
+            ownership.changes_since(&previous)
+        );
+        assert_eq!(
+            Some(&4),
+            ownership.center_counts().get(&Nation::from("ITA"))
+        );
+    }
+}
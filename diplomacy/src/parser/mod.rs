@@ -8,7 +8,7 @@
 //!
 //! # Supported Commands
 //! 1. Hold: `hold` or `holds`
-//! 1. Move: `-> {Destination}`
+//! 1. Move: `-> {Destination}`, optionally followed by `via convoy` or `no convoy`
 //! 1. Support: `supports {UnitType} {Region} [-> {Dest}]`
 //! 1. Convoy: `convoys {Region} -> {Dest}`
 //! 1. Build: `build` (this is non-idiomatic, but easier to parse)
@@ -85,6 +85,9 @@ impl<L: Location + FromStr<Err = Error>> FromWords for MoveCommand<L> {
             3 if w[1] == "via" && CONVOY_CASINGS.contains(&w[2]) => {
                 Ok(MoveCommand::with_mandatory_convoy(w[0].parse()?))
             }
+            3 if w[1] == "no" && CONVOY_CASINGS.contains(&w[2]) => {
+                Ok(MoveCommand::with_forbidden_convoy(w[0].parse()?))
+            }
             _ => Err(Error::new(ErrorKind::MalformedMove, w.join(" "))),
         }
     }
@@ -179,4 +182,20 @@ mod test {
         let no_pref: OrderParseResult = "ENG: A Lon -> Bel".parse();
         assert_ne!(no_pref.unwrap(), order);
     }
+
+    #[test]
+    fn army_move_no_convoy() {
+        let m_order: OrderParseResult = "ENG: A Lon -> Bel no convoy".parse();
+        let order = m_order.unwrap();
+        match &order.command {
+            MainCommand::Move(cmd) => assert!(cmd.forbids_convoy()),
+            _ => panic!("Order should be a move"),
+        }
+
+        let round_trip: OrderParseResult = order.to_string().parse();
+        assert_eq!(round_trip.unwrap(), order);
+
+        let via_convoy: OrderParseResult = "ENG: A Lon -> Bel via convoy".parse();
+        assert_ne!(via_convoy.unwrap(), order);
+    }
 }
@@ -0,0 +1,245 @@
+//! Drawing metadata for a map, loaded from a text document kept alongside the map data.
+//!
+//! # Text Format
+//! A layout document has three sections, which must appear in this order.
+//!
+//! ```text
+//! # Lines starting with '#' and blank lines are ignored.
+//! [size]
+//! width,height
+//! 960,780
+//!
+//! [nations]
+//! nation,color
+//! FRA,#5dade2
+//!
+//! [regions]
+//! region,x,y
+//! bre,250,410
+//! spa(nc),200,520
+//! ```
+//!
+//! The header row of each section is optional. Regions are written as they are in orders, and
+//! their coordinates are the center of the region in the drawing. Colors can be any SVG paint.
+
+use crate::geo::RegionKey;
+use crate::Nation;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::BufRead;
+
+const SIZE_HEADER: &str = "width,height";
+const NATIONS_HEADER: &str = "nation,color";
+const REGIONS_HEADER: &str = "region,x,y";
+
+/// The error type for loading a layout document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// A row or value in the document couldn't be parsed.
+    Malformed(String),
+    /// The document had a section header where it wasn't expected.
+    UnexpectedSection(String),
+    /// The document ended without the named section.
+    MissingSection(String),
+    /// Reading the document failed.
+    Io(String),
+    /// An error on the given line of the document, counting from 1.
+    Line(usize, Box<LayoutError>),
+    /// A region was given coordinates more than once.
+    DuplicateRegion(String),
+}
+
+impl Error for LayoutError {}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutError::Malformed(input) => write!(f, "Malformed input: '{}'", input),
+            LayoutError::UnexpectedSection(name) => write!(f, "Unexpected section: '{}'", name),
+            LayoutError::MissingSection(name) => write!(f, "Missing section: '{}'", name),
+            LayoutError::Io(message) => write!(f, "I/O error: {}", message),
+            LayoutError::Line(line, e) => write!(f, "Line {}: {}", line, e),
+            LayoutError::DuplicateRegion(name) => write!(f, "Duplicate region: '{}'", name),
+        }
+    }
+}
+
+/// Where to draw each region of a map, and the color to draw each nation in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout {
+    width: f64,
+    height: f64,
+    colors: HashMap<Nation, String>,
+    positions: HashMap<RegionKey, (f64, f64)>,
+}
+
+impl Layout {
+    /// Load a layout from a document in the format described in the module documentation.
+    ///
+    /// Errors in the document are reported with the line on which they occurred.
+    pub fn from_reader(reader: impl BufRead) -> Result<Self, LayoutError> {
+        let mut section = Section::Start;
+        let mut layout = Layout {
+            width: 0.0,
+            height: 0.0,
+            colors: HashMap::new(),
+            positions: HashMap::new(),
+        };
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| LayoutError::Io(e.to_string()))?;
+            section = layout
+                .read_line(section, line.trim())
+                .map_err(|e| LayoutError::Line(index + 1, Box::new(e)))?;
+        }
+
+        match section {
+            Section::Regions => Ok(layout),
+            Section::Start => Err(LayoutError::MissingSection("size".into())),
+            Section::Size => Err(LayoutError::MissingSection("nations".into())),
+            Section::Nations => Err(LayoutError::MissingSection("regions".into())),
+        }
+    }
+
+    /// The width of the drawing.
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    /// The height of the drawing.
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+
+    /// Get the center of `region` in the drawing, if the layout places it.
+    pub fn position(&self, region: &RegionKey) -> Option<(f64, f64)> {
+        self.positions.get(region).copied()
+    }
+
+    /// Get the color for `nation`, if the layout assigns it one.
+    pub fn color(&self, nation: &Nation) -> Option<&str> {
+        self.colors.get(nation).map(String::as_str)
+    }
+
+    fn read_line(&mut self, section: Section, line: &str) -> Result<Section, LayoutError> {
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(section);
+        }
+
+        match (section, line) {
+            (Section::Start, "[size]") => Ok(Section::Size),
+            (Section::Size, "[nations]") => Ok(Section::Nations),
+            (Section::Nations, "[regions]") => Ok(Section::Regions),
+            (_, line) if line.starts_with('[') => Err(LayoutError::UnexpectedSection(line.into())),
+            (Section::Start, line) => Err(LayoutError::Malformed(line.into())),
+            (section, line) if section.is_header(line) => Ok(section),
+            (Section::Size, line) => {
+                match split_line(line)[..] {
+                    [width, height] => {
+                        self.width = number(width)?;
+                        self.height = number(height)?;
+                    }
+                    _ => return Err(LayoutError::Malformed(line.into())),
+                }
+
+                Ok(Section::Size)
+            }
+            (Section::Nations, line) => {
+                match split_line(line)[..] {
+                    [nation, color] if !nation.is_empty() && !color.is_empty() => {
+                        self.colors.insert(Nation::from(nation), color.into());
+                    }
+                    _ => return Err(LayoutError::Malformed(line.into())),
+                }
+
+                Ok(Section::Nations)
+            }
+            (Section::Regions, line) => {
+                match split_line(line)[..] {
+                    [region, x, y] => {
+                        let key = region
+                            .parse::<RegionKey>()
+                            .map_err(|_| LayoutError::Malformed(region.into()))?;
+                        let position = (number(x)?, number(y)?);
+                        if self.positions.insert(key, position).is_some() {
+                            return Err(LayoutError::DuplicateRegion(region.into()));
+                        }
+                    }
+                    _ => return Err(LayoutError::Malformed(line.into())),
+                }
+
+                Ok(Section::Regions)
+            }
+        }
+    }
+}
+
+/// The section of a layout document being read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Start,
+    Size,
+    Nations,
+    Regions,
+}
+
+impl Section {
+    fn is_header(self, line: &str) -> bool {
+        let header = match self {
+            Section::Start => return false,
+            Section::Size => SIZE_HEADER,
+            Section::Nations => NATIONS_HEADER,
+            Section::Regions => REGIONS_HEADER,
+        };
+
+        split_line(line) == split_line(header)
+    }
+}
+
+fn split_line(line: &str) -> Vec<&str> {
+    line.split(',').map(str::trim).collect()
+}
+
+fn number(word: &str) -> Result<f64, LayoutError> {
+    word.parse()
+        .map_err(|_| LayoutError::Malformed(word.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Layout, LayoutError};
+    use crate::geo::{standard_map, RegionKey};
+    use crate::render::standard_layout;
+    use crate::Nation;
+
+    #[test]
+    fn standard_layout_places_every_region() {
+        let layout = standard_layout();
+        for region in standard_map().regions() {
+            assert!(
+                layout.position(&RegionKey::from(region)).is_some(),
+                "{} has no position",
+                RegionKey::from(region)
+            );
+        }
+
+        for nation in &["AUS", "ENG", "FRA", "GER", "ITA", "RUS", "TUR"] {
+            assert!(layout.color(&Nation::from(*nation)).is_some());
+        }
+    }
+
+    #[test]
+    fn errors_report_line() {
+        let document = "[size]\n10,10\n[nations]\n[regions]\nbre,1\n";
+        assert_eq!(
+            LayoutError::Line(5, Box::new(LayoutError::Malformed("bre,1".into()))),
+            Layout::from_reader(document.as_bytes()).unwrap_err()
+        );
+
+        assert_eq!(
+            LayoutError::MissingSection("regions".into()),
+            Layout::from_reader("[size]\n[nations]\n".as_bytes()).unwrap_err()
+        );
+    }
+}
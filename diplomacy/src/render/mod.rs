@@ -0,0 +1,514 @@
+//! Draws the board as SVG, with units, orders, order outcomes and supply center ownership.
+//!
+//! Provinces are drawn as a schematic: each region is a circle at the position given by a
+//! `Layout`, joined to its neighbors by the borders of the map. Ownership, units and orders are
+//! drawn over it, so a `Board` renders the same way for any map with a layout.
+//!
+//! ```rust
+//! use diplomacy::geo::{standard_map, RegionKey};
+//! use diplomacy::render::Board;
+//! use diplomacy::UnitPosition;
+//!
+//! let units: Vec<UnitPosition<'_, RegionKey>> = vec!["FRA: A par".parse().unwrap()];
+//! let svg = Board::standard().with_units(&units).to_string();
+//! assert!(svg.starts_with("<svg"));
+//! ```
+
+mod layout;
+
+pub use self::layout::{Layout, LayoutError};
+
+use crate::geo::{standard_map, Map, ProvinceKey, RegionKey, Terrain};
+use crate::judge::{
+    retreat, Adjudicate, AttackOutcome, MappedMainOrder, OrderOutcome, OrderState, Outcome,
+};
+use crate::order::{MainCommand, SupportedOrder};
+use crate::{Nation, ShortName, SupplyCenterOwnership, UnitPositions, UnitType};
+use lazy_static::lazy_static;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+lazy_static! {
+    static ref STANDARD_LAYOUT: Layout = Layout::from_reader(&include_bytes!("standard.txt")[..])
+        .expect("standard layout shouldn't have issues");
+}
+
+/// Gets a static reference to the layout for the standard map.
+pub fn standard_layout() -> &'static Layout {
+    &STANDARD_LAYOUT
+}
+
+const PROVINCE_RADIUS: f64 = 16.0;
+const COAST_RADIUS: f64 = 5.0;
+const UNIT_WIDTH: f64 = 22.0;
+const UNIT_HEIGHT: f64 = 14.0;
+const DISLODGED_OFFSET: f64 = 14.0;
+const UNOWNED_COLOR: &str = "#999999";
+const LAND_COLOR: &str = "#f5e6c8";
+const SEA_COLOR: &str = "#a9cce3";
+
+/// A unit to draw on the board.
+#[derive(Debug, Clone)]
+struct Piece {
+    nation: Nation,
+    unit_type: UnitType,
+    region: RegionKey,
+}
+
+/// An order to draw on the board, with its state if it has been adjudicated.
+#[derive(Debug, Clone)]
+struct Arrow {
+    order: MappedMainOrder,
+    state: Option<OrderState>,
+}
+
+/// A drawing of the board. Add the units, ownership and orders to show, then render the board
+/// as SVG with `Display`.
+#[derive(Debug, Clone)]
+pub struct Board<'a> {
+    map: &'a Map,
+    layout: &'a Layout,
+    units: Vec<Piece>,
+    dislodged: Vec<Piece>,
+    orders: Vec<Arrow>,
+    bounces: BTreeSet<ProvinceKey>,
+    owners: HashMap<ProvinceKey, Nation>,
+}
+
+impl<'a> Board<'a> {
+    /// Create an empty drawing of `map`. Regions without a position in `layout` aren't drawn.
+    pub fn new(map: &'a Map, layout: &'a Layout) -> Self {
+        Board {
+            map,
+            layout,
+            units: vec![],
+            dislodged: vec![],
+            orders: vec![],
+            bounces: BTreeSet::new(),
+            owners: HashMap::new(),
+        }
+    }
+
+    /// Create an empty drawing of the standard map.
+    pub fn standard() -> Board<'static> {
+        Board::new(standard_map(), standard_layout())
+    }
+
+    /// Draw the units in `positions`.
+    pub fn with_units(mut self, positions: &impl UnitPositions<RegionKey>) -> Self {
+        self.units
+            .extend(positions.unit_positions().into_iter().map(|unit| Piece {
+                nation: unit.nation().clone(),
+                unit_type: unit.unit.unit_type(),
+                region: unit.region.clone(),
+            }));
+        self
+    }
+
+    /// Color each owned supply center with the color of its owner.
+    pub fn with_ownership(mut self, ownership: &SupplyCenterOwnership) -> Self {
+        self.owners.extend(
+            ownership
+                .iter()
+                .map(|(province, nation)| (province.clone(), nation.clone())),
+        );
+        self
+    }
+
+    /// Draw arrows for `orders`, which haven't been adjudicated.
+    pub fn with_orders<'o>(
+        mut self,
+        orders: impl IntoIterator<Item = &'o MappedMainOrder>,
+    ) -> Self {
+        self.orders.extend(orders.into_iter().map(|order| Arrow {
+            order: order.clone(),
+            state: None,
+        }));
+        self
+    }
+
+    /// Draw arrows for the adjudicated orders in `outcome`, fading those that failed and marking
+    /// the provinces where moves bounced.
+    pub fn with_outcome<'o, A: Adjudicate>(mut self, outcome: &'o Outcome<'o, A>) -> Self {
+        for order in outcome.orders() {
+            let order_outcome = outcome.get(order);
+            if let Some(OrderOutcome::Move(attack)) = order_outcome {
+                if is_bounce(attack) {
+                    if let MainCommand::Move(cmd) = &order.command {
+                        self.bounces.insert(cmd.dest().province().clone());
+                    }
+                }
+            }
+
+            self.orders.push(Arrow {
+                order: order.clone(),
+                state: order_outcome.map(OrderState::from),
+            });
+        }
+
+        self
+    }
+
+    /// Draw the units dislodged in the main phase before `start`, offset from the province they
+    /// were dislodged from.
+    pub fn with_retreat_start(mut self, start: &retreat::Start<'_>) -> Self {
+        let mut dislodged = start
+            .dislodged()
+            .keys()
+            .map(|order| Piece {
+                nation: order.nation.clone(),
+                unit_type: order.unit_type,
+                region: order.region.clone(),
+            })
+            .collect::<Vec<_>>();
+        dislodged.sort_by(|a, b| a.region.cmp(&b.region));
+        self.dislodged.extend(dislodged);
+        self
+    }
+
+    /// Get the position of `region`, falling back to its province for regions the layout
+    /// doesn't place.
+    fn position(&self, region: &RegionKey) -> Option<(f64, f64)> {
+        self.layout.position(region).or_else(|| {
+            self.layout
+                .position(&RegionKey::new(region.province().clone(), None))
+        })
+    }
+
+    fn color(&self, nation: &Nation) -> &str {
+        self.layout.color(nation).unwrap_or(UNOWNED_COLOR)
+    }
+
+    fn write_borders(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut borders = self.map.borders().collect::<Vec<_>>();
+        borders.sort_by_key(|border| border.sides());
+
+        writeln!(f, r#"<g class="borders" stroke-width="1.5">"#)?;
+        for border in borders {
+            let (a, b) = border.sides();
+            let ((x1, y1), (x2, y2)) = match (self.position(a), self.position(b)) {
+                (Some(a), Some(b)) => (a, b),
+                _ => continue,
+            };
+
+            let terrain = border.terrain();
+            writeln!(
+                f,
+                r#"<line class="{}" x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}"/>"#,
+                terrain_word(terrain),
+                x1,
+                y1,
+                x2,
+                y2,
+                if terrain == Terrain::Sea {
+                    "#5d8aa8"
+                } else {
+                    "#b5a27a"
+                }
+            )?;
+        }
+        writeln!(f, "</g>")
+    }
+
+    fn write_regions(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut regions = self.map.regions().collect::<Vec<_>>();
+        regions.sort_by_key(|region| RegionKey::from(*region));
+        let supply_centers = self
+            .map
+            .provinces()
+            .filter(|province| province.is_supply_center())
+            .map(ProvinceKey::from)
+            .collect::<HashSet<_>>();
+
+        writeln!(
+            f,
+            r#"<g class="regions" font-family="sans-serif" font-size="10">"#
+        )?;
+        for region in regions {
+            let key = RegionKey::from(region);
+            let (x, y) = match self.layout.position(&key) {
+                Some(position) => position,
+                None => continue,
+            };
+
+            if let Some(coast) = region.coast() {
+                writeln!(
+                    f,
+                    r##"<circle class="coast" cx="{}" cy="{}" r="{}" fill="{}" stroke="#333333"/>"##,
+                    x, y, COAST_RADIUS, SEA_COLOR
+                )?;
+                writeln!(
+                    f,
+                    r#"<text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+                    x,
+                    y - COAST_RADIUS - 3.0,
+                    escape(coast.short_name().trim_matches(|c| c == '(' || c == ')'))
+                )?;
+                continue;
+            }
+
+            let fill = match (self.owners.get(key.province()), region.terrain()) {
+                (Some(nation), _) => self.color(nation),
+                (None, Terrain::Sea) => SEA_COLOR,
+                (None, _) => LAND_COLOR,
+            };
+
+            writeln!(
+                f,
+                r##"<circle class="province" cx="{}" cy="{}" r="{}" fill="{}" stroke="#333333"/>"##,
+                x, y, PROVINCE_RADIUS, fill
+            )?;
+
+            if supply_centers.contains(key.province()) {
+                writeln!(
+                    f,
+                    r##"<circle class="supply-center" cx="{}" cy="{}" r="3" fill="#000000"/>"##,
+                    x,
+                    y - PROVINCE_RADIUS + 5.0
+                )?;
+            }
+
+            writeln!(
+                f,
+                r#"<text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+                x,
+                y + PROVINCE_RADIUS + 10.0,
+                escape(&key.short_name())
+            )?;
+        }
+        writeln!(f, "</g>")
+    }
+
+    fn write_orders(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, r#"<g class="orders" fill="none" stroke-width="2.5">"#)?;
+        for arrow in &self.orders {
+            let order = &arrow.order;
+            let from = match self.position(&order.region) {
+                Some(position) => position,
+                None => continue,
+            };
+
+            let (class, to, marker) = match &order.command {
+                MainCommand::Hold => continue,
+                MainCommand::Move(cmd) => match self.position(cmd.dest()) {
+                    Some(to) => ("move", shorten(from, to, PROVINCE_RADIUS), "arrow"),
+                    None => continue,
+                },
+                MainCommand::Support(SupportedOrder::Hold(_, region)) => {
+                    match self.position(region) {
+                        Some(to) => ("support", shorten(from, to, PROVINCE_RADIUS), "ring"),
+                        None => continue,
+                    }
+                }
+                MainCommand::Support(SupportedOrder::Move(_, start, dest)) => {
+                    match (self.position(start), self.position(dest)) {
+                        (Some(start), Some(dest)) => ("support", along(start, dest, 0.7), "ring"),
+                        _ => continue,
+                    }
+                }
+                MainCommand::Convoy(mv) => {
+                    match (self.position(mv.from()), self.position(mv.to())) {
+                        (Some(start), Some(dest)) => ("convoy", along(start, dest, 0.5), "ring"),
+                        _ => continue,
+                    }
+                }
+            };
+
+            let state = match arrow.state {
+                Some(OrderState::Succeeds) => " succeeds",
+                Some(OrderState::Fails) => " fails",
+                None => "",
+            };
+
+            writeln!(
+                f,
+                r#"<line class="{}{}" x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}"{}{} marker-end="url(#{})"><title>{}</title></line>"#,
+                class,
+                state,
+                from.0,
+                from.1,
+                to.0,
+                to.1,
+                self.color(&order.nation),
+                match class {
+                    "support" => r#" stroke-dasharray="6 3""#,
+                    "convoy" => r#" stroke-dasharray="2 3""#,
+                    _ => "",
+                },
+                if arrow.state == Some(OrderState::Fails) {
+                    r#" stroke-opacity="0.4""#
+                } else {
+                    ""
+                },
+                marker,
+                escape(&order.to_string())
+            )?;
+        }
+        writeln!(f, "</g>")
+    }
+
+    fn write_bounces(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            r##"<g class="bounces" stroke="#cc0000" stroke-width="3">"##
+        )?;
+        for province in &self.bounces {
+            let (x, y) = match self.position(&RegionKey::new(province.clone(), None)) {
+                Some(position) => position,
+                None => continue,
+            };
+
+            let size = PROVINCE_RADIUS / 2.0;
+            writeln!(
+                f,
+                r#"<path class="bounce" d="M {} {} L {} {} M {} {} L {} {}"><title>Bounce in {}</title></path>"#,
+                x - size,
+                y - size,
+                x + size,
+                y + size,
+                x - size,
+                y + size,
+                x + size,
+                y - size,
+                escape(&province.short_name())
+            )?;
+        }
+        writeln!(f, "</g>")
+    }
+
+    fn write_units(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            r#"<g class="units" font-family="sans-serif" font-size="10" font-weight="bold">"#
+        )?;
+        for (piece, dislodged) in self
+            .units
+            .iter()
+            .map(|piece| (piece, false))
+            .chain(self.dislodged.iter().map(|piece| (piece, true)))
+        {
+            let (mut x, mut y) = match self.position(&piece.region) {
+                Some(position) => position,
+                None => continue,
+            };
+
+            if dislodged {
+                x += DISLODGED_OFFSET;
+                y += DISLODGED_OFFSET;
+            }
+
+            let (class, letter, corner) = match piece.unit_type {
+                UnitType::Army => ("army", "A", 3.0),
+                UnitType::Fleet => ("fleet", "F", UNIT_HEIGHT / 2.0),
+            };
+
+            writeln!(
+                f,
+                r#"<g class="unit {}{}"><title>{}: {} {}</title>"#,
+                class,
+                if dislodged { " dislodged" } else { "" },
+                escape(&piece.nation.short_name()),
+                letter,
+                escape(&piece.region.short_name())
+            )?;
+            writeln!(
+                f,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" fill="{}" stroke="{}" stroke-width="{}"/>"#,
+                x - UNIT_WIDTH / 2.0,
+                y - UNIT_HEIGHT / 2.0,
+                UNIT_WIDTH,
+                UNIT_HEIGHT,
+                corner,
+                self.color(&piece.nation),
+                if dislodged { "#cc0000" } else { "#000000" },
+                if dislodged { 2.5 } else { 1.0 }
+            )?;
+            writeln!(
+                f,
+                r#"<text x="{}" y="{}" text-anchor="middle">{}</text></g>"#,
+                x,
+                y + 4.0,
+                letter
+            )?;
+        }
+        writeln!(f, "</g>")
+    }
+}
+
+/// Render the board as an SVG document.
+impl fmt::Display for Board<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = self.layout.width(),
+            h = self.layout.height()
+        )?;
+        writeln!(f, "<defs>")?;
+        writeln!(
+            f,
+            r#"<marker id="arrow" viewBox="0 0 10 10" refX="9" refY="5" markerWidth="5" markerHeight="5" orient="auto"><path d="M 0 0 L 10 5 L 0 10 z" fill="context-stroke"/></marker>"#
+        )?;
+        writeln!(
+            f,
+            r#"<marker id="ring" viewBox="0 0 10 10" refX="5" refY="5" markerWidth="4" markerHeight="4"><circle cx="5" cy="5" r="4" fill="none" stroke="context-stroke" stroke-width="2"/></marker>"#
+        )?;
+        writeln!(f, "</defs>")?;
+
+        self.write_borders(f)?;
+        self.write_regions(f)?;
+        self.write_orders(f)?;
+        self.write_bounces(f)?;
+        self.write_units(f)?;
+
+        writeln!(f, "</svg>")
+    }
+}
+
+/// Returns true if the attack failed because another unit held or prevented it, rather than for
+/// lack of a path or a paradox.
+fn is_bounce(attack: &AttackOutcome<'_>) -> bool {
+    matches!(
+        attack,
+        AttackOutcome::Prevented(_)
+            | AttackOutcome::LostHeadToHead
+            | AttackOutcome::OccupierDefended
+            | AttackOutcome::FriendlyFire
+    )
+}
+
+/// The point `fraction` of the way from `from` to `to`, rounded to one decimal place.
+fn along(from: (f64, f64), to: (f64, f64), fraction: f64) -> (f64, f64) {
+    let round = |value: f64| (value * 10.0).round() / 10.0;
+    (
+        round(from.0 + (to.0 - from.0) * fraction),
+        round(from.1 + (to.1 - from.1) * fraction),
+    )
+}
+
+/// Move `to` back towards `from` by `by`, so an arrow ends at the edge of a province rather
+/// than its center.
+fn shorten(from: (f64, f64), to: (f64, f64), by: f64) -> (f64, f64) {
+    let length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+    if length <= by {
+        to
+    } else {
+        along(from, to, (length - by) / length)
+    }
+}
+
+fn terrain_word(terrain: Terrain) -> &'static str {
+    match terrain {
+        Terrain::Land => "land",
+        Terrain::Coast => "coast",
+        Terrain::Sea => "sea",
+    }
+}
+
+/// Escape text for use in SVG content.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
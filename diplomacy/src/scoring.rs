@@ -0,0 +1,421 @@
+//! Detecting the end of a game and scoring the result.
+//!
+//! A game ends when one nation owns enough supply centers for a solo victory, or when it reaches
+//! a configured year limit. Games can also end early when the surviving players agree to a draw;
+//! this crate doesn't track proposals, but `Standings` can score a draw at any point.
+
+use crate::geo::{Map, RegionKey};
+use crate::{Nation, SupplyCenterOwnership, Time, UnitPositions};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The conditions under which a game ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EndConditions {
+    /// The number of supply centers a nation needs to own to win the game alone.
+    pub victory_centers: usize,
+    /// The last year of the game. If no nation has won by the end of this year, the game ends
+    /// in a draw among the surviving nations.
+    pub last_year: Option<usize>,
+}
+
+impl EndConditions {
+    /// The usual conditions for a map: a nation wins by owning more than half of its supply
+    /// centers, and there is no year limit. On the standard map, this is 18 of 34.
+    pub fn for_map(map: &Map) -> Self {
+        let supply_centers = map.provinces().filter(|p| p.is_supply_center()).count();
+        Self {
+            victory_centers: supply_centers / 2 + 1,
+            last_year: None,
+        }
+    }
+
+    /// Set the last year of the game.
+    pub fn with_last_year(self, last_year: usize) -> Self {
+        Self {
+            last_year: Some(last_year),
+            ..self
+        }
+    }
+}
+
+/// The reason a game ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameEnd {
+    /// The nation owns enough supply centers to win alone.
+    Solo(Nation),
+    /// The game passed its last year, and ends in a draw among the surviving nations.
+    YearLimit,
+}
+
+/// The number of supply centers and units each nation has at a point in the game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Standings {
+    centers: BTreeMap<Nation, usize>,
+    units: BTreeMap<Nation, usize>,
+}
+
+impl Standings {
+    /// Count the supply centers and units of every nation in `nations`. Nations which own
+    /// centers or units but aren't in `nations` are counted as well.
+    pub fn new<'a>(
+        nations: impl IntoIterator<Item = &'a Nation>,
+        ownership: &SupplyCenterOwnership,
+        units: &impl UnitPositions<RegionKey>,
+    ) -> Self {
+        let mut centers = nations
+            .into_iter()
+            .map(|nation| (nation.clone(), 0))
+            .collect::<BTreeMap<_, _>>();
+        let mut unit_counts = centers.clone();
+
+        for (_, nation) in ownership.iter() {
+            *centers.entry(nation.clone()).or_insert(0) += 1;
+        }
+
+        for unit in units.unit_positions() {
+            *unit_counts.entry(unit.nation().clone()).or_insert(0) += 1;
+            centers.entry(unit.nation().clone()).or_insert(0);
+        }
+
+        for nation in centers.keys() {
+            unit_counts.entry(nation.clone()).or_insert(0);
+        }
+
+        Self {
+            centers,
+            units: unit_counts,
+        }
+    }
+
+    /// Iterate over the nations in the game, including eliminated nations.
+    pub fn nations(&self) -> impl Iterator<Item = &Nation> {
+        self.centers.keys()
+    }
+
+    /// The number of supply centers owned by `nation`.
+    pub fn centers(&self, nation: &Nation) -> usize {
+        self.centers.get(nation).copied().unwrap_or(0)
+    }
+
+    /// The number of units owned by `nation`.
+    pub fn units(&self, nation: &Nation) -> usize {
+        self.units.get(nation).copied().unwrap_or(0)
+    }
+
+    /// Check if `nation` has been eliminated, meaning it has no units and no supply centers.
+    pub fn is_eliminated(&self, nation: &Nation) -> bool {
+        self.centers(nation) == 0 && self.units(nation) == 0
+    }
+
+    /// The nations which have no units and no supply centers.
+    pub fn eliminated(&self) -> BTreeSet<&Nation> {
+        self.nations().filter(|n| self.is_eliminated(n)).collect()
+    }
+
+    /// The nations which have not been eliminated.
+    pub fn survivors(&self) -> BTreeSet<&Nation> {
+        self.nations().filter(|n| !self.is_eliminated(n)).collect()
+    }
+
+    /// The nation that owns enough supply centers to win alone, if any.
+    pub fn solo_winner(&self, conditions: &EndConditions) -> Option<&Nation> {
+        self.centers
+            .iter()
+            .find(|(_, &centers)| centers >= conditions.victory_centers)
+            .map(|(nation, _)| nation)
+    }
+
+    /// Check if the game is over at `time`. A solo victory takes precedence over the year
+    /// limit, which is reached once `time` is after the last year of the game.
+    pub fn game_end(&self, conditions: &EndConditions, time: &Time) -> Option<GameEnd> {
+        if let Some(winner) = self.solo_winner(conditions) {
+            Some(GameEnd::Solo(winner.clone()))
+        } else if conditions.last_year.is_some_and(|last| time.year() > last) {
+            Some(GameEnd::YearLimit)
+        } else {
+            None
+        }
+    }
+
+    /// Score the game as it stands. If a nation has won alone it takes the maximum score and
+    /// every other nation scores zero; otherwise the game is scored as a draw among the
+    /// surviving nations. Every nation in the game has a score, including eliminated nations.
+    pub fn score(
+        &self,
+        system: ScoringSystem,
+        conditions: &EndConditions,
+    ) -> BTreeMap<Nation, f64> {
+        let mut scores = self
+            .nations()
+            .map(|nation| (nation.clone(), 0.0))
+            .collect::<BTreeMap<_, _>>();
+
+        if let Some(winner) = self.solo_winner(conditions) {
+            scores.insert(winner.clone(), system.solo_score(self));
+            return scores;
+        }
+
+        let survivors = self.survivors();
+        match system {
+            ScoringSystem::DrawSize => {
+                for nation in &survivors {
+                    scores.insert((*nation).clone(), 100.0 / survivors.len() as f64);
+                }
+            }
+            ScoringSystem::SumOfSquares => {
+                let squares = self.centers.values().map(|&c| (c * c) as f64).sum::<f64>();
+                for nation in &survivors {
+                    let centers = self.centers(nation) as f64;
+                    // Survivors which only have units left share the points equally.
+                    let score = if squares == 0.0 {
+                        100.0 / survivors.len() as f64
+                    } else {
+                        100.0 * centers * centers / squares
+                    };
+                    scores.insert((*nation).clone(), score);
+                }
+            }
+            ScoringSystem::Dixie => {
+                let pot = self.total_centers() as f64 / survivors.len() as f64;
+                for nation in &survivors {
+                    scores.insert((*nation).clone(), self.centers(nation) as f64 + pot);
+                }
+            }
+            ScoringSystem::CDiplo => {
+                for (nation, score) in &mut scores {
+                    *score = 1.0 + self.centers(nation) as f64;
+                }
+
+                for (nation, bonus) in self.c_diplo_rank_bonuses() {
+                    *scores.get_mut(nation).unwrap() += bonus;
+                }
+            }
+        }
+
+        scores
+    }
+
+    fn total_centers(&self) -> usize {
+        self.centers.values().sum()
+    }
+
+    /// Split the C-Diplo bonuses for the three largest nations by supply centers. Nations tied
+    /// for a rank share the bonuses of the ranks they occupy.
+    fn c_diplo_rank_bonuses(&self) -> Vec<(&Nation, f64)> {
+        const BONUSES: [f64; 3] = [38.0, 14.0, 7.0];
+
+        let mut ranked = self.centers.iter().collect::<Vec<_>>();
+        ranked.sort_by(|a, b| b.1.cmp(a.1));
+
+        let mut bonuses = vec![];
+        let mut rank = 0;
+        while rank < BONUSES.len() && rank < ranked.len() {
+            let centers = ranked[rank].1;
+            let tied = ranked[rank..]
+                .iter()
+                .take_while(|(_, c)| *c == centers)
+                .count();
+            let pool = BONUSES.iter().skip(rank).take(tied).sum::<f64>();
+            for (nation, _) in &ranked[rank..rank + tied] {
+                bonuses.push((*nation, pool / tied as f64));
+            }
+
+            rank += tied;
+        }
+
+        bonuses
+    }
+}
+
+/// A system for scoring finished games.
+///
+/// Every system gives a solo winner the maximum score and the other nations nothing. They differ
+/// in how they score draws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScoringSystem {
+    /// Draw-size scoring: 100 points are split equally among the surviving nations, regardless
+    /// of how many supply centers each owns.
+    DrawSize,
+    /// Sum-of-Squares: 100 points are split among the surviving nations in proportion to the
+    /// square of the number of supply centers each owns. If no surviving nation owns a supply
+    /// center, the points are split equally.
+    SumOfSquares,
+    /// Dixie: each surviving nation scores one point per supply center it owns, and the
+    /// surviving nations split a pot equal to the number of owned supply centers. A solo winner
+    /// scores twice the number of owned supply centers.
+    Dixie,
+    /// C-Diplo: every nation scores one point for playing and one point per supply center, and
+    /// the three nations with the most supply centers score bonuses of 38, 14 and 7 points.
+    /// Tied nations split the bonuses of the ranks they share. A solo winner scores 100 points.
+    CDiplo,
+}
+
+impl ScoringSystem {
+    fn solo_score(self, standings: &Standings) -> f64 {
+        match self {
+            ScoringSystem::Dixie => 2.0 * standings.total_centers() as f64,
+            _ => 100.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geo::standard_map;
+    use crate::{Phase, Season, UnitPosition};
+
+    fn nation(name: &str) -> Nation {
+        Nation::from(name)
+    }
+
+    /// Build standings where each nation owns the given number of supply centers and no units.
+    fn standings(counts: &[(&str, usize)]) -> Standings {
+        let mut provinces = standard_map()
+            .provinces()
+            .filter(|p| p.is_supply_center())
+            .map(|p| p.into());
+        let ownership = counts
+            .iter()
+            .flat_map(|&(name, count)| {
+                provinces
+                    .by_ref()
+                    .take(count)
+                    .map(move |province| (province, nation(name)))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<SupplyCenterOwnership>();
+
+        Standings::new(
+            counts
+                .iter()
+                .map(|(name, _)| nation(name))
+                .collect::<Vec<_>>()
+                .iter(),
+            &ownership,
+            &Vec::<UnitPosition<'static, RegionKey>>::new(),
+        )
+    }
+
+    #[test]
+    fn victory_centers_for_standard_map() {
+        assert_eq!(18, EndConditions::for_map(standard_map()).victory_centers);
+    }
+
+    #[test]
+    fn solo_victory_and_year_limit() {
+        let conditions = EndConditions::for_map(standard_map()).with_last_year(1910);
+        let spring_1911 = Time::new(Season::Spring, 1911, Phase::Main);
+        let fall_1910 = Time::new(Season::Fall, 1910, Phase::Main);
+
+        let solo = standings(&[("FRA", 18), ("GER", 16)]);
+        assert_eq!(
+            Some(GameEnd::Solo(nation("FRA"))),
+            solo.game_end(&conditions, &fall_1910)
+        );
+
+        let draw = standings(&[("FRA", 17), ("GER", 17), ("ITA", 0)]);
+        assert_eq!(None, draw.game_end(&conditions, &fall_1910));
+        assert_eq!(
+            Some(GameEnd::YearLimit),
+            draw.game_end(&conditions, &spring_1911)
+        );
+        assert_eq!(
+            vec![&nation("ITA")],
+            draw.eliminated().into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn draw_scores() {
+        let conditions = EndConditions::for_map(standard_map());
+        let standings = standings(&[("ENG", 16), ("FRA", 12), ("GER", 6), ("ITA", 0)]);
+
+        let dss = standings.score(ScoringSystem::DrawSize, &conditions);
+        assert_eq!(100.0 / 3.0, dss[&nation("ENG")]);
+        assert_eq!(0.0, dss[&nation("ITA")]);
+
+        let sos = standings.score(ScoringSystem::SumOfSquares, &conditions);
+        assert_eq!(100.0 * 256.0 / 436.0, sos[&nation("ENG")]);
+        assert_eq!(100.0 * 36.0 / 436.0, sos[&nation("GER")]);
+
+        let dixie = standings.score(ScoringSystem::Dixie, &conditions);
+        assert_eq!(16.0 + 34.0 / 3.0, dixie[&nation("ENG")]);
+
+        let c_diplo = standings.score(ScoringSystem::CDiplo, &conditions);
+        assert_eq!(1.0 + 16.0 + 38.0, c_diplo[&nation("ENG")]);
+        assert_eq!(1.0 + 12.0 + 14.0, c_diplo[&nation("FRA")]);
+        assert_eq!(1.0 + 6.0 + 7.0, c_diplo[&nation("GER")]);
+        assert_eq!(1.0, c_diplo[&nation("ITA")]);
+    }
+
+    #[test]
+    fn sum_of_squares_without_centers() {
+        let conditions = EndConditions::for_map(standard_map());
+        let units = ["FRA: A par", "GER: A mun"]
+            .iter()
+            .map(|unit| unit.parse().unwrap())
+            .collect::<Vec<UnitPosition<'static, RegionKey>>>();
+        let standings = Standings::new(
+            &[nation("FRA"), nation("GER"), nation("ITA")],
+            &vec![].into_iter().collect(),
+            &units,
+        );
+
+        let scores = standings.score(ScoringSystem::SumOfSquares, &conditions);
+        assert_eq!(50.0, scores[&nation("FRA")]);
+        assert_eq!(50.0, scores[&nation("GER")]);
+        assert_eq!(0.0, scores[&nation("ITA")]);
+    }
+
+    /// A three-way draw with every supply center owned: each survivor scores its centers plus a
+    /// third of the 34-point pot, so together the survivors score the 68 points of a solo.
+    #[test]
+    fn dixie_three_way_draw() {
+        let conditions = EndConditions::for_map(standard_map());
+        let standings = standings(&[("ENG", 15), ("FRA", 12), ("GER", 7), ("ITA", 0)]);
+        let scores = standings.score(ScoringSystem::Dixie, &conditions);
+
+        assert_eq!(15.0 + 34.0 / 3.0, scores[&nation("ENG")]);
+        assert_eq!(12.0 + 34.0 / 3.0, scores[&nation("FRA")]);
+        assert_eq!(7.0 + 34.0 / 3.0, scores[&nation("GER")]);
+        assert_eq!(0.0, scores[&nation("ITA")]);
+        assert!((68.0 - scores.values().sum::<f64>()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn c_diplo_splits_tied_ranks() {
+        let conditions = EndConditions::for_map(standard_map());
+        let standings = standings(&[("ENG", 10), ("FRA", 10), ("GER", 4)]);
+        let scores = standings.score(ScoringSystem::CDiplo, &conditions);
+
+        assert_eq!(1.0 + 10.0 + 26.0, scores[&nation("ENG")]);
+        assert_eq!(1.0 + 10.0 + 26.0, scores[&nation("FRA")]);
+        assert_eq!(1.0 + 4.0 + 7.0, scores[&nation("GER")]);
+    }
+
+    #[test]
+    fn solo_scores() {
+        let conditions = EndConditions::for_map(standard_map());
+        let standings = standings(&[("RUS", 18), ("TUR", 16)]);
+
+        for system in [
+            ScoringSystem::DrawSize,
+            ScoringSystem::SumOfSquares,
+            ScoringSystem::CDiplo,
+        ] {
+            let scores = standings.score(system, &conditions);
+            assert_eq!(100.0, scores[&nation("RUS")]);
+            assert_eq!(0.0, scores[&nation("TUR")]);
+        }
+
+        assert_eq!(
+            68.0,
+            standings.score(ScoringSystem::Dixie, &conditions)[&nation("RUS")]
+        );
+    }
+}
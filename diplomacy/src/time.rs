@@ -6,6 +6,7 @@ use std::str::FromStr;
 
 /// The step in a current season. Not all seasons will have all steps.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Phase {
     Main,
     Retreat,
@@ -37,6 +38,7 @@ impl FromStr for Phase {
 
 /// The current season in the year. Not all game variants use all seasons.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Season {
     Spring,
     Summer,
@@ -183,7 +185,7 @@ mod test {
 
     #[test]
     fn cmp() {
-        let turns = vec!["S1901M", "S1901R", "F1901M", "F1901R", "F1901B", "W1901B"];
+        let turns = ["S1901M", "S1901R", "F1901M", "F1901R", "F1901B", "W1901B"];
 
         let parsed = turns
             .iter()
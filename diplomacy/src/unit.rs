@@ -1,5 +1,5 @@
 use crate::parser::{Error, ErrorKind};
-use crate::{geo::Location, geo::RegionKey, Command, Nation, Order, ShortName};
+use crate::{geo::Location, geo::RegionKey, geo::Terrain, Command, Nation, Order, ShortName};
 use std::borrow::{Borrow, Cow};
 use std::collections::HashMap;
 use std::hash::{BuildHasher, Hash};
@@ -19,6 +19,17 @@ pub enum UnitType {
     Fleet,
 }
 
+impl UnitType {
+    /// Returns true if a unit of this type can be in, or move across, terrain of type `terrain`.
+    pub fn can_occupy(self, terrain: Terrain) -> bool {
+        match terrain {
+            Terrain::Coast => true,
+            Terrain::Land => self == UnitType::Army,
+            Terrain::Sea => self == UnitType::Fleet,
+        }
+    }
+}
+
 impl FromStr for UnitType {
     type Err = Error;
 
@@ -66,6 +77,14 @@ impl<'a> Unit<'a> {
     pub fn unit_type(&self) -> UnitType {
         self.unit_type
     }
+
+    /// Create a copy of the unit that owns its nation.
+    pub fn into_owned(self) -> Unit<'static> {
+        Unit {
+            nation: Cow::Owned(self.nation.into_owned()),
+            unit_type: self.unit_type,
+        }
+    }
 }
 
 /// A unit's instantaneous position in a region.
@@ -96,6 +115,16 @@ impl<'a, L> UnitPosition<'a, L> {
     }
 }
 
+impl<L: Clone> UnitPosition<'_, &L> {
+    /// Create a copy of the unit position that owns its nation and region.
+    pub fn into_owned(self) -> UnitPosition<'static, L> {
+        UnitPosition {
+            unit: self.unit.into_owned(),
+            region: self.region.clone(),
+        }
+    }
+}
+
 impl<'a> FromStr for UnitPosition<'a, RegionKey> {
     type Err = Error;
 
@@ -223,7 +252,7 @@ where
     }
 
     fn find_province_occupier(&self, province: &L::Province) -> Option<UnitPosition<'_, &L>> {
-        self.get(&province).cloned()
+        self.get(province).cloned()
     }
 
     fn find_region_occupier(&self, region: &L) -> Option<Unit<'_>> {
@@ -0,0 +1,136 @@
+//! Build-phase cases not covered by the DATC.
+
+#![cfg(test)]
+
+#[path = "./util.rs"]
+mod util;
+
+use diplomacy::judge::build::OrderOutcome::*;
+use util::*;
+
+#[test]
+fn only_one_build_across_coasts() {
+    let results = get_build_results(
+        vec![
+            ("mos", "RUS"),
+            ("sev", "RUS"),
+            ("stp", "RUS"),
+            ("war", "RUS"),
+        ],
+        vec!["RUS: A ukr"],
+        vec!["RUS: F stp(sc) build", "RUS: A stp build"],
+    );
+
+    assert_eq!(Succeeds, results.orders[&build_ord("RUS: F stp(sc) build")]);
+    assert_eq!(
+        MultipleBuildsInProvince,
+        results.orders[&build_ord("RUS: A stp build")]
+    );
+}
+
+#[test]
+fn invalid_build_does_not_block_later_build() {
+    let results = get_build_results(
+        vec![
+            ("mos", "RUS"),
+            ("sev", "RUS"),
+            ("stp", "RUS"),
+            ("war", "RUS"),
+        ],
+        vec!["RUS: A ukr"],
+        vec!["RUS: F stp build", "RUS: A stp build"],
+    );
+
+    assert_eq!(
+        UnspecifiedCoast,
+        results.orders[&build_ord("RUS: F stp build")]
+    );
+    assert_eq!(Succeeds, results.orders[&build_ord("RUS: A stp build")]);
+}
+
+#[test]
+fn disband_fleet_without_naming_coast() {
+    let results = get_build_results(
+        vec![("stp", "RUS")],
+        vec!["RUS: F stp(sc)", "RUS: A ukr"],
+        vec!["RUS: F stp disband", "RUS: F stp(sc) disband"],
+    );
+
+    assert_eq!(Succeeds, results.orders[&build_ord("RUS: F stp disband")]);
+    assert_eq!(
+        MultipleDisbandsForUnit,
+        results.orders[&build_ord("RUS: F stp(sc) disband")]
+    );
+    assert!(results.civil_disorder.is_empty());
+}
+
+#[test]
+fn disband_requires_matching_unit_type() {
+    let results = get_build_results(
+        vec![("mos", "RUS")],
+        vec!["RUS: A mos", "RUS: A ukr"],
+        vec!["RUS: F mos disband"],
+    );
+
+    assert_eq!(
+        DisbandingNonexistentUnit,
+        results.orders[&build_ord("RUS: F mos disband")]
+    );
+}
+
+/// Each nation in Chaos has a single home supply center, and starts the game by building in it.
+#[test]
+fn chaos_initial_builds() {
+    use diplomacy::geo::variant;
+    use diplomacy::judge::build::{to_initial_ownerships, ResolverContext};
+
+    let map = variant::chaos().map();
+    let ownerships = to_initial_ownerships(map);
+    let world = BuildWorld::new(vec![]);
+    let orders = vec![
+        "PAR: A par build",
+        "BRE: F bre build",
+        "MUN: F mun build",
+        "PAR: A bur build",
+    ]
+    .into_iter()
+    .map(build_ord)
+    .collect::<Vec<_>>();
+
+    let context = ResolverContext::new(map, &ownerships, &world, orders.iter().collect());
+    let outcome = context.resolve();
+
+    assert_eq!(Succeeds, outcome.orders[&orders[0]]);
+    assert_eq!(Succeeds, outcome.orders[&orders[1]]);
+    assert_eq!(InvalidTerrain, outcome.orders[&orders[2]]);
+    assert_eq!(InvalidProvince, outcome.orders[&orders[3]]);
+}
+
+/// A nation that controls no supply centers must disband all of its units.
+#[test]
+fn nation_without_centers_disbands_everything() {
+    let civil_disorder = get_civil_disorder(
+        vec![("ber", "GER"), ("mun", "GER")],
+        vec!["GER: A ber", "GER: A mun", "RUS: A ukr", "RUS: F bla"],
+    );
+
+    let removed = civil_disorder
+        .iter()
+        .map(|(_, region)| region.to_string())
+        .collect::<std::collections::BTreeSet<_>>();
+
+    assert_eq!(vec!["bla", "ukr"], removed.into_iter().collect::<Vec<_>>());
+}
+
+/// Units at the same distance are removed in alphabetical order of their provinces' full
+/// names, so the fleet in Finland goes before the fleet in the Gulf of Bothnia even though
+/// `bot` sorts before `fin`.
+#[test]
+fn civil_disorder_orders_provinces_by_full_name() {
+    use diplomacy::UnitType::*;
+
+    assert_eq!(
+        get_civil_disorder(vec![("stp", "RUS")], vec!["RUS: F bot", "RUS: F fin"]),
+        vec![(Fleet, reg("fin"))].into_iter().collect()
+    );
+}
@@ -6,9 +6,24 @@ mod util;
 
 use diplomacy::geo;
 use diplomacy::judge::OrderState::{Fails, Succeeds};
-use diplomacy::judge::{retreat::DestStatus, InvalidOrder, OrderOutcome, Rulebook};
+use diplomacy::judge::{
+    retreat::DestStatus, InvalidOrder, MappedMainOrder, OrderOutcome, OrderState, RewriteReason,
+    Rulebook, Submission,
+};
+use diplomacy::Nation;
+use std::collections::HashMap;
 use util::*;
 
+/// Adjudicate using the standard rules, except that impossible orders are treated as illegal.
+fn sanity_checked(orders: Vec<&str>) -> HashMap<MappedMainOrder, OrderState> {
+    let rules = Rulebook {
+        illegal_orders_hold: true,
+        ..Rulebook::default()
+    };
+
+    get_results_with_rules(rules, orders)
+}
+
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.A.1
 #[test]
 fn t6a01_move_to_non_neighbor_fails() {
@@ -59,7 +74,7 @@ fn t6a06_ordering_a_unit_of_another_country() {
         )],
         vec![order.clone()],
     );
-    let outcome = submission.adjudicate(geo::standard_map(), Rulebook);
+    let outcome = submission.adjudicate(geo::standard_map(), Rulebook::default());
     assert_eq!(
         outcome.get(&order),
         Some(&OrderOutcome::Invalid(InvalidOrder::ForeignUnit))
@@ -129,7 +144,7 @@ fn t6b01_moving_without_required_coast_fails() {
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.B.2
 #[test]
 fn t6b02_moving_with_unspecified_coast_when_coast_is_not_necessary() {
-    judge! { "FRA: F gas -> spa": Fails };
+    judge! { "FRA: F gas -> spa": Succeeds };
 }
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.B.3
@@ -172,59 +187,75 @@ fn t6b06_support_can_be_cut_with_other_coast() {
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.B.7
 #[test]
-#[ignore]
 fn t6b07_supporting_with_unspecified_coast() {
     judge! {
        "FRA: F por Supports F mao -> spa",
-       "FRA: F mao -> spa(nc)",
+       "FRA: F mao -> spa(nc)": Fails,
        "ITA: F lyo Supports F wes -> spa(sc)",
-       "ITA: F wes -> spa(sc)",
+       "ITA: F wes -> spa(sc)": Fails,
     };
 }
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.B.8
 #[test]
-#[ignore]
 fn t6b08_supporting_with_unspecified_coast_when_only_one_coast_is_possible() {
     judge! {
        "FRA: F por Supports F gas -> spa",
-       "FRA: F gas -> spa(nc)",
+       "FRA: F gas -> spa(nc)": Fails,
        "ITA: F lyo Supports F wes -> spa(sc)",
-       "ITA: F wes -> spa(sc)",
+       "ITA: F wes -> spa(sc)": Fails,
     };
 }
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.B.9
 #[test]
-#[ignore]
 fn t6b09_supporting_with_wrong_coast() {
     judge! {
        "FRA: F por Supports F mao -> spa(nc)",
-       "FRA: F mao -> spa(sc)",
+       "FRA: F mao -> spa(sc)": Fails,
        "ITA: F lyo Supports F wes -> spa(sc)",
-       "ITA: F wes -> spa(sc)",
+       "ITA: F wes -> spa(sc)": Succeeds,
     };
 }
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.B.10
 #[test]
-#[ignore]
 fn t6b10_unit_ordered_with_wrong_coast() {
-    judge! { "FRA: F spa(nc) -> lyo" };
+    let submission = Submission::new(
+        &vec![ord("FRA: F spa(sc) Holds")],
+        vec![ord("FRA: F spa(nc) -> lyo")],
+    );
+    let outcome = submission.adjudicate(geo::standard_map(), Rulebook::default());
+    let submitted = ord("FRA: F spa(nc) -> lyo");
+    let result = outcome.get(&submitted).unwrap();
+    assert_eq!(Succeeds, result.into());
+    match result {
+        OrderOutcome::Rewritten(rewrite) => {
+            assert_eq!(rewrite.order, &ord("FRA: F spa(sc) -> lyo"));
+            assert_eq!(rewrite.reason, RewriteReason::CoastIgnored);
+        }
+        other => panic!("expected a rewritten order, got {:?}", other),
+    }
 }
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.B.11
 #[test]
-#[ignore]
 fn t6b11_coast_can_not_be_ordered_to_change() {
-    judge! { "FRA: F spa(sc) -> lyo" };
+    let submission = Submission::new(
+        &vec![ord("FRA: F spa(nc) Holds")],
+        vec![ord("FRA: F spa(sc) -> lyo")],
+    );
+    let outcome = submission.adjudicate(geo::standard_map(), Rulebook::default());
+    assert_eq!(
+        Fails,
+        outcome.get(&ord("FRA: F spa(sc) -> lyo")).unwrap().into()
+    );
 }
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.B.12
 #[test]
-#[ignore]
 fn t6b12_army_movement_with_coastal_specification() {
-    judge! { "FRA: A gas -> spa(nc)" };
+    judge! { "FRA: A gas -> spa(nc)": Succeeds };
 }
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.B.13
@@ -238,9 +269,24 @@ fn t6b13_coastal_crawl_not_allowed() {
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.B.14
 #[test]
-#[ignore]
 fn t6b14_building_with_unspecified_coast() {
-    judge! { "RUS: Build F St Petersburg" };
+    use diplomacy::judge::build::OrderOutcome::*;
+
+    let results = get_build_results(
+        vec![
+            ("mos", "RUS"),
+            ("sev", "RUS"),
+            ("stp", "RUS"),
+            ("war", "RUS"),
+        ],
+        vec!["RUS: A ukr"],
+        vec!["RUS: F stp build"],
+    );
+
+    assert_eq!(
+        UnspecifiedCoast,
+        results.orders[&build_ord("RUS: F stp build")]
+    );
 }
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.C.1
@@ -612,68 +658,73 @@ fn t6d27_failing_convoy_can_be_supported() {
     };
 }
 
+/// The DATC prefers that impossible orders are treated as illegal, which is an option in this
+/// adjudicator rather than the default.
+///
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.D.28
 #[test]
-#[ignore]
 fn t6d28_impossible_move_and_support() {
     judge! {
-       "AUS: A bud Supports F rum",
-       "RUS: F rum -> hol",
-       "TUR: F bla -> rum",
-       "TUR: A bul Supports F bla -> rum",
+       @using sanity_checked =>
+       "AUS: A bud Supports F rum": Succeeds,
+       "RUS: F rum -> hol": Fails,
+       "TUR: F bla -> rum": Fails,
+       "TUR: A bul Supports F bla -> rum"
     };
 }
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.D.29
 #[test]
-#[ignore]
 fn t6d29_move_to_impossible_coast_and_support() {
     judge! {
-       "AUS: A bud Supports F rum",
-       "RUS: F rum -> bul(sc)",
-       "TUR: F bla -> rum",
-       "TUR: A bul Supports F bla -> rum",
+       @using sanity_checked =>
+       "AUS: A bud Supports F rum": Succeeds,
+       "RUS: F rum -> bul(sc)": Fails,
+       "TUR: F bla -> rum": Fails,
+       "TUR: A bul Supports F bla -> rum"
     };
 }
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.D.30
 #[test]
-#[ignore]
 fn t6d30_move_without_coast_and_support() {
     judge! {
        "ITA: F aeg Supports F con",
-       "RUS: F con -> bul",
-       "TUR: F bla -> con",
+       "RUS: F con -> bul": Fails,
+       "TUR: F bla -> con": Fails,
        "TUR: A bul Supports F bla -> con",
     };
 }
 
 /// In this case the proposed behavior is that the fleet order should be treated as illegal and
 /// dropped entirely. It's not clear why that would be the case in computerized games, so this
-/// test will remain ignored.
+/// is only done when the rules treat impossible orders as illegal.
 ///
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.D.31
 #[test]
-#[ignore]
 fn t6d31_a_tricky_impossible_support() {
-    judge! {
-       "AUS: A rum -> arm",
-       "TUR: F bla Supports A rum -> arm",
+    let results = judge! {
+       @using sanity_checked =>
+       "AUS: A rum -> arm": Fails,
+       "TUR: F bla Supports A rum -> arm": Fails
     };
+
+    assert_eq!(Some(&Succeeds), results.get(&ord("TUR: F bla Hold")));
 }
 
 /// In this case it is proposed that the army order be treated as illegal and dropped entirely.
-/// It's not clear why that should be the case, so this will remain ignored for now.
+/// It's not clear why that should be the case, so this is only done when the rules treat
+/// impossible orders as illegal.
 ///
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.D.32
 #[test]
-#[ignore]
 fn t6d32_a_missing_fleet() {
     judge! {
+       @using sanity_checked =>
        "ENG: F edi Supports A lvp -> yor",
-       "ENG: A lvp -> yor",
-       "FRA: F lon Supports A yor",
-       "GER: A yor -> hol",
+       "ENG: A lvp -> yor": Fails,
+       "FRA: F lon Supports A yor": Succeeds,
+       "GER: A yor -> hol": Fails
     };
 }
 
@@ -1219,11 +1270,10 @@ fn t6g02_kidnapping_an_army() {
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.G.3
 #[test]
-#[ignore]
 fn t6g03_kidnapping_with_a_disrupted_convoy() {
     judge! {
-       "FRA: F bre -> eng",
-       "FRA: A pic -> bel",
+       "FRA: F bre -> eng": Succeeds,
+       "FRA: A pic -> bel": Succeeds,
        "FRA: A bur Supports A pic -> bel",
        "FRA: F mao Supports F bre -> eng",
        "ENG: F eng convoys pic -> bel",
@@ -1232,15 +1282,14 @@ fn t6g03_kidnapping_with_a_disrupted_convoy() {
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.G.4
 #[test]
-#[ignore]
 fn t6g04_kidnapping_with_a_disrupted_convoy_and_opposite_move() {
     judge! {
-       "FRA: F bre -> eng",
-       "FRA: A pic -> bel",
+       "FRA: F bre -> eng": Succeeds,
+       "FRA: A pic -> bel": Succeeds,
        "FRA: A bur Supports A pic -> bel",
        "FRA: F mao Supports F bre -> eng",
-       "ENG: F eng convoys pic -> bel",
-       "ENG: A bel -> pic",
+       "ENG: F eng convoys pic -> bel": Fails,
+       "ENG: A bel -> pic": Fails,
     };
 }
 
@@ -1769,98 +1818,271 @@ fn t6h16_contested_for_both_coasts() {
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.I.2
 #[test]
-#[ignore]
 fn t6i02_fleets_can_not_be_build_in_land_areas() {
-    judge! { "RUS: Build F mos" };
+    use diplomacy::judge::build::OrderOutcome::*;
+
+    let results = get_build_results(
+        vec![
+            ("mos", "RUS"),
+            ("sev", "RUS"),
+            ("stp", "RUS"),
+            ("war", "RUS"),
+        ],
+        vec!["RUS: A ukr"],
+        vec!["RUS: F mos build"],
+    );
+
+    assert_eq!(
+        InvalidTerrain,
+        results.orders[&build_ord("RUS: F mos build")]
+    );
 }
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.I.3
 #[test]
-#[ignore]
 fn t6i03_supply_center_must_be_empty_for_building() {
-    judge! { "GER: Build A ber" };
+    use diplomacy::judge::build::OrderOutcome::*;
+
+    let results = get_build_results(
+        vec![("ber", "GER"), ("kie", "GER"), ("mun", "GER")],
+        vec!["GER: A ber"],
+        vec!["GER: A ber build"],
+    );
+
+    assert_eq!(
+        OccupiedProvince,
+        results.orders[&build_ord("GER: A ber build")]
+    );
 }
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.I.4
 #[test]
-#[ignore]
 fn t6i04_both_coasts_must_be_empty_for_building() {
-    judge! { "RUS: Build A St Petersburg(nc)" };
+    use diplomacy::judge::build::OrderOutcome::*;
+
+    let results = get_build_results(
+        vec![
+            ("mos", "RUS"),
+            ("sev", "RUS"),
+            ("stp", "RUS"),
+            ("war", "RUS"),
+        ],
+        vec!["RUS: F stp(sc)"],
+        vec!["RUS: F stp(nc) build"],
+    );
+
+    assert_eq!(
+        OccupiedProvince,
+        results.orders[&build_ord("RUS: F stp(nc) build")]
+    );
 }
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.I.5
 #[test]
-#[ignore]
 fn t6i05_building_in_home_supply_center_that_is_not_owned() {
-    judge! { "GER: Build A ber" };
+    use diplomacy::judge::build::OrderOutcome::*;
+
+    let results = get_build_results(
+        vec![("ber", "RUS"), ("kie", "GER"), ("mun", "GER")],
+        vec!["GER: A kie"],
+        vec!["GER: A ber build"],
+    );
+
+    assert_eq!(
+        ForeignControlled,
+        results.orders[&build_ord("GER: A ber build")]
+    );
 }
 
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.I.6
 #[test]
-#[ignore]
 fn t6i06_building_in_owned_supply_center_that_is_not_a_home_supply_center() {
-    judge! { "GER: Build A war" };
+    use diplomacy::judge::build::OrderOutcome::*;
+
+    let results = get_build_results(
+        vec![
+            ("ber", "GER"),
+            ("kie", "GER"),
+            ("mun", "GER"),
+            ("war", "GER"),
+        ],
+        vec!["GER: A ber"],
+        vec!["GER: A war build"],
+    );
+
+    assert_eq!(
+        InvalidProvince,
+        results.orders[&build_ord("GER: A war build")]
+    );
 }
 
+/// The second build in Moscow fails because Moscow already has a build, so only one army is
+/// built.
+///
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.I.7
 #[test]
-#[ignore]
 fn t6i07_only_one_build_in_a_home_supply_center() {
-    judge! { "RUS: Build A mos", "RUS: Build A mos" };
+    use diplomacy::judge::build::OrderOutcome::*;
+    use diplomacy::UnitType::Army;
+
+    let results = get_build_results(
+        vec![
+            ("mos", "RUS"),
+            ("sev", "RUS"),
+            ("stp", "RUS"),
+            ("war", "RUS"),
+        ],
+        vec!["RUS: A ukr"],
+        vec!["RUS: A mos build", "RUS: A mos build"],
+    );
+
+    assert_eq!(
+        vec![Succeeds, MultipleBuildsInProvince],
+        results
+            .outcomes
+            .iter()
+            .map(|(_, outcome)| *outcome)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        results.final_units[&Nation::from("RUS")],
+        vec![(Army, reg("ukr")), (Army, reg("mos"))]
+            .into_iter()
+            .collect()
+    );
 }
 
+/// France must remove two units but only orders one removal, twice. The second order fails,
+/// and the army in Burgundy is removed in civil disorder.
+///
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.J.2
 #[test]
-#[ignore]
 fn t6j02_removing_the_same_unit_twice() {
-    judge! { "FRA: Remove A par", "FRA: Remove A par" };
+    use diplomacy::judge::build::OrderOutcome::*;
+    use diplomacy::UnitType::Army;
+
+    let results = get_build_results(
+        vec![("par", "FRA")],
+        vec!["FRA: A par", "FRA: A pic", "FRA: A bur"],
+        vec!["FRA: A par disband", "FRA: A par disband"],
+    );
+
+    assert_eq!(
+        vec![Succeeds, MultipleDisbandsForUnit],
+        results
+            .outcomes
+            .iter()
+            .map(|(_, outcome)| *outcome)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        results.civil_disorder,
+        vec![(Army, reg("bur"))].into_iter().collect()
+    );
 }
 
+/// Russia controls only St Petersburg and has one unit too many. Ukraine is two moves from
+/// St Petersburg while Finland is adjacent, so the army in Ukraine is removed.
+///
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.J.3
 #[test]
-#[ignore]
 fn t6j03_civil_disorder_two_armies_with_different_distance() {
-    judge! { "" };
+    use diplomacy::UnitType::*;
+
+    assert_eq!(
+        get_civil_disorder(vec![("stp", "RUS")], vec!["RUS: A fin", "RUS: A ukr"]),
+        vec![(Army, reg("ukr"))].into_iter().collect()
+    );
 }
 
+/// Both fleets are two moves from St Petersburg, so the fleet in the North Sea is removed
+/// because it comes first alphabetically.
+///
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.J.6
 #[test]
-#[ignore]
 fn t6j06_civil_disorder_two_fleets_with_equal_distance() {
-    judge! { "" };
+    use diplomacy::UnitType::*;
+
+    assert_eq!(
+        get_civil_disorder(vec![("stp", "RUS")], vec!["RUS: F ska", "RUS: F nth"]),
+        vec![(Fleet, reg("nth"))].into_iter().collect()
+    );
 }
 
+/// All three units are two moves from St Petersburg. Fleets are removed before armies, so
+/// both fleets are removed even though Prussia comes before Skagerrak alphabetically.
+///
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.J.7
 #[test]
-#[ignore]
 fn t6j07_civil_disorder_two_fleets_and_army_with_equal_distance() {
-    judge! { "" };
+    use diplomacy::UnitType::*;
+
+    assert_eq!(
+        get_civil_disorder(
+            vec![("stp", "RUS")],
+            vec!["RUS: A pru", "RUS: F ska", "RUS: F nth"]
+        ),
+        vec![(Fleet, reg("nth")), (Fleet, reg("ska"))]
+            .into_iter()
+            .collect()
+    );
 }
 
+/// The fleet in the Gulf of Bothnia is adjacent to the south coast of St Petersburg, so the
+/// army in Prussia is farther away and is removed.
+///
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.J.8
 #[test]
-#[ignore]
 fn t6j08_civil_disorder_a_fleet_with_shorter_distance_then_the_army() {
-    judge! { "" };
+    use diplomacy::UnitType::*;
+
+    assert_eq!(
+        get_civil_disorder(vec![("stp", "RUS")], vec!["RUS: A pru", "RUS: F bot"]),
+        vec![(Army, reg("pru"))].into_iter().collect()
+    );
 }
 
+/// The fleet in the North Sea is two moves from the north coast of St Petersburg, but four
+/// from the south coast. The army in Silesia is three moves away, so it is removed.
+///
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.J.9
 #[test]
-#[ignore]
 fn t6j09_civil_disorder_must_be_counted_from_both_coasts() {
-    judge! { "" };
+    use diplomacy::UnitType::*;
+
+    assert_eq!(
+        get_civil_disorder(vec![("stp", "RUS")], vec!["RUS: A sil", "RUS: F nth"]),
+        vec![(Army, reg("sil"))].into_iter().collect()
+    );
 }
 
+/// Albania is two moves from Naples by convoy through the Ionian Sea, but four moves over
+/// land. Tyrolia is three moves away, so the army in Tyrolia is removed.
+///
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.J.10
 #[test]
-#[ignore]
 fn t6j10_civil_disorder_counting_convoying_distance() {
-    judge! { "" };
+    use diplomacy::UnitType::*;
+
+    assert_eq!(
+        get_civil_disorder(
+            vec![("nap", "ITA"), ("tun", "ITA")],
+            vec!["ITA: F ion", "ITA: A alb", "ITA: A tyr"]
+        ),
+        vec![(Army, reg("tyr"))].into_iter().collect()
+    );
 }
 
+/// As in the previous case, but without a fleet in the Ionian Sea. The distance through the
+/// sea is still counted.
+///
 /// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.J.11
 #[test]
-#[ignore]
 fn t6j11_civil_disorder_counting_distance_without_convoying_fleet() {
-    judge! { "" };
+    use diplomacy::UnitType::*;
+
+    assert_eq!(
+        get_civil_disorder(vec![("nap", "ITA")], vec!["ITA: A alb", "ITA: A tyr"]),
+        vec![(Army, reg("tyr"))].into_iter().collect()
+    );
 }
@@ -0,0 +1,56 @@
+//! Dependency graphs of main-phase resolution.
+
+#![cfg(all(test, feature = "dependency-graph"))]
+
+#[path = "./util.rs"]
+mod util;
+
+use diplomacy::geo;
+use diplomacy::judge::dependency_graph::DependencyKind;
+use diplomacy::judge::{OrderState, Rulebook, Submission};
+use util::*;
+
+/// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.F.14
+#[test]
+fn simple_convoy_paradox_is_highlighted() {
+    let submission = Submission::with_inferred_state(vec![
+        ord("ENG: F lon Supports F wal -> eng"),
+        ord("ENG: F wal -> eng"),
+        ord("FRA: A bre -> lon"),
+        ord("FRA: F eng convoys bre -> lon"),
+    ]);
+    let outcome = submission.adjudicate(geo::standard_map(), Rulebook::default());
+    let graph = outcome.dependencies();
+
+    let convoy = graph
+        .nodes
+        .iter()
+        .find(|node| node.order == ord("FRA: F eng convoys bre -> lon"))
+        .unwrap();
+    assert!(convoy.paradox);
+    assert!(convoy.in_cycle);
+    assert_eq!(OrderState::Fails, convoy.state);
+
+    assert_eq!(1, graph.cycles.len());
+    assert!(graph.edges.iter().any(|edge| edge.in_cycle
+        && edge.kind == DependencyKind::ConvoyPath
+        && edge.to == ord("FRA: F eng convoys bre -> lon")));
+
+    let dot = graph.to_string();
+    assert!(dot.starts_with("digraph G {\n"));
+    assert!(dot.contains(
+        r#""FRA: F eng convoys A bre -> lon" [label="FRA: F eng convoys A bre -> lon\nFails", color=orange, penwidth=2, style=filled, fillcolor=red]"#
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn graph_serializes_to_json() {
+    let submission =
+        Submission::with_inferred_state(vec![ord("FRA: A bur -> mun"), ord("GER: A mun -> bur")]);
+    let outcome = submission.adjudicate(geo::standard_map(), Rulebook::default());
+    let json = serde_json::to_value(outcome.dependencies()).unwrap();
+
+    assert_eq!(2, json["nodes"].as_array().unwrap().len());
+    assert_eq!("Fails", json["nodes"][0]["state"]);
+}
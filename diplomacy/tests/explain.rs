@@ -0,0 +1,107 @@
+//! Explanations of main-phase outcomes.
+
+#![cfg(test)]
+
+#[path = "./util.rs"]
+mod util;
+
+use diplomacy::geo;
+use diplomacy::judge::explain::{Reason, StrengthKind};
+use diplomacy::judge::{Rulebook, Submission};
+use util::*;
+
+#[test]
+fn bounce_lists_strengths_and_cut_supports() {
+    let submission = Submission::with_inferred_state(vec![
+        ord("FRA: A bur -> mun"),
+        ord("FRA: A ruh supports A bur -> mun"),
+        ord("FRA: A kie supports A bur -> mun"),
+        ord("GER: A tyr -> mun"),
+        ord("GER: A boh supports A tyr -> mun"),
+        ord("GER: A ber -> kie"),
+    ]);
+    let outcome = submission.adjudicate(geo::standard_map(), Rulebook::default());
+    let explanation = outcome.explain(&ord("FRA: A bur -> mun")).unwrap();
+
+    assert_eq!(
+        "FRA: A bur -> mun: bounced\n\
+         \x20 attack strength 2 (supported by A ruh)\n\
+         \x20   support from A kie was cut by GER: A ber -> kie\n\
+         \x20 prevent strength 2 from GER: A tyr -> mun (supported by A boh)\n",
+        explanation.to_string()
+    );
+
+    match &explanation.reasons[1] {
+        Reason::Strength(report) => {
+            assert_eq!(StrengthKind::Prevent, report.kind);
+            assert_eq!(ord("GER: A tyr -> mun"), report.order);
+            assert_eq!(
+                vec![ord("GER: A boh supports A tyr -> mun")],
+                report.supports
+            );
+        }
+        reason => panic!("Expected prevent strength, got {:?}", reason),
+    }
+
+    assert_eq!(6, outcome.explanations().len());
+}
+
+#[test]
+fn dislodged_unit_explains_dislodger() {
+    let submission = Submission::with_inferred_state(vec![
+        ord("AUS: A ser -> bul"),
+        ord("AUS: A rum supports A ser -> bul"),
+        ord("TUR: A bul Hold"),
+        ord("TUR: A gre supports A bul"),
+        ord("ITA: A alb -> gre"),
+    ]);
+    let outcome = submission.adjudicate(geo::standard_map(), Rulebook::default());
+    let explanation = outcome.explain(&ord("TUR: A bul Hold")).unwrap();
+
+    assert_eq!(
+        "TUR: A bul holds: dislodged\n\
+         \x20 dislodged by\n\
+         \x20   AUS: A ser -> bul: succeeds\n\
+         \x20     attack strength 2 (supported by A rum)\n\
+         \x20     hold strength 1 from TUR: A bul holds\n\
+         \x20       support from A gre was cut by ITA: A alb -> gre\n",
+        explanation.to_string()
+    );
+}
+
+#[test]
+fn head_to_head_strengths() {
+    let submission = Submission::with_inferred_state(vec![
+        ord("FRA: A bur -> mun"),
+        ord("FRA: A ruh supports A bur -> mun"),
+        ord("GER: A kie supports A bur -> mun"),
+        ord("GER: A mun -> bur"),
+    ]);
+    let outcome = submission.adjudicate(geo::standard_map(), Rulebook::default());
+
+    let french = outcome.strengths(&ord("FRA: A bur -> mun")).unwrap();
+    let attack = french.attack.unwrap();
+    assert_eq!(2, attack.strength);
+    assert_eq!(
+        vec![ord("FRA: A ruh supports A bur -> mun")],
+        attack.supports
+    );
+    assert_eq!(3, french.defend.unwrap().strength);
+    assert_eq!(3, french.prevent.unwrap().strength);
+    assert_eq!(0, french.hold.strength);
+
+    let german = outcome.strengths(&ord("GER: A mun -> bur")).unwrap();
+    assert_eq!(1, german.attack.unwrap().strength);
+    assert_eq!(1, german.defend.unwrap().strength);
+    assert_eq!(0, german.prevent.unwrap().strength);
+    assert_eq!(1, german.hold.strength);
+
+    let support = outcome
+        .strengths(&ord("FRA: A ruh supports A bur -> mun"))
+        .unwrap();
+    assert_eq!(1, support.hold.strength);
+    assert_eq!(None, support.attack);
+
+    assert_eq!(4, outcome.all_strengths().len());
+    assert!(outcome.strengths(&ord("ITA: A ven Hold")).is_none());
+}
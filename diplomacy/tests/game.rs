@@ -0,0 +1,167 @@
+//! Playing games across multiple phases.
+
+#![cfg(test)]
+
+#[path = "./util.rs"]
+mod util;
+
+use diplomacy::game::{Error, Orders};
+use diplomacy::geo::{variant, RegionKey};
+use diplomacy::judge::Rulebook;
+use diplomacy::{Game, Nation, Phase, ShortName, UnitPosition};
+use util::*;
+
+fn orders<T>(parse: fn(&str) -> T, orders: &[&str]) -> Vec<T> {
+    orders.iter().map(|o| parse(o)).collect()
+}
+
+fn game_with_units(units: &[&str]) -> Game<'static> {
+    let variant = variant::standard();
+    Game::new(
+        variant.map(),
+        variant.calendar(),
+        units.iter().map(|u| u.parse().unwrap()).collect(),
+        Rulebook::default(),
+    )
+}
+
+fn has_unit(game: &Game, unit: &str) -> bool {
+    let unit = unit.parse::<UnitPosition<'_, RegionKey>>().unwrap();
+    game.units().contains(&unit)
+}
+
+#[test]
+fn skips_phases_without_orders() {
+    let game = Game::from_variant(variant::standard());
+    assert_eq!("S1901M", game.time().short_name());
+
+    let game = game
+        .advance(orders(ord, &["TUR: A con -> bul", "RUS: A war -> gal"]))
+        .unwrap();
+
+    assert_eq!("F1901M", game.time().short_name());
+    assert!(has_unit(&game, "TUR: A bul"));
+    assert!(has_unit(&game, "RUS: A gal"));
+    assert_eq!(22, game.units().len());
+
+    // Only Turkey gains a supply center, and its capital is vacant to build in.
+    let game = game.advance(Orders::Main(vec![])).unwrap();
+
+    assert_eq!("W1901B", game.time().short_name());
+    assert_eq!(
+        Some(&Nation::from("TUR")),
+        game.ownerships().owner(&prov("bul"))
+    );
+
+    let game = game
+        .advance(orders(build_ord, &["TUR: F con build"]))
+        .unwrap();
+
+    assert_eq!("S1902M", game.time().short_name());
+    assert!(has_unit(&game, "TUR: F con"));
+    assert_eq!(23, game.units().len());
+}
+
+#[test]
+fn stops_for_retreats() {
+    let game = game_with_units(&["AUS: A ser", "AUS: A rum", "TUR: A bul"]);
+
+    let game = game
+        .advance(orders(
+            ord,
+            &["AUS: A ser -> bul", "AUS: A rum supports A ser -> bul"],
+        ))
+        .unwrap();
+
+    assert_eq!(Phase::Retreat, game.time().phase());
+    assert!(!has_unit(&game, "TUR: A bul"));
+
+    let destinations = game.retreat_destinations();
+    let bul = "TUR: A bul".parse::<UnitPosition<'_, RegionKey>>().unwrap();
+    assert_eq!(
+        vec![&reg("con"), &reg("gre")],
+        destinations[&bul].iter().collect::<Vec<_>>()
+    );
+
+    let game = game
+        .advance(orders(retreat_ord, &["TUR: A bul -> gre"]))
+        .unwrap();
+
+    assert_eq!("F1901M", game.time().short_name());
+    assert!(has_unit(&game, "TUR: A gre"));
+    assert!(has_unit(&game, "AUS: A bul"));
+}
+
+#[test]
+fn resumes_retreat_phase_from_snapshot() {
+    let game = game_with_units(&["AUS: A ser", "AUS: A rum", "TUR: A bul"])
+        .advance(orders(
+            ord,
+            &["AUS: A ser -> bul", "AUS: A rum supports A ser -> bul"],
+        ))
+        .unwrap();
+
+    let resumed = Game::resume(
+        game.map(),
+        variant::standard().calendar(),
+        Rulebook::default(),
+        game.snapshot(),
+    )
+    .unwrap();
+    assert_eq!(game.retreat_destinations(), resumed.retreat_destinations());
+
+    let resumed = resumed
+        .advance(orders(retreat_ord, &["TUR: A bul -> con"]))
+        .unwrap();
+    assert!(has_unit(&resumed, "TUR: A con"));
+}
+
+#[test]
+fn disbands_units_that_cannot_retreat() {
+    let game = game_with_units(&[
+        "AUS: A ser",
+        "AUS: A rum",
+        "AUS: A gre",
+        "AUS: A con",
+        "TUR: A bul",
+    ]);
+
+    let game = game
+        .advance(orders(
+            ord,
+            &["AUS: A ser -> bul", "AUS: A rum supports A ser -> bul"],
+        ))
+        .unwrap();
+
+    assert_eq!("F1901M", game.time().short_name());
+    assert_eq!(4, game.units().len());
+    assert!(game.retreat_destinations().is_empty());
+}
+
+#[test]
+fn rejects_orders_for_wrong_phase() {
+    let game = Game::from_variant(variant::standard());
+
+    assert_eq!(
+        Error::WrongPhase {
+            expected: Phase::Main,
+            received: Phase::Build,
+        },
+        game.advance(Orders::Build(vec![])).unwrap_err()
+    );
+}
+
+#[test]
+fn chaos_starts_with_builds() {
+    let game = Game::from_variant(variant::chaos());
+    assert_eq!("W1900B", game.time().short_name());
+    assert!(game.needs_orders());
+
+    let game = game
+        .advance(orders(build_ord, &["PAR: A par build", "BRE: F bre build"]))
+        .unwrap();
+
+    assert_eq!("S1901M", game.time().short_name());
+    assert_eq!(2, game.units().len());
+    assert!(has_unit(&game, "BRE: F bre"));
+}
@@ -49,7 +49,7 @@ fn dipmath_figure9() {
         ),
     ]);
 
-    let result = orders.adjudicate(geo::standard_map(), Rulebook);
+    let result = orders.adjudicate(geo::standard_map(), Rulebook::default());
 
     for order in orders.submitted_orders() {
         assert_eq!(result.get(order).unwrap(), &OrderState::Succeeds);
@@ -99,7 +99,7 @@ fn dipmath_figure6() {
         .find_border_between(&reg("sil"), &reg("boh"))
         .is_some());
 
-    let result = orders.adjudicate(geo::standard_map(), Rulebook);
+    let result = orders.adjudicate(geo::standard_map(), Rulebook::default());
     for o in orders.submitted_orders() {
         assert_eq!(
             result.get(o).unwrap(),
@@ -153,7 +153,7 @@ fn dipmath_figure16() {
         ),
     ]);
 
-    let result = orders.adjudicate(geo::standard_map(), Rulebook);
+    let result = orders.adjudicate(geo::standard_map(), Rulebook::default());
     for o in orders.submitted_orders() {
         assert_eq!(
             result.get(o).unwrap(),
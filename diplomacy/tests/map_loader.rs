@@ -0,0 +1,103 @@
+//! Loading maps at runtime.
+
+#![cfg(test)]
+
+use diplomacy::geo::{standard_map, Coast, Map, MapError, ProvinceKey, RegionKey};
+use diplomacy::ShortName;
+
+/// The standard map, written as a single map document.
+fn standard_document() -> String {
+    format!(
+        "[provinces]\n{}\n[regions]\n{}\n[borders]\n{}",
+        include_str!("../src/geo/provinces.csv"),
+        include_str!("../src/geo/regions.csv"),
+        include_str!("../src/geo/borders.csv"),
+    )
+}
+
+#[test]
+fn load_standard_map_from_document() {
+    let map = Map::from_reader(standard_document().as_bytes()).unwrap();
+    assert_eq!(&map, standard_map());
+}
+
+#[test]
+fn error_reports_line_number() {
+    let doc = standard_document().replace("bul,e,sea", "bul,x,sea");
+    let line = doc.lines().position(|l| l == "bul,x,sea").unwrap() + 1;
+
+    match Map::from_reader(doc.as_bytes()) {
+        Err(MapError::Line(reported, e)) => {
+            assert_eq!(line, reported);
+            assert_eq!(MapError::Malformed("x".into()), *e);
+        }
+        other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn missing_borders_section() {
+    let doc = "[provinces]\nbre,Brest,FRA\n[regions]\nbre,,coast\n";
+    assert_eq!(
+        Err(MapError::MissingSection("borders".into())),
+        Map::from_reader(doc.as_bytes()).map(|_| ())
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn load_map_definition_from_json() {
+    use diplomacy::geo::{MapDefinition, RegionKey};
+    use std::convert::TryFrom;
+
+    let definition: MapDefinition = serde_json::from_str(
+        r#"{
+            "provinces": [
+                { "short_name": "bre", "full_name": "Brest", "supply_center_for": "FRA" },
+                { "short_name": "mao", "full_name": "Mid-Atlantic Ocean" }
+            ],
+            "regions": [
+                { "province": "bre", "terrain": "coast" },
+                { "province": "mao", "terrain": "sea" }
+            ],
+            "borders": [
+                { "from": "bre", "to": "mao", "terrain": "sea" }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    let map = Map::try_from(definition).unwrap();
+    assert_eq!(
+        vec![&RegionKey::new("mao", None)],
+        map.find_bordering(&RegionKey::new("bre", None))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn adjacency_by_region_and_province() {
+    let map = standard_map();
+    let stp = |coast| RegionKey::new("stp", coast);
+
+    let mut north_coast = map
+        .find_bordering(&stp(Some(Coast::North)))
+        .map(|r| r.short_name().into_owned())
+        .collect::<Vec<_>>();
+    north_coast.sort();
+    assert_eq!(vec!["bar", "nwy"], north_coast);
+
+    let province = map.borders_containing(&ProvinceKey::new("stp"));
+    let regions = map
+        .borders_containing(&stp(None))
+        .chain(map.borders_containing(&stp(Some(Coast::North))))
+        .chain(map.borders_containing(&stp(Some(Coast::South))));
+    assert_eq!(regions.count(), province.len());
+
+    assert_eq!(
+        1,
+        map.find_borders_between(&stp(Some(Coast::South)), &ProvinceKey::new("fin"))
+            .count()
+    );
+    assert_eq!(0, map.borders_containing(&ProvinceKey::new("xyz")).count());
+}
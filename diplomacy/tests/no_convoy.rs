@@ -0,0 +1,48 @@
+//! Move orders which explicitly forbid the use of a convoy.
+
+#![cfg(test)]
+
+#[path = "./util.rs"]
+mod util;
+
+use diplomacy::judge::OrderState::{Fails, Succeeds};
+use util::*;
+
+/// Without "no convoy" the English fleet would kidnap the Russian army and the two units would
+/// swap places, as in DATC 6.G.7. Forbidding the convoy makes this a head-to-head battle.
+#[test]
+fn forbidding_convoy_prevents_kidnapping() {
+    judge! {
+       "ENG: F ska convoys swe -> nwy",
+       "ENG: F nwy -> swe": Fails,
+       "RUS: A swe -> nwy no convoy": Fails,
+       "RUS: F bot convoys swe -> nwy",
+    };
+}
+
+#[test]
+fn forbidding_convoy_with_no_direct_border() {
+    judge! {
+       "ENG: A lon -> bel no convoy": Fails,
+       "ENG: F nth convoys lon -> bel",
+    };
+}
+
+#[test]
+fn forbidding_convoy_allows_direct_move() {
+    judge! {
+       "ENG: A yor -> lon no convoy": Succeeds,
+       "ENG: F nth convoys yor -> lon",
+    };
+}
+
+/// A convoy route exists for the English army, but it forbids the convoy, so the two armies
+/// meet head-to-head and bounce.
+#[test]
+fn forbidden_convoy_swap_is_head_to_head() {
+    judge! {
+       "ENG: A yor -> lon no convoy": Fails,
+       "ENG: F nth convoys yor -> lon",
+       "FRA: A lon -> yor no convoy": Fails,
+    };
+}
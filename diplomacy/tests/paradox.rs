@@ -0,0 +1,189 @@
+//! Convoy paradoxes from DATC section 6.F, adjudicated under each of the paradox rules
+//! described in DATC section 4.A.2.
+
+#![cfg(test)]
+
+#[path = "./util.rs"]
+mod util;
+
+use diplomacy::judge::OrderState::{Fails, Succeeds};
+use diplomacy::judge::{MappedMainOrder, OrderState, ParadoxRule, Rulebook};
+use std::collections::HashMap;
+use util::*;
+
+fn all_hold(orders: Vec<&str>) -> HashMap<MappedMainOrder, OrderState> {
+    get_results_with_rules(Rulebook::with_paradox_rule(ParadoxRule::AllHold), orders)
+}
+
+fn rulebook_1971(orders: Vec<&str>) -> HashMap<MappedMainOrder, OrderState> {
+    get_results_with_rules(
+        Rulebook::with_paradox_rule(ParadoxRule::Rulebook1971),
+        orders,
+    )
+}
+
+fn rulebook_1982(orders: Vec<&str>) -> HashMap<MappedMainOrder, OrderState> {
+    get_results_with_rules(
+        Rulebook::with_paradox_rule(ParadoxRule::Rulebook1982),
+        orders,
+    )
+}
+
+fn dptg(orders: Vec<&str>) -> HashMap<MappedMainOrder, OrderState> {
+    get_results_with_rules(Rulebook::with_paradox_rule(ParadoxRule::Dptg), orders)
+}
+
+/// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.F.14
+#[test]
+fn t6f14_simple_convoy_paradox_all_hold() {
+    judge! {
+        @using all_hold =>
+        "ENG: F lon Supports F wal -> eng": Fails,
+        "ENG: F wal -> eng": Fails,
+        "FRA: A bre -> lon": Fails,
+        "FRA: F eng convoys bre -> lon": Fails
+    };
+}
+
+/// Under the 1982 rule the support from London isn't cut, so the result matches Szykman.
+///
+/// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.F.14
+#[test]
+fn t6f14_simple_convoy_paradox_1982() {
+    judge! {
+        @using rulebook_1982 =>
+        "ENG: F lon Supports F wal -> eng": Succeeds,
+        "ENG: F wal -> eng": Succeeds,
+        "FRA: A bre -> lon": Fails,
+        "FRA: F eng convoys bre -> lon": Fails
+    };
+}
+
+/// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.F.16
+#[test]
+fn t6f16_pandins_paradox_1982() {
+    judge! {
+        @using rulebook_1982 =>
+        "ENG: F lon Supports F wal -> eng": Succeeds,
+        "ENG: F wal -> eng": Fails,
+        "FRA: A bre -> lon": Fails,
+        "FRA: F eng convoys bre -> lon": Succeeds,
+        "GER: F nth Supports F bel -> eng",
+        "GER: F bel -> eng": Fails
+    };
+}
+
+/// With the support from London intact, the convoy is never disrupted and the supported
+/// French army dislodges London.
+///
+/// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.F.17
+#[test]
+fn t6f17_pandins_extended_paradox_1971() {
+    judge! {
+        @using rulebook_1971 =>
+        "ENG: F lon Supports F wal -> eng": Succeeds,
+        "ENG: F wal -> eng": Fails,
+        "FRA: A bre -> lon": Succeeds,
+        "FRA: F eng convoys bre -> lon": Succeeds,
+        "FRA: F yor Supports A bre -> lon",
+        "GER: F nth Supports F bel -> eng",
+        "GER: F bel -> eng": Fails
+    };
+}
+
+/// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.F.17
+#[test]
+fn t6f17_pandins_extended_paradox_dptg() {
+    judge! {
+        @using dptg =>
+        "ENG: F lon Supports F wal -> eng": Succeeds,
+        "ENG: F wal -> eng": Fails,
+        "FRA: A bre -> lon": Succeeds,
+        "FRA: F eng convoys bre -> lon": Succeeds,
+        "FRA: F yor Supports A bre -> lon",
+        "GER: F nth Supports F bel -> eng",
+        "GER: F bel -> eng": Fails
+    };
+}
+
+/// The 1971 rule also covers supports to hold a convoying fleet, so the army is not
+/// betrayed by its own attack.
+///
+/// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.F.18
+#[test]
+fn t6f18_betrayal_paradox_1971() {
+    judge! {
+        @using rulebook_1971 =>
+        "ENG: F nth convoys lon -> bel": Succeeds,
+        "ENG: A lon -> bel": Succeeds,
+        "ENG: F eng Supports A lon -> bel",
+        "FRA: F bel Supports F nth": Succeeds,
+        "GER: F hel Supports F ska -> nth",
+        "GER: F ska -> nth": Fails
+    };
+}
+
+/// The 1982 rule only covers supports for attacks on a convoying fleet, so the paradox
+/// falls through to the Szykman rule.
+///
+/// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.F.18
+#[test]
+fn t6f18_betrayal_paradox_1982() {
+    judge! {
+        @using rulebook_1982 =>
+        "ENG: F nth convoys lon -> bel": Fails,
+        "ENG: A lon -> bel": Fails,
+        "ENG: F eng Supports A lon -> bel",
+        "FRA: F bel Supports F nth": Succeeds,
+        "GER: F hel Supports F ska -> nth",
+        "GER: F ska -> nth": Fails
+    };
+}
+
+/// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.F.19
+#[test]
+fn t6f19_multi_route_convoy_disruption_paradox_1982() {
+    judge! {
+        @using rulebook_1982 =>
+        "FRA: A tun -> nap": Fails,
+        "FRA: F tys convoys tun -> nap": Fails,
+        "FRA: F ion convoys tun -> nap": Succeeds,
+        "ITA: F nap Supports F rom -> tys": Succeeds,
+        "ITA: F rom -> tys": Succeeds
+    };
+}
+
+/// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.F.22
+#[test]
+fn t6f22_second_order_paradox_with_two_resolutions_all_hold() {
+    judge! {
+        @using all_hold =>
+        "ENG: F edi -> nth": Fails,
+        "ENG: F lon Supports F edi -> nth",
+        "FRA: A bre -> lon": Fails,
+        "FRA: F eng convoys bre -> lon": Fails,
+        "GER: F bel Supports F pic -> eng",
+        "GER: F pic -> eng": Fails,
+        "RUS: A nwy -> bel": Fails,
+        "RUS: F nth convoys nwy -> bel": Fails
+    };
+}
+
+/// A paradox that shielding supports can't settle falls back to the Szykman rule.
+///
+/// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.F.24
+#[test]
+fn t6f24_second_order_paradox_with_no_resolution_dptg() {
+    judge! {
+        @using dptg =>
+        "ENG: F edi -> nth": Succeeds,
+        "ENG: F lon Supports F edi -> nth",
+        "ENG: F iri -> eng": Fails,
+        "ENG: F mao Supports F iri -> eng",
+        "FRA: A bre -> lon": Fails,
+        "FRA: F eng convoys bre -> lon",
+        "FRA: F bel Supports F eng",
+        "RUS: A nwy -> bel": Fails,
+        "RUS: F nth convoys nwy -> bel": Fails
+    };
+}
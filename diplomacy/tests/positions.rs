@@ -0,0 +1,90 @@
+//! Unit positions between phases.
+
+#![cfg(test)]
+
+#[path = "./util.rs"]
+mod util;
+
+use diplomacy::geo::{self, RegionKey};
+use diplomacy::judge::{retreat, Rulebook, Submission};
+use diplomacy::{UnitPosition, UnitPositions};
+use util::*;
+
+fn unit(s: &str) -> UnitPosition<'static, RegionKey> {
+    s.parse().unwrap()
+}
+
+fn sorted_units(
+    positions: &impl UnitPositions<RegionKey>,
+) -> Vec<UnitPosition<'static, RegionKey>> {
+    let mut units = positions
+        .unit_positions()
+        .into_iter()
+        .map(UnitPosition::into_owned)
+        .collect::<Vec<_>>();
+    units.sort_by(|a, b| a.region.cmp(&b.region));
+    units
+}
+
+#[test]
+fn interim_positions_include_dislodged_units() {
+    let submission = Submission::with_inferred_state(vec![
+        ord("AUS: A ser -> bul"),
+        ord("AUS: A rum supports A ser -> bul"),
+        ord("TUR: A bul Hold"),
+        ord("TUR: A con -> ank"),
+    ]);
+    let outcome = submission.adjudicate(geo::standard_map(), Rulebook::default());
+    let positions = outcome.to_interim_positions();
+
+    assert_eq!(
+        vec![unit("TUR: A bul")],
+        positions
+            .dislodged()
+            .iter()
+            .cloned()
+            .map(UnitPosition::into_owned)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![
+            unit("TUR: A ank"),
+            unit("AUS: A bul"),
+            unit("TUR: A bul"),
+            unit("AUS: A rum"),
+        ],
+        sorted_units(&positions)
+    );
+
+    let occupier = positions.find_province_occupier(&prov("bul")).unwrap();
+    assert_eq!(unit("AUS: A bul"), occupier.into_owned());
+    assert!(positions.find_province_occupier(&prov("con")).is_none());
+    assert!(positions.find_province_occupier(&prov("ser")).is_none());
+}
+
+#[test]
+fn retreat_outcome_to_submission() {
+    let submission = Submission::with_inferred_state(vec![
+        ord("AUS: A ser -> bul"),
+        ord("AUS: A rum supports A ser -> bul"),
+        ord("TUR: A bul Hold"),
+    ]);
+    let outcome = submission.adjudicate(geo::standard_map(), Rulebook::default());
+    let start = outcome.to_retreat_start();
+    let retreat = retreat::Context::new(&start, vec![retreat_ord("TUR: A bul -> gre")]);
+
+    let next = retreat
+        .resolve()
+        .to_submission(vec![ord("TUR: A gre -> alb")]);
+
+    let mut generated = next.generated_orders().cloned().collect::<Vec<_>>();
+    generated.sort();
+    assert_eq!(
+        vec![ord("AUS: A bul Hold"), ord("AUS: A rum Hold")],
+        generated
+    );
+    assert_eq!(
+        vec![unit("AUS: A bul"), unit("TUR: A gre"), unit("AUS: A rum")],
+        sorted_units(&next)
+    );
+}
@@ -0,0 +1,78 @@
+//! SVG rendering of the board.
+
+#![cfg(all(test, feature = "render"))]
+
+#[path = "./util.rs"]
+mod util;
+
+use diplomacy::geo::{self, RegionKey};
+use diplomacy::judge::{retreat, Rulebook, Submission};
+use diplomacy::render::{standard_layout, Board, Layout};
+use diplomacy::{SupplyCenterOwnership, UnitPosition};
+use util::*;
+
+#[test]
+fn outcome_with_bounce_and_dislodgement() {
+    let units = [
+        "FRA: A bur",
+        "FRA: A ruh",
+        "GER: A tyr",
+        "AUS: A ser",
+        "AUS: A rum",
+        "TUR: A bul",
+    ]
+    .iter()
+    .map(|unit| unit.parse().unwrap())
+    .collect::<Vec<UnitPosition<'_, RegionKey>>>();
+
+    let orders = vec![
+        ord("FRA: A bur -> mun"),
+        ord("FRA: A ruh supports A bur -> mun"),
+        ord("GER: A tyr -> mun"),
+        ord("AUS: A ser -> bul"),
+        ord("AUS: A rum supports A ser -> bul"),
+        ord("TUR: A bul Hold"),
+    ];
+    let submission = Submission::with_inferred_state(orders);
+    let outcome = submission.adjudicate(geo::standard_map(), Rulebook::default());
+    let start = retreat::Start::new(&outcome);
+
+    let svg = Board::standard()
+        .with_units(&units)
+        .with_ownership(&SupplyCenterOwnership::initial(geo::standard_map()))
+        .with_outcome(&outcome)
+        .with_retreat_start(&start)
+        .to_string();
+
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert!(svg.contains("<title>Bounce in mun</title>"));
+    assert!(!svg.contains("<title>Bounce in bul</title>"));
+    assert!(svg.contains(r#"class="move fails""#));
+    assert!(svg.contains(r#"class="move succeeds""#));
+    assert!(svg.contains(r#"class="support succeeds""#));
+    assert!(svg.contains(r#"class="unit army dislodged"><title>TUR: A bul</title>"#));
+    assert!(svg.contains(&format!(
+        r#"class="province" cx="540" cy="450" r="16" fill="{}""#,
+        standard_layout().color(&"AUS".into()).unwrap()
+    )));
+}
+
+#[test]
+fn custom_layout_skips_unplaced_regions() {
+    let layout = Layout::from_reader(
+        "[size]\n100,100\n[nations]\nENG,blue\n[regions]\nlon,20,20\nnth,80,20\n".as_bytes(),
+    )
+    .unwrap();
+    let units = vec!["ENG: F lon".parse::<UnitPosition<'_, RegionKey>>().unwrap()];
+    let orders = vec![ord("ENG: F lon -> nth"), ord("ENG: F lon -> eng")];
+
+    let svg = Board::new(geo::standard_map(), &layout)
+        .with_units(&units)
+        .with_orders(&orders)
+        .to_string();
+
+    assert_eq!(1, svg.matches(r#"<line class="move""#).count());
+    assert_eq!(1, svg.matches(r#"class="unit fleet""#).count());
+    assert!(svg.contains(r#"fill="blue""#));
+}
@@ -0,0 +1,209 @@
+//! Owned reports of adjudication results.
+
+#![cfg(test)]
+
+#[path = "./util.rs"]
+mod util;
+
+use diplomacy::judge::report::{self, OrderReport, Prevent};
+use diplomacy::judge::retreat::{self, DestStatus};
+use diplomacy::judge::{build, OrderState, OwnedOutcome, Rulebook, Submission};
+use diplomacy::{geo, ShortName};
+use util::*;
+
+fn submit(orders: &[&str]) -> Submission {
+    Submission::with_inferred_state(orders.iter().map(|o| ord(o)).collect())
+}
+
+#[test]
+fn main_phase_reports_failure_reasons() {
+    let submission = submit(&["FRA: A par -> bur", "GER: A mun -> bur"]);
+    let outcome = submission.adjudicate(geo::standard_map(), Rulebook::default());
+    let report = outcome.to_report();
+
+    assert_eq!(
+        vec![
+            OrderReport {
+                order: ord("FRA: A par -> bur"),
+                outcome: report::OrderOutcome::Move(report::AttackOutcome::Prevented(
+                    Prevent::Prevents {
+                        order: ord("GER: A mun -> bur"),
+                        supporters: vec![],
+                    }
+                )),
+            },
+            OrderReport {
+                order: ord("GER: A mun -> bur"),
+                outcome: report::OrderOutcome::Move(report::AttackOutcome::Prevented(
+                    Prevent::Prevents {
+                        order: ord("FRA: A par -> bur"),
+                        supporters: vec![],
+                    }
+                )),
+            },
+        ],
+        report.orders
+    );
+}
+
+#[test]
+fn retreat_reports() {
+    let submission = submit(&[
+        "AUS: A ser -> bul",
+        "AUS: A rum supports A ser -> bul",
+        "TUR: A bul Hold",
+    ]);
+    let outcome = submission.adjudicate(geo::standard_map(), Rulebook::default());
+    let start = outcome.to_retreat_start();
+    let start_report = start.to_report();
+
+    assert_eq!(1, start_report.dislodged.len());
+    let dislodgement = &start_report.dislodged[0];
+    assert_eq!(ord("TUR: A bul Hold"), dislodgement.order);
+    assert_eq!(ord("AUS: A ser -> bul"), dislodgement.dislodger);
+    assert!(dislodgement
+        .destinations
+        .contains(&(reg("ser"), DestStatus::BlockedByDislodger)));
+    assert!(dislodgement
+        .destinations
+        .contains(&(reg("gre"), DestStatus::Available)));
+
+    let retreat_report = retreat::Context::new(&start, vec![retreat_ord("TUR: A bul -> gre")])
+        .resolve()
+        .to_report();
+
+    assert_eq!(
+        vec![OrderReport {
+            order: retreat_ord("TUR: A bul -> gre"),
+            outcome: report::RetreatOutcome::Moves,
+        }],
+        retreat_report.orders
+    );
+    assert_eq!(
+        vec!["bul", "gre", "rum"],
+        retreat_report
+            .units
+            .iter()
+            .map(|unit| unit.region.short_name().into_owned())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn build_report() {
+    let map = geo::standard_map();
+    let ownerships = build::to_initial_ownerships(map);
+    let world = BuildWorld::new(vec!["RUS: A mos", "RUS: F sev"]);
+    let orders = [build_ord("RUS: A war build")];
+    let context = build::ResolverContext::new(map, &ownerships, &world, orders.iter().collect());
+    let report = context.resolve().to_report();
+
+    assert_eq!(
+        vec![OrderReport {
+            order: build_ord("RUS: A war build"),
+            outcome: build::OrderOutcome::Succeeds,
+        }],
+        report.orders
+    );
+    assert_eq!(3, report.units.len());
+    assert!(report.civil_disorder.is_empty());
+}
+
+/// Adjudicate a main phase without keeping the submission alive.
+fn adjudicate_owned(orders: &[&str]) -> OwnedOutcome {
+    submit(orders)
+        .adjudicate(geo::standard_map(), Rulebook::default())
+        .into_owned()
+}
+
+#[test]
+fn owned_outcome_outlives_submission() {
+    let outcome = adjudicate_owned(&[
+        "AUS: A ser -> bul",
+        "AUS: A rum supports A ser -> bul",
+        "TUR: A bul Hold",
+    ]);
+
+    assert_eq!(3, outcome.orders().count());
+    assert_eq!(
+        Some(&report::OrderOutcome::Hold(report::HoldOutcome::Dislodged(
+            ord("AUS: A ser -> bul")
+        ))),
+        outcome.get(&ord("TUR: A bul Hold"))
+    );
+
+    let start = outcome.to_retreat_start();
+    assert_eq!(
+        Some(&ord("AUS: A ser -> bul")),
+        start.dislodged().get(&ord("TUR: A bul Hold")).copied()
+    );
+
+    let context = retreat::Context::new(&start, vec![retreat_ord("TUR: A bul -> gre")]);
+    assert_eq!(
+        Some(&retreat::OrderOutcome::Moves),
+        context.resolve().get(&retreat_ord("TUR: A bul -> gre"))
+    );
+
+    let states: std::collections::HashMap<_, _> = outcome.into();
+    assert_eq!(OrderState::Fails, states[&ord("TUR: A bul Hold")]);
+    assert_eq!(OrderState::Succeeds, states[&ord("AUS: A ser -> bul")]);
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use super::util::*;
+    use diplomacy::game::Snapshot;
+    use diplomacy::geo::{self, variant, Map};
+    use diplomacy::judge::{report::MainPhaseReport, Rulebook};
+    use diplomacy::Game;
+
+    #[test]
+    fn main_phase_report_round_trip() {
+        let submission = super::submit(&[
+            "FRA: A par -> bur",
+            "GER: A mun -> bur",
+            "GER: A ruh supports A mun -> bur",
+            "ENG: F nth convoys yor -> nwy",
+            "ENG: A yor -> nwy",
+        ]);
+        let report = submission
+            .adjudicate(geo::standard_map(), Rulebook::default())
+            .to_report();
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(
+            report,
+            serde_json::from_str::<MainPhaseReport>(&json).unwrap()
+        );
+    }
+
+    #[test]
+    fn map_round_trip() {
+        let json = serde_json::to_string(geo::standard_map()).unwrap();
+        assert_eq!(
+            *geo::standard_map(),
+            serde_json::from_str::<Map>(&json).unwrap()
+        );
+    }
+
+    #[test]
+    fn game_snapshot_round_trip() {
+        let game = Game::from_variant(variant::standard())
+            .advance(vec![ord("TUR: A con -> bul")])
+            .unwrap();
+
+        let json = serde_json::to_string(&game.snapshot()).unwrap();
+        let snapshot = serde_json::from_str::<Snapshot>(&json).unwrap();
+        assert_eq!(game.snapshot(), snapshot);
+
+        let resumed = Game::resume(
+            geo::standard_map(),
+            variant::standard().calendar(),
+            Rulebook::default(),
+            snapshot,
+        )
+        .unwrap();
+        assert_eq!(game.time(), resumed.time());
+        assert_eq!(game.units(), resumed.units());
+    }
+}
@@ -0,0 +1,142 @@
+//! DATC cases whose outcome depends on the options chosen in the `Rulebook`.
+
+#![cfg(test)]
+
+#[path = "./util.rs"]
+mod util;
+
+use diplomacy::judge::OrderState::{Fails, Succeeds};
+use diplomacy::judge::{
+    ConvoyToAdjacent, InvalidOrder, MappedMainOrder, MultipleOrders, OrderOutcome, OrderState,
+    Rulebook,
+};
+use std::collections::HashMap;
+use util::*;
+
+fn explicit_convoys(orders: Vec<&str>) -> HashMap<MappedMainOrder, OrderState> {
+    let rules = Rulebook {
+        convoy_to_adjacent: ConvoyToAdjacent::Explicit,
+        ..Rulebook::default()
+    };
+
+    get_results_with_rules(rules, orders)
+}
+
+fn convoy_intent(orders: Vec<&str>) -> HashMap<MappedMainOrder, OrderState> {
+    let rules = Rulebook {
+        convoy_to_adjacent: ConvoyToAdjacent::Intent,
+        ..Rulebook::default()
+    };
+
+    get_results_with_rules(rules, orders)
+}
+
+/// Without an explicit "via convoy", the English fleet can't kidnap the Russian army, so the
+/// two units meet head-to-head.
+///
+/// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.G.7
+#[test]
+fn t6g07_swapping_with_illegal_intent_explicit() {
+    judge! {
+        @using explicit_convoys =>
+        "ENG: F ska convoys swe -> nwy",
+        "ENG: F nwy -> swe": Fails,
+        "RUS: A swe -> nwy": Fails,
+        "RUS: F bot convoys swe -> nwy"
+    };
+}
+
+/// Russia ordered its own fleet to convoy the army, so the army goes by convoy and can't cut
+/// the support from Norway. This is the DATC preference.
+///
+/// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.G.11
+#[test]
+fn t6g11_a_convoy_to_an_adjacent_place_with_a_paradox_intent() {
+    judge! {
+        @using convoy_intent =>
+        "ENG: F nwy Supports F nth -> ska",
+        "ENG: F nth -> ska": Succeeds,
+        "RUS: A swe -> nwy": Fails,
+        "RUS: F ska convoys swe -> nwy",
+        "RUS: F bar Supports A swe -> nwy"
+    };
+}
+
+/// http://web.inter.nl.net/users/L.B.Kruijswijk/#6.H.11
+#[test]
+fn t6h11_retreat_when_dislodged_by_adjacent_convoy_blocked() {
+    use diplomacy::judge::retreat::{DestStatus, OrderOutcome::*};
+
+    let rules = Rulebook {
+        retreat_to_convoy_origin: false,
+        ..Rulebook::default()
+    };
+
+    let (submission, expected) = submit_main_phase! {
+       "FRA: A gas -> mar via Convoy": Succeeds,
+       "FRA: A bur Supports A gas -> mar",
+       "FRA: F mao convoys gas -> mar",
+       "FRA: F wes convoys gas -> mar",
+       "FRA: F lyo convoys gas -> mar",
+       "ITA: A mar Hold": Fails,
+    };
+
+    let outcome = resolve_main!(submission, expected, rules);
+
+    judge_retreat! {
+        outcome,
+        "ITA: A mar -> gas": InvalidDestination(DestStatus::BlockedByDislodger)
+    };
+}
+
+#[test]
+fn multiple_orders_last_is_followed() {
+    let rules = Rulebook {
+        multiple_orders: MultipleOrders::Last,
+        ..Rulebook::default()
+    };
+
+    let (submission, expected) = submit_main_phase! {
+        "ENG: F lon -> nth",
+        "ENG: F lon -> eng": Succeeds,
+    };
+
+    let outcome = resolve_main!(submission, expected, rules);
+    assert_eq!(
+        Some(&OrderOutcome::Invalid(InvalidOrder::MultipleToSameUnit)),
+        outcome.get(&ord("ENG: F lon -> nth"))
+    );
+}
+
+#[test]
+fn multiple_orders_hold() {
+    let rules = Rulebook {
+        multiple_orders: MultipleOrders::Hold,
+        ..Rulebook::default()
+    };
+
+    let results = get_results_with_rules(rules, vec!["ENG: F lon -> nth", "ENG: F lon -> eng"]);
+
+    assert_eq!(Some(&Fails), results.get(&ord("ENG: F lon -> nth")));
+    assert_eq!(Some(&Fails), results.get(&ord("ENG: F lon -> eng")));
+    assert_eq!(Some(&Succeeds), results.get(&ord("ENG: F lon Hold")));
+}
+
+/// webDiplomacy doesn't allow convoys to adjacent provinces unless they're explicit, and
+/// doesn't let impossible orders through.
+#[test]
+fn web_diplomacy_preset() {
+    let results = get_results_with_rules(
+        Rulebook::web_diplomacy(),
+        vec![
+            "ENG: F ska convoys swe -> nwy",
+            "ENG: F nwy -> swe",
+            "RUS: A swe -> nwy",
+            "RUS: F bot convoys swe -> nwy",
+        ],
+    );
+
+    assert_eq!(Some(&Fails), results.get(&ord("ENG: F nwy -> swe")));
+    assert_eq!(Some(&Fails), results.get(&ord("RUS: A swe -> nwy")));
+    assert_eq!(Some(&Succeeds), results.get(&ord("RUS: F bot Hold")));
+}
@@ -0,0 +1,52 @@
+//! Borders of the standard map.
+
+#![cfg(test)]
+
+#[path = "./util.rs"]
+mod util;
+
+use diplomacy::geo::{self, RegionKey};
+use diplomacy::judge::OrderState::Succeeds;
+use util::*;
+
+#[test]
+fn standard_map_has_every_border() {
+    let borders = [
+        ("alb", "ser"),
+        ("bot", "fin"),
+        ("bot", "lvn"),
+        ("bot", "stp(sc)"),
+        ("bot", "swe"),
+        ("gal", "ukr"),
+        ("lvn", "war"),
+        ("mao", "spa(sc)"),
+        ("nwy", "stp(nc)"),
+        ("nwy", "stp"),
+        ("pie", "tus"),
+        ("pie", "tyr"),
+        ("pie", "ven"),
+        ("tus", "tys"),
+        ("ukr", "war"),
+    ];
+
+    for (r1, r2) in borders.iter() {
+        let r1 = r1.parse::<RegionKey>().unwrap();
+        let r2 = r2.parse::<RegionKey>().unwrap();
+        assert!(
+            geo::standard_map().find_border_between(&r1, &r2).is_some(),
+            "{} should border {}",
+            r1,
+            r2
+        );
+    }
+}
+
+#[test]
+fn moves_across_restored_borders() {
+    judge! {
+        "AUS: A ser -> alb": Succeeds,
+        "RUS: A ukr -> war": Succeeds,
+        "ITA: F tus -> tys": Succeeds,
+        "GER: A tyr -> pie": Succeeds,
+    };
+}
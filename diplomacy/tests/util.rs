@@ -3,8 +3,12 @@
 
 use diplomacy::geo;
 use diplomacy::geo::{Coast, ProvinceKey, RegionKey};
-use diplomacy::judge::{MappedMainOrder, MappedRetreatOrder, OrderState, Rulebook};
-use std::collections::HashMap;
+use diplomacy::judge::build::{self, WorldState};
+use diplomacy::judge::{
+    MappedBuildOrder, MappedMainOrder, MappedRetreatOrder, OrderState, Rulebook,
+};
+use diplomacy::{Nation, UnitType};
+use std::collections::{HashMap, HashSet};
 
 pub fn prov(s: &str) -> ProvinceKey {
     ProvinceKey::new(s)
@@ -37,11 +41,15 @@ macro_rules! submit_main_phase {
 
 #[macro_export]
 macro_rules! resolve_main {
-    ($context:expr, $expectation:expr) => {{
-        let outcome = $context.adjudicate(
-            ::diplomacy::geo::standard_map(),
-            ::diplomacy::judge::Rulebook,
-        );
+    ($context:expr, $expectation:expr) => {
+        resolve_main!(
+            $context,
+            $expectation,
+            ::diplomacy::judge::Rulebook::default()
+        )
+    };
+    ($context:expr, $expectation:expr, $rules:expr) => {{
+        let outcome = $context.adjudicate(::diplomacy::geo::standard_map(), $rules);
 
         // We refer back to the submitted orders to ensure we visit orders in the same
         // order across test runs. This makes output diffing easier.
@@ -128,22 +136,118 @@ macro_rules! judge_retreat {
 
 pub fn ord(s: &str) -> MappedMainOrder {
     s.parse()
-        .unwrap_or_else(|_| panic!(format!("'{}' should be a valid order", s)))
+        .unwrap_or_else(|_| panic!("'{}' should be a valid order", s))
 }
 
 pub fn retreat_ord(s: &str) -> MappedRetreatOrder {
     s.parse()
-        .unwrap_or_else(|_| panic!(format!("'{}' should be a valid order", s)))
+        .unwrap_or_else(|_| panic!("'{}' should be a valid order", s))
 }
 
 pub fn get_results(orders: Vec<&str>) -> HashMap<MappedMainOrder, OrderState> {
+    get_results_with_rules(Rulebook::default(), orders)
+}
+
+/// Adjudicate a set of orders using a specific rulebook, reporting the success or failure of each.
+pub fn get_results_with_rules(
+    rules: Rulebook,
+    orders: Vec<&str>,
+) -> HashMap<MappedMainOrder, OrderState> {
     let parsed = orders.into_iter().map(ord).collect::<Vec<_>>();
     let ctx = diplomacy::judge::Submission::with_inferred_state(parsed);
 
-    let out = ctx.adjudicate(geo::standard_map(), Rulebook);
+    let out = ctx.adjudicate(geo::standard_map(), rules);
     for o in ctx.submitted_orders() {
-        println!("{:?}: {:?}", o, out.get(&o).unwrap());
+        println!("{:?}: {:?}", o, out.get(o).unwrap());
     }
 
     out.into()
 }
+
+pub fn build_ord(s: &str) -> MappedBuildOrder {
+    s.parse()
+        .unwrap_or_else(|_| panic!("'{}' should be a valid order", s))
+}
+
+/// Unit positions at the start of a build phase, written as `"RUS: A lvn"`.
+pub struct BuildWorld(HashMap<Nation, HashSet<(UnitType, RegionKey)>>);
+
+impl BuildWorld {
+    pub fn new(units: Vec<&str>) -> Self {
+        let mut positions: HashMap<_, HashSet<_>> = HashMap::new();
+        for unit in units {
+            let order = ord(&format!("{} Hold", unit));
+            positions
+                .entry(order.nation)
+                .or_default()
+                .insert((order.unit_type, order.region));
+        }
+
+        Self(positions)
+    }
+}
+
+impl WorldState for BuildWorld {
+    fn nations(&self) -> HashSet<&Nation> {
+        self.0.keys().collect()
+    }
+
+    fn occupier(&self, province: &ProvinceKey) -> Option<&Nation> {
+        self.0.iter().find_map(|(nation, units)| {
+            if units.iter().any(|(_, region)| province == region) {
+                Some(nation)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn unit_count(&self, nation: &Nation) -> u8 {
+        self.0
+            .get(nation)
+            .map(|units| units.len() as u8)
+            .unwrap_or(0)
+    }
+
+    fn units(&self, nation: &Nation) -> HashSet<(UnitType, RegionKey)> {
+        self.0.get(nation).cloned().unwrap_or_default()
+    }
+}
+
+/// Adjudicate a build phase.
+///
+/// `ownerships` lists the supply centers owned at the end of the previous year as
+/// `(province, nation)` pairs; unlisted supply centers are unowned unless occupied.
+pub fn get_build_results(
+    ownerships: Vec<(&str, &str)>,
+    units: Vec<&str>,
+    orders: Vec<&str>,
+) -> build::OwnedOutcome {
+    let last_time = ownerships
+        .into_iter()
+        .map(|(province, nation)| (prov(province), Nation::from(nation)))
+        .collect::<HashMap<_, _>>();
+    let world = BuildWorld::new(units);
+    let orders = orders.into_iter().map(build_ord).collect::<Vec<_>>();
+    let context = build::ResolverContext::new(
+        geo::standard_map(),
+        &last_time,
+        &world,
+        orders.iter().collect(),
+    );
+    let outcome = context.resolve();
+
+    for (order, result) in &outcome.outcomes {
+        println!("{}: {:?}", order, result);
+    }
+
+    outcome.into_owned()
+}
+
+/// Find the units removed in civil disorder when no build-phase orders are issued.
+pub fn get_civil_disorder(
+    ownerships: Vec<(&str, &str)>,
+    units: Vec<&str>,
+) -> HashSet<(UnitType, RegionKey)> {
+    get_build_results(ownerships, units, vec![]).civil_disorder
+}